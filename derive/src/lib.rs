@@ -0,0 +1,459 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Derive macros for [tokio-bitstream-io](https://docs.rs/tokio-bitstream-io/)
+//!
+//! `#[derive(BitDecode)]` generates an inherent
+//! `async fn read(r: &mut R) -> std::io::Result<Self>`
+//! for any `R: BitRead`, and `#[derive(BitEncode)]` generates an inherent
+//! `async fn write(&self, w: &mut W) -> std::io::Result<()>`
+//! for any `W: BitWrite`, so the same struct can be used with
+//! `BitReader`, `BitWriter`, `BitCounter` and `BitRecorder`.
+//! They also implement `FromBitStream` and `ToBitStream`
+//! (with `std::io::Error` as the error type) in terms of those functions,
+//! so derived structs may be parsed or built as parts of
+//! larger tuples, arrays and `Vec`s.
+//!
+//! Fields are read and written in declaration order,
+//! and how each field is handled is chosen by its attribute:
+//!
+//! | attribute      | field type          | read with                | written with            |
+//! |----------------|---------------------|--------------------------|-------------------------|
+//! | `#[bits(n)]`   | any `Numeric`       | `read(n)`                | `write(n, value)`       |
+//! | `#[signed(n)]` | any `SignedNumeric` | `read_signed(n)`         | `write_signed(n, value)`|
+//! | `#[unary0]`    | `u32`               | `read_unary0()`          | `write_unary0(value)`   |
+//! | `#[unary1]`    | `u32`               | `read_unary1()`          | `write_unary1(value)`   |
+//! | `#[bytes(n)]`  | `[u8; n]`           | `read_to_bytes()`        | `write_bytes(value)`    |
+//! | `#[bytes(n)]`  | `Vec<u8>`           | `read_to_vec(n)`         | `write_bytes(value)`    |
+//! | (none)         | `bool`              | `read_bit()`             | `write_bit(value)`      |
+//! | (none)         | any other type      | `parse::<T>()`           | `build(value)`          |
+//!
+//! Fields without an attribute which are not `bool` must implement
+//! `FromBitStream` and `ToBitStream` with errors convertible
+//! to `std::io::Error`, such as other derived structs,
+//! full-width integers, or tuples and arrays of those.
+//!
+//! In addition, `#[align]` may be combined with any of the above
+//! to skip to the next whole byte before the field is read
+//! and pad to the next whole byte before it is written.
+//!
+//! The bit counts given to `bits`, `signed` and `bytes`
+//! may be any constant expression.
+//! Writing a `Vec<u8>` field whose length differs from its `bytes` count
+//! returns an error of kind `InvalidInput`, since it couldn't be read back.
+//!
+//! Generic structs are supported. Each field's type is bound by
+//! the trait its attribute needs, such as `Numeric` for `#[bits(n)]`
+//! or `FromBitStream` and `ToBitStream` for fields without one.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::io::Cursor;
+//! use tokio_bitstream_io::{BigEndian, BitReader, BitWriter, BitDecode, BitEncode};
+//!
+//! #[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+//! struct BlockHeader {
+//!     last_block: bool,
+//!     #[bits(7)]
+//!     block_type: u8,
+//!     #[bits(24)]
+//!     block_size: u32,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+//! struct Block {
+//!     header: BlockHeader,
+//!     #[signed(5)]
+//!     offset: i8,
+//!     #[unary0]
+//!     count: u32,
+//!     #[align]
+//!     #[bytes(2)]
+//!     tag: [u8; 2],
+//! }
+//!
+//! # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+//! let block = Block {
+//!     header: BlockHeader { last_block: true, block_type: 4, block_size: 2 },
+//!     offset: -3,
+//!     count: 2,
+//!     tag: *b"hi",
+//! };
+//!
+//! let mut data = Vec::new();
+//! let mut writer = BitWriter::endian(&mut data, BigEndian);
+//! block.write(&mut writer).await.unwrap();
+//! assert_eq!(data, [0x84, 0x00, 0x00, 0x02, 0b11101_110, b'h', b'i']);
+//!
+//! let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+//! assert_eq!(Block::read(&mut reader).await.unwrap(), block);
+//! # });
+//! ```
+
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote_spanned, Data, DeriveInput, Expr, Field, Fields, Generics,
+    Index, Member, Type, WherePredicate,
+};
+
+/// Generates an `async fn read` which reads the struct
+/// from any `BitRead` implementation, field by field.
+///
+/// See the crate documentation for the supported field attributes.
+#[proc_macro_derive(BitDecode, attributes(bits, signed, unary0, unary1, bytes, align))]
+pub fn derive_bit_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates an `async fn write` which writes the struct
+/// to any `BitWrite` implementation, field by field.
+///
+/// See the crate documentation for the supported field attributes.
+#[proc_macro_derive(BitEncode, attributes(bits, signed, unary0, unary1, bytes, align))]
+pub fn derive_bit_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How a single field is read and written
+enum Encoding {
+    Bits(Expr),
+    Signed(Expr),
+    Unary0,
+    Unary1,
+    Bytes(Expr),
+    Bit,
+    Nested,
+}
+
+struct FieldSpec<'a> {
+    field: &'a Field,
+    encoding: Encoding,
+    align: bool,
+}
+
+impl<'a> FieldSpec<'a> {
+    fn parse(field: &'a Field) -> syn::Result<Self> {
+        let mut encoding = None;
+        let mut align = false;
+
+        for attr in field.attrs.iter() {
+            let path = attr.path();
+            let parsed = if path.is_ident("bits") {
+                Encoding::Bits(attr.parse_args()?)
+            } else if path.is_ident("signed") {
+                Encoding::Signed(attr.parse_args()?)
+            } else if path.is_ident("bytes") {
+                Encoding::Bytes(attr.parse_args()?)
+            } else if path.is_ident("unary0") {
+                attr.meta.require_path_only()?;
+                Encoding::Unary0
+            } else if path.is_ident("unary1") {
+                attr.meta.require_path_only()?;
+                Encoding::Unary1
+            } else if path.is_ident("align") {
+                attr.meta.require_path_only()?;
+                align = true;
+                continue;
+            } else {
+                continue;
+            };
+
+            if encoding.replace(parsed).is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "only one of bits, signed, unary0, unary1 or bytes may be given per field",
+                ));
+            }
+        }
+
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None if is_bool(&field.ty) => Encoding::Bit,
+            None => Encoding::Nested,
+        };
+
+        Ok(Self {
+            field,
+            encoding,
+            align,
+        })
+    }
+
+    fn read(&self, reader: &TokenStream2) -> TokenStream2 {
+        let ty = &self.field.ty;
+        let span = self.field.span();
+        let read = match &self.encoding {
+            Encoding::Bits(bits) => {
+                quote_spanned! {span=> #reader.read::<#ty>(#bits).await? }
+            }
+            Encoding::Signed(bits) => {
+                quote_spanned! {span=> #reader.read_signed::<#ty>(#bits).await? }
+            }
+            Encoding::Unary0 => quote_spanned! {span=> #reader.read_unary0().await? },
+            Encoding::Unary1 => quote_spanned! {span=> #reader.read_unary1().await? },
+            Encoding::Bytes(bytes) if is_array(ty) => {
+                quote_spanned! {span=> #reader.read_to_bytes::<{ #bytes }>().await? }
+            }
+            Encoding::Bytes(bytes) => {
+                quote_spanned! {span=> #reader.read_to_vec(#bytes).await? }
+            }
+            Encoding::Bit => quote_spanned! {span=> #reader.read_bit().await? },
+            Encoding::Nested => quote_spanned! {span=> #reader.parse::<#ty>().await? },
+        };
+        if self.align {
+            quote! {{
+                #reader.byte_align();
+                #read
+            }}
+        } else {
+            read
+        }
+    }
+
+    fn write(&self, writer: &TokenStream2, value: &TokenStream2) -> TokenStream2 {
+        let span = self.field.span();
+        let write = match &self.encoding {
+            Encoding::Bits(bits) => {
+                quote_spanned! {span=> #writer.write(#bits, *#value).await?; }
+            }
+            Encoding::Signed(bits) => {
+                quote_spanned! {span=> #writer.write_signed(#bits, *#value).await?; }
+            }
+            Encoding::Unary0 => quote_spanned! {span=> #writer.write_unary0(*#value).await?; },
+            Encoding::Unary1 => quote_spanned! {span=> #writer.write_unary1(*#value).await?; },
+            Encoding::Bytes(_) if is_array(&self.field.ty) => {
+                quote_spanned! {span=> #writer.write_bytes(&#value[..]).await?; }
+            }
+            Encoding::Bytes(bytes) => {
+                quote_spanned! {span=>
+                    if #value.len() != (#bytes) {
+                        return ::std::result::Result::Err(::std::io::Error::new(
+                            ::std::io::ErrorKind::InvalidInput,
+                            "byte field length differs from its bytes attribute",
+                        ));
+                    }
+                    #writer.write_bytes(&#value[..]).await?;
+                }
+            }
+            Encoding::Bit => quote_spanned! {span=> #writer.write_bit(*#value).await?; },
+            Encoding::Nested => quote_spanned! {span=> #writer.build(#value).await?; },
+        };
+        if self.align {
+            quote! {
+                #writer.byte_align().await?;
+                #write
+            }
+        } else {
+            write
+        }
+    }
+
+    /// The bounds the field's type needs in order to be read,
+    /// which a generic struct adds to its where-clause
+    fn read_bounds(&self) -> Vec<WherePredicate> {
+        let ty = &self.field.ty;
+        let span = ty.span();
+        match &self.encoding {
+            Encoding::Nested => vec![
+                parse_quote_spanned! {span=> #ty: ::tokio_bitstream_io::FromBitStream },
+                parse_quote_spanned! {span=>
+                    ::std::io::Error: ::std::convert::From<
+                        <#ty as ::tokio_bitstream_io::FromBitStream>::Error
+                    >
+                },
+            ],
+            _ => self.numeric_bounds(),
+        }
+    }
+
+    /// The bounds the field's type needs in order to be written,
+    /// which a generic struct adds to its where-clause
+    fn write_bounds(&self) -> Vec<WherePredicate> {
+        let ty = &self.field.ty;
+        let span = ty.span();
+        match &self.encoding {
+            Encoding::Nested => vec![
+                parse_quote_spanned! {span=> #ty: ::tokio_bitstream_io::ToBitStream },
+                parse_quote_spanned! {span=>
+                    ::std::io::Error: ::std::convert::From<
+                        <#ty as ::tokio_bitstream_io::ToBitStream>::Error
+                    >
+                },
+            ],
+            _ => self.numeric_bounds(),
+        }
+    }
+
+    fn numeric_bounds(&self) -> Vec<WherePredicate> {
+        let ty = &self.field.ty;
+        let span = ty.span();
+        match &self.encoding {
+            Encoding::Bits(_) => {
+                vec![parse_quote_spanned! {span=> #ty: ::tokio_bitstream_io::Numeric }]
+            }
+            Encoding::Signed(_) => {
+                vec![parse_quote_spanned! {span=> #ty: ::tokio_bitstream_io::SignedNumeric }]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Returns the struct's generics, with the given field bounds
+/// added to the where-clause if it has any type parameters
+fn bounded_generics(generics: &Generics, bounds: Vec<WherePredicate>) -> Generics {
+    let mut generics = generics.clone();
+    if generics.type_params().next().is_some() {
+        generics.make_where_clause().predicates.extend(bounds);
+    }
+    generics
+}
+
+fn is_bool(ty: &Type) -> bool {
+    matches!(ty, Type::Path(p) if p.qself.is_none() && p.path.is_ident("bool"))
+}
+
+fn is_array(ty: &Type) -> bool {
+    matches!(ty, Type::Array(_))
+}
+
+fn struct_fields(input: &DeriveInput) -> syn::Result<&Fields> {
+    match &input.data {
+        Data::Struct(data) => Ok(&data.fields),
+        _ => Err(syn::Error::new(
+            input.ident.span(),
+            "BitDecode and BitEncode can only be derived for structs",
+        )),
+    }
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = struct_fields(input)?;
+    let reader = quote! { r };
+
+    let specs = fields
+        .iter()
+        .map(FieldSpec::parse)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let generics = bounded_generics(
+        &input.generics,
+        specs.iter().flat_map(FieldSpec::read_bounds).collect(),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let reads = specs.iter().map(|spec| spec.read(&reader));
+    let construct = match fields {
+        Fields::Named(_) => {
+            let names = specs.iter().map(|spec| &spec.field.ident);
+            quote! { Self { #(#names: #reads,)* } }
+        }
+        Fields::Unnamed(_) => quote! { Self(#(#reads,)*) },
+        Fields::Unit => quote! { Self },
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Reads this value from the given bitstream reader
+            ///
+            /// # Errors
+            ///
+            /// Passes along any I/O error from the underlying stream.
+            #[allow(unused_imports)]
+            pub async fn read<R>(#reader: &mut R) -> ::std::io::Result<Self>
+            where
+                R: ::tokio_bitstream_io::BitRead + ?::std::marker::Sized,
+            {
+                use ::tokio_bitstream_io::BitRead as _;
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+
+        impl #impl_generics ::tokio_bitstream_io::FromBitStream for #name #ty_generics #where_clause {
+            type Error = ::std::io::Error;
+
+            #[inline]
+            async fn from_reader<R>(r: &mut R) -> ::std::io::Result<Self>
+            where
+                R: ::tokio_bitstream_io::BitRead + ?::std::marker::Sized,
+            {
+                Self::read(r).await
+            }
+        }
+    })
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = struct_fields(input)?;
+    let writer = quote! { w };
+
+    let specs = fields
+        .iter()
+        .map(FieldSpec::parse)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let generics = bounded_generics(
+        &input.generics,
+        specs.iter().flat_map(FieldSpec::write_bounds).collect(),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let writes = specs.iter().enumerate().map(|(index, spec)| {
+        let member = match &spec.field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        let value = quote! { self.#member };
+        let value = quote! { (&#value) };
+        spec.write(&writer, &value)
+    });
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Writes this value to the given bitstream writer
+            ///
+            /// # Errors
+            ///
+            /// Passes along any I/O error from the underlying stream.
+            #[allow(unused_imports, unused_variables)]
+            pub async fn write<W>(&self, #writer: &mut W) -> ::std::io::Result<()>
+            where
+                W: ::tokio_bitstream_io::BitWrite + ?::std::marker::Sized,
+            {
+                use ::tokio_bitstream_io::BitWrite as _;
+                #(#writes)*
+                ::std::result::Result::Ok(())
+            }
+        }
+
+        impl #impl_generics ::tokio_bitstream_io::ToBitStream for #name #ty_generics #where_clause {
+            type Error = ::std::io::Error;
+
+            #[inline]
+            async fn to_writer<W>(&self, w: &mut W) -> ::std::io::Result<()>
+            where
+                W: ::tokio_bitstream_io::BitWrite + ?::std::marker::Sized,
+            {
+                self.write(w).await
+            }
+        }
+    })
+}
@@ -0,0 +1,417 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Running checksums over the bytes read from or written to a stream.
+//!
+//! [`Checksummed`] wraps the stream underneath a `BitReader` or `BitWriter`
+//! and feeds every byte that passes through it to a [`Checksum`].
+//! Because those readers and writers only consume or emit whole bytes,
+//! the checksum is exact whenever the bitstream is byte-aligned,
+//! which is when `BitReader::reader` and `BitWriter::writer`
+//! give access to the wrapper.
+//!
+//! Table-driven CRCs of up to 32 bits are provided by [`Crc`],
+//! with common algorithms such as [`CRC_8`] and [`CRC_16`]
+//! (as used by FLAC frames) built in.
+//!
+//! ## Example
+//!
+//! Verifying the CRC-8 at the end of a FLAC frame header.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use tokio_bitstream_io::checksum::{Checksummed, Crc, CRC_8};
+//! use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+//!
+//! # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+//! let frame = [0xFF, 0xF8, 0x69, 0x18, 0x00, 0x00, 0xBF];
+//! let mut reader = BitReader::endian(
+//!     Checksummed::new(Cursor::new(&frame), Crc::new(CRC_8)),
+//!     BigEndian,
+//! );
+//! assert_eq!(reader.read::<u16>(14).await.unwrap(), 0b11111111111110);
+//! reader.skip(34).await.unwrap();
+//!
+//! let crc = reader.reader().unwrap().checksum();
+//! assert_eq!(reader.read::<u32>(8).await.unwrap(), crc);
+//! # });
+//! ```
+
+use std::fmt::Debug;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A running checksum which may be updated a few bytes at a time
+pub trait Checksum {
+    /// The checksum's value
+    type Output: Copy + PartialEq + Debug;
+
+    /// Adds the given bytes to the checksum
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Restores the checksum to its initial state
+    fn reset(&mut self);
+
+    /// Returns the checksum of all bytes added since
+    /// the checksum was created or last reset
+    fn value(&self) -> Self::Output;
+}
+
+/// The parameters of a CRC algorithm of up to 32 bits,
+/// in the form used by the common catalogs of CRC algorithms
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CrcAlgorithm {
+    /// The CRC's width in bits, from 1 to 32
+    pub width: u32,
+    /// The generator polynomial, without its highest bit
+    pub poly: u32,
+    /// The register's initial value
+    pub init: u32,
+    /// Whether bytes are processed least-significant bit first
+    /// (and the final value is reflected to match)
+    pub reflect: bool,
+    /// The value XORed with the register to produce the checksum
+    pub xorout: u32,
+}
+
+/// CRC-8 as used by FLAC frame headers
+pub const CRC_8: CrcAlgorithm = CrcAlgorithm {
+    width: 8,
+    poly: 0x07,
+    init: 0x00,
+    reflect: false,
+    xorout: 0x00,
+};
+
+/// CRC-16 as used by FLAC frames, also known as CRC-16/UMTS
+pub const CRC_16: CrcAlgorithm = CrcAlgorithm {
+    width: 16,
+    poly: 0x8005,
+    init: 0x0000,
+    reflect: false,
+    xorout: 0x0000,
+};
+
+/// CRC-16/CCITT-FALSE, also known as CRC-16/IBM-3740
+pub const CRC_16_CCITT_FALSE: CrcAlgorithm = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0xFFFF,
+    reflect: false,
+    xorout: 0x0000,
+};
+
+/// CRC-16/XMODEM
+pub const CRC_16_XMODEM: CrcAlgorithm = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0x0000,
+    reflect: false,
+    xorout: 0x0000,
+};
+
+/// CRC-16/KERMIT, the reflected form of the CCITT polynomial
+pub const CRC_16_KERMIT: CrcAlgorithm = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0x0000,
+    reflect: true,
+    xorout: 0x0000,
+};
+
+/// CRC-32 as used by Ethernet, zip and PNG
+pub const CRC_32: CrcAlgorithm = CrcAlgorithm {
+    width: 32,
+    poly: 0x04C1_1DB7,
+    init: 0xFFFF_FFFF,
+    reflect: true,
+    xorout: 0xFFFF_FFFF,
+};
+
+/// CRC-32/MPEG-2 as used by MPEG transport stream tables
+pub const CRC_32_MPEG2: CrcAlgorithm = CrcAlgorithm {
+    width: 32,
+    poly: 0x04C1_1DB7,
+    init: 0xFFFF_FFFF,
+    reflect: false,
+    xorout: 0x0000_0000,
+};
+
+/// A table-driven CRC of up to 32 bits
+///
+/// # Example
+/// ```
+/// use tokio_bitstream_io::checksum::{Checksum, Crc, CrcAlgorithm, CRC_16, CRC_32};
+///
+/// let mut crc = Crc::new(CRC_32);
+/// crc.update(b"12345");
+/// crc.update(b"6789");
+/// assert_eq!(crc.value(), 0xCBF4_3926);
+///
+/// let mut crc = Crc::new(CRC_16);
+/// crc.update(b"123456789");
+/// assert_eq!(crc.value(), 0xFEE8);
+///
+/// // CRC-5/USB, defined by hand
+/// let mut crc = Crc::new(CrcAlgorithm {
+///     width: 5,
+///     poly: 0x05,
+///     init: 0x1F,
+///     reflect: true,
+///     xorout: 0x1F,
+/// });
+/// crc.update(b"123456789");
+/// assert_eq!(crc.value(), 0x19);
+/// ```
+#[derive(Clone)]
+pub struct Crc {
+    algorithm: CrcAlgorithm,
+    table: [u32; 256],
+    register: u32,
+}
+
+impl Crc {
+    /// Builds the lookup table for the given algorithm
+    ///
+    /// # Panics
+    ///
+    /// Panics if the algorithm's width is not between 1 and 32.
+    pub fn new(algorithm: CrcAlgorithm) -> Self {
+        assert!(
+            (1..=32).contains(&algorithm.width),
+            "CRC width must be between 1 and 32 bits"
+        );
+        let mut table = [0; 256];
+        if algorithm.reflect {
+            let poly = reflect(algorithm.poly, algorithm.width);
+            for (byte, entry) in table.iter_mut().enumerate() {
+                let mut register = byte as u32;
+                for _ in 0..8 {
+                    register = if register & 1 == 1 {
+                        (register >> 1) ^ poly
+                    } else {
+                        register >> 1
+                    };
+                }
+                *entry = register;
+            }
+        } else {
+            // non-reflected CRCs are kept aligned to the top of the register
+            // so that widths under 8 bits need no special handling
+            let poly = algorithm.poly << (32 - algorithm.width);
+            for (byte, entry) in table.iter_mut().enumerate() {
+                let mut register = (byte as u32) << 24;
+                for _ in 0..8 {
+                    register = if register & 0x8000_0000 != 0 {
+                        (register << 1) ^ poly
+                    } else {
+                        register << 1
+                    };
+                }
+                *entry = register;
+            }
+        }
+        Crc {
+            algorithm,
+            table,
+            register: initial_register(&algorithm),
+        }
+    }
+
+    /// Returns the algorithm this CRC computes
+    #[inline]
+    pub fn algorithm(&self) -> &CrcAlgorithm {
+        &self.algorithm
+    }
+}
+
+impl Checksum for Crc {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        let table = &self.table;
+        self.register = if self.algorithm.reflect {
+            bytes.iter().fold(self.register, |register, byte| {
+                (register >> 8) ^ table[((register ^ u32::from(*byte)) & 0xFF) as usize]
+            })
+        } else {
+            bytes.iter().fold(self.register, |register, byte| {
+                (register << 8) ^ table[((register >> 24) ^ u32::from(*byte)) as usize]
+            })
+        };
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.register = initial_register(&self.algorithm);
+    }
+
+    #[inline]
+    fn value(&self) -> u32 {
+        let value = if self.algorithm.reflect {
+            self.register
+        } else {
+            self.register >> (32 - self.algorithm.width)
+        };
+        value ^ self.algorithm.xorout
+    }
+}
+
+fn initial_register(algorithm: &CrcAlgorithm) -> u32 {
+    if algorithm.reflect {
+        reflect(algorithm.init, algorithm.width)
+    } else {
+        algorithm.init << (32 - algorithm.width)
+    }
+}
+
+#[inline]
+fn reflect(value: u32, width: u32) -> u32 {
+    value.reverse_bits() >> (32 - width)
+}
+
+/// A stream wrapper which updates a checksum with
+/// every byte read from or written to the stream.
+///
+/// It implements `AsyncRead` if the wrapped stream does,
+/// and `AsyncWrite` if the wrapped stream does,
+/// so it may sit underneath either a `BitReader` or a `BitWriter`.
+///
+/// # Example
+/// ```
+/// use tokio_bitstream_io::checksum::{Checksummed, Crc, CRC_16};
+/// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let mut writer = BitWriter::endian(Checksummed::new(Vec::new(), Crc::new(CRC_16)), BigEndian);
+/// writer.write(4, 0b1111).await.unwrap();
+/// writer.write(12, 0b1111_1000_0110).await.unwrap();
+/// let crc = writer.writer().unwrap().checksum();
+/// writer.write(16, crc).await.unwrap();
+///
+/// // a CRC-16 over data followed by its own CRC is 0
+/// assert_eq!(writer.writer().unwrap().checksum(), 0);
+/// assert_eq!(writer.into_writer().into_inner(), [0xFF, 0x86, 0x01, 0x18]);
+/// # });
+/// ```
+pub struct Checksummed<T, C> {
+    inner: T,
+    checksum: C,
+}
+
+impl<T, C: Checksum> Checksummed<T, C> {
+    /// Wraps a stream with the given checksum
+    pub fn new(inner: T, checksum: C) -> Self {
+        Checksummed { inner, checksum }
+    }
+
+    /// Returns the checksum of all bytes passed through
+    /// since the wrapper was created or last reset
+    #[inline]
+    pub fn checksum(&self) -> C::Output {
+        self.checksum.value()
+    }
+
+    /// Restarts the checksum from its initial state
+    #[inline]
+    pub fn reset(&mut self) {
+        self.checksum.reset()
+    }
+
+    /// Compares the current checksum to the expected value
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidData` error if the values differ.
+    pub fn verify(&self, expected: C::Output) -> io::Result<()> {
+        if self.checksum.value() == expected {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "checksum mismatch",
+            ))
+        }
+    }
+
+    /// Returns a reference to the checksum itself
+    #[inline]
+    pub fn checksummer(&self) -> &C {
+        &self.checksum
+    }
+
+    /// Returns a reference to the wrapped stream
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped stream
+    ///
+    /// Bytes read from or written to it directly
+    /// are not included in the checksum.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps the stream
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Unwraps the stream and its checksum
+    #[inline]
+    pub fn into_parts(self) -> (T, C) {
+        (self.inner, self.checksum)
+    }
+}
+
+impl<T: AsyncRead + Unpin, C: Checksum + Unpin> AsyncRead for Checksummed<T, C> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let start = buf.filled().len();
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            this.checksum.update(&buf.filled()[start..]);
+        }
+        result
+    }
+}
+
+impl<T: AsyncWrite + Unpin, C: Checksum + Unpin> AsyncWrite for Checksummed<T, C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            this.checksum.update(&buf[0..written]);
+        }
+        result
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A `tokio-util` codec for framing streams with bit-level parsers.
+//!
+//! [`BitCodec`] implements `Decoder` and `Encoder` for any frame type
+//! which implements [`FromBitStream`] and [`ToBitStream`],
+//! so that it may be used with `Framed`, `FramedRead` and `FramedWrite`.
+//!
+//! Each frame starts on a byte boundary and, if its bits
+//! do not fill its final byte, the rest of that byte is padding.
+//!
+//! This module is only available with the `codec` feature enabled.
+//!
+//! ## Example
+//!
+//! ```
+//! use bytes::BytesMut;
+//! use tokio_util::codec::{Decoder, Encoder};
+//! use tokio_bitstream_io::codec::BitCodec;
+//! use tokio_bitstream_io::{BigEndian, BitRead, BitWrite, FromBitStream, ToBitStream};
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Message {
+//!     urgent: bool,
+//!     body: Vec<u8>,
+//! }
+//!
+//! impl FromBitStream for Message {
+//!     type Error = std::io::Error;
+//!
+//!     async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> std::io::Result<Self> {
+//!         let urgent = r.read_bit().await?;
+//!         let len = r.read::<u8>(7).await?;
+//!         Ok(Message { urgent, body: r.read_to_vec(usize::from(len)).await? })
+//!     }
+//! }
+//!
+//! impl ToBitStream for Message {
+//!     type Error = std::io::Error;
+//!
+//!     async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> std::io::Result<()> {
+//!         w.write_bit(self.urgent).await?;
+//!         w.write(7, self.body.len() as u8).await?;
+//!         w.write_bytes(&self.body).await
+//!     }
+//! }
+//!
+//! let mut codec = BitCodec::<Message, BigEndian>::new();
+//! let mut buf = BytesMut::new();
+//! codec.encode(Message { urgent: true, body: b"hi".to_vec() }, &mut buf).unwrap();
+//! assert_eq!(&buf[..], [0b1_0000010, b'h', b'i']);
+//!
+//! // a partial frame is left in the buffer until the rest arrives
+//! let mut partial = buf.split_to(2);
+//! assert_eq!(codec.decode(&mut partial).unwrap(), None);
+//! assert_eq!(partial.len(), 2);
+//! partial.unsplit(buf);
+//! assert_eq!(
+//!     codec.decode(&mut partial).unwrap(),
+//!     Some(Message { urgent: true, body: b"hi".to_vec() })
+//! );
+//! assert!(partial.is_empty());
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use bytes::{Buf, BytesMut};
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::{
+    BitReader, BitWrite, BitWriter, BitstreamError, BitstreamErrorKind, Endianness, FromBitStream,
+    ToBitStream,
+};
+
+/// A codec which decodes frames with [`FromBitStream`]
+/// and encodes them with [`ToBitStream`] in the given endianness.
+///
+/// When decoding, a parse which fails with an unexpected end of stream
+/// after reading past the end of the buffered bytes is taken to mean
+/// that more bytes are needed, so nothing is consumed and `None` is returned.
+/// The end of stream is recognized from an `io::Error` or [`BitstreamError`]
+/// of that kind, either as the frame's error itself
+/// or anywhere along its chain of [`Error::source`]s.
+/// Any other failure is returned as an error.
+/// Only a successful parse consumes the frame's bytes from the buffer.
+///
+/// A parse can't be resumed, so each call to `decode` parses
+/// the frame from its first byte again.
+/// A frame which arrives in many small pieces is parsed once per piece,
+/// which is quadratic in the frame's size at worst.
+///
+/// Parsing happens entirely in memory, so a frame's
+/// `from_reader` and `to_writer` should not wait on anything
+/// but the reader or writer they are given.
+/// One which does fails with an error of kind `WouldBlock`.
+pub struct BitCodec<T, E: Endianness> {
+    phantom: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T, E: Endianness> BitCodec<T, E> {
+    /// Creates a new codec
+    #[inline]
+    pub fn new() -> Self {
+        BitCodec {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, E: Endianness> Default for BitCodec<T, E> {
+    #[inline]
+    fn default() -> Self {
+        BitCodec::new()
+    }
+}
+
+impl<T, E: Endianness> Clone for BitCodec<T, E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        BitCodec::new()
+    }
+}
+
+impl<T, E: Endianness> fmt::Debug for BitCodec<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BitCodec").finish()
+    }
+}
+
+impl<T, E> Decoder for BitCodec<T, E>
+where
+    T: FromBitStream,
+    T::Error: From<io::Error> + Error + 'static,
+    E: Endianness,
+{
+    type Item = T;
+    type Error = T::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, T::Error> {
+        let exhausted = AtomicBool::new(false);
+        let mut reader = BitReader::<_, E>::new(Frame {
+            data: &src[..],
+            exhausted: &exhausted,
+        });
+        match complete(T::from_reader(&mut reader))? {
+            Ok(frame) => {
+                let bytes = reader.bits_read().div_ceil(8) as usize;
+                src.advance(bytes);
+                Ok(Some(frame))
+            }
+            Err(err) if exhausted.load(Ordering::Relaxed) && is_eof(&err) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<T, E> Encoder<T> for BitCodec<T, E>
+where
+    T: ToBitStream,
+    T::Error: From<io::Error>,
+    E: Endianness,
+{
+    type Error = T::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), T::Error> {
+        let mut writer = BitWriter::<_, E>::new(Vec::new());
+        complete(async {
+            item.to_writer(&mut writer).await?;
+            writer.byte_align().await?;
+            Ok::<(), T::Error>(())
+        })??;
+        dst.extend_from_slice(&writer.into_writer());
+        Ok(())
+    }
+}
+
+/// A frame's worth of buffered bytes which
+/// notes whether anything tried to read past them
+struct Frame<'a> {
+    data: &'a [u8],
+    exhausted: &'a AtomicBool,
+}
+
+impl<'a> AsyncRead for Frame<'a> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.data.is_empty() && buf.remaining() > 0 {
+            self.exhausted.store(true, Ordering::Relaxed);
+        }
+        let len = self.data.len().min(buf.remaining());
+        buf.put_slice(&self.data[0..len]);
+        self.data = &self.data[len..];
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Whether an error or any of its sources is an unexpected end of stream
+fn is_eof(err: &(dyn Error + 'static)) -> bool {
+    let mut next = Some(err);
+    while let Some(err) = next {
+        let eof = if let Some(err) = err.downcast_ref::<io::Error>() {
+            err.kind() == io::ErrorKind::UnexpectedEof
+        } else if let Some(err) = err.downcast_ref::<BitstreamError>() {
+            err.kind() == BitstreamErrorKind::UnexpectedEof
+        } else {
+            false
+        };
+        if eof {
+            return true;
+        }
+        next = err.source();
+    }
+    false
+}
+
+/// A waker for futures which never need waking
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Runs a future which only works with in-memory data to completion
+fn complete<F: Future>(future: F) -> io::Result<F::Output> {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let future = std::pin::pin!(future);
+    match future.poll(&mut Context::from_waker(&waker)) {
+        Poll::Ready(output) => Ok(output),
+        Poll::Pending => Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "bitstream codec waited on something other than its data",
+        )),
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Adapters for using `futures-io` streams with readers and writers.
+//!
+//! Readers and writers are built on tokio's `AsyncRead`, `AsyncWrite`
+//! and `AsyncSeek` traits, which need none of tokio's runtime.
+//! This module re-exports `tokio_util::compat`, whose [`Compat`]
+//! wraps any stream implementing the `futures-io` versions
+//! of those traits, such as async-std sockets and files,
+//! so that it may be used with `BitReader`, `BitWriter`,
+//! `ByteReader` and `ByteWriter` directly.
+//! Wrap a stream to read with [`FuturesAsyncReadCompatExt::compat`]
+//! and one to write with [`FuturesAsyncWriteCompatExt::compat_write`].
+//! Wrapping a seekable stream also makes methods such as
+//! `BitReader::seek_bits` available.
+//!
+//! This module is only available with the `futures-io` feature enabled.
+//!
+//! ## Example
+//!
+//! ```
+//! use futures::io::Cursor;
+//! use std::io::SeekFrom;
+//! use tokio_bitstream_io::compat::{FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt};
+//! use tokio_bitstream_io::{BigEndian, BitReader, BitRead, BitWriter, BitWrite};
+//!
+//! # futures::executor::block_on(async {
+//! let mut writer = BitWriter::endian(Cursor::new(Vec::new()).compat_write(), BigEndian);
+//! writer.write(4, 0b1011).await.unwrap();
+//! writer.write(12, 0x123).await.unwrap();
+//! let data = writer.into_writer().into_inner().into_inner();
+//! assert_eq!(data, [0b1011_0001, 0x23]);
+//!
+//! let mut reader = BitReader::endian(Cursor::new(&data).compat(), BigEndian);
+//! assert_eq!(reader.seek_bits(SeekFrom::Start(8)).await.unwrap(), 8);
+//! assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x23);
+//! # });
+//! ```
+
+pub use tokio_util::compat::{
+    Compat, FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt, TokioAsyncReadCompatExt,
+    TokioAsyncWriteCompatExt,
+};
@@ -0,0 +1,842 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Synchronous bitstream reading and writing for data held in memory.
+//!
+//! Every call to a `BitRead` or `BitWrite` method builds and polls a future,
+//! and the bytes themselves pass through an `AsyncRead` or `AsyncWrite`,
+//! which is wasted effort once a whole frame is already in memory.
+//! [`BitSlice`] reads from a `&[u8]` and [`BitVecWriter`] writes to a `Vec<u8>`
+//! with plain methods of the same names instead,
+//! using the same endianness handling and Huffman trees.
+//!
+//! Converting between these and the async types happens at byte boundaries:
+//!
+//! * [`BitSlice::into_bitreader`] and [`BitVecWriter::into_bitwriter`]
+//!   carry on where the synchronous type left off
+//! * a `BitReader<&[u8], E>` gives back the rest of its slice from
+//!   `BitReader::into_parts`, and a `BitWriter<Vec<u8>, E>` its bytes from
+//!   `BitWriter::into_writer`, for [`BitSlice::new`] and
+//!   [`BitVecWriter::from_vec`] to continue with.
+//!   Any bytes the reader has read ahead come back separately
+//!   and must be read before the rest of the slice.
+//!
+//! ## Example
+//!
+//! ```
+//! use tokio_bitstream_io::{BigEndian, BitSlice, BitVecWriter, BitRead};
+//!
+//! let mut writer = BitVecWriter::<BigEndian>::new();
+//! writer.write(3, 0b101).unwrap();
+//! writer.write_signed(5, -3).unwrap();
+//! writer.write_unary0(2).unwrap();
+//! writer.byte_align();
+//! let data = writer.into_vec();
+//! assert_eq!(data, [0b1011_1101, 0b1100_0000]);
+//!
+//! let mut reader = BitSlice::<BigEndian>::new(&data);
+//! assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+//! assert_eq!(reader.read_signed::<i8>(5).unwrap(), -3);
+//! assert_eq!(reader.read_unary0().unwrap(), 2);
+//!
+//! // the rest may be read asynchronously
+//! reader.byte_align();
+//! let mut reader = reader.into_bitreader();
+//! # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+//! assert!(reader.read_bit().await.is_err());
+//! # });
+//! ```
+
+use std::borrow::Borrow;
+use std::io;
+
+use super::error::{io_error, BitstreamErrorKind};
+use super::huffman::{ReadHuffmanTree, WideEntry, WideHuffmanTable, WriteHuffmanCodes};
+use super::read::unassigned_code;
+use super::write::huffman_code;
+use super::{
+    numeric_from_u128, numeric_to_u128, BitQueue, BitReader, BitWriter, Endianness, Numeric,
+    SignedNumeric,
+};
+
+/// For reading non-aligned bits from a slice of bytes in a given endianness.
+///
+/// Unlike `BitReader`, a read which would go past the end of the slice
+/// fails with `UnexpectedEof` without consuming anything,
+/// so it may be retried once more data is available.
+pub struct BitSlice<'a, E: Endianness> {
+    data: &'a [u8],
+    len: usize,
+    bitqueue: BitQueue<E, u8>,
+}
+
+impl<'a, E: Endianness> Clone for BitSlice<'a, E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        BitSlice {
+            data: self.data,
+            len: self.len,
+            bitqueue: self.bitqueue.clone(),
+        }
+    }
+}
+
+impl<'a, E: Endianness> BitSlice<'a, E> {
+    /// Wraps a BitSlice around a slice of bytes
+    #[inline]
+    pub fn new(data: &'a [u8]) -> BitSlice<'a, E> {
+        BitSlice {
+            data,
+            len: data.len(),
+            bitqueue: BitQueue::new(),
+        }
+    }
+
+    /// Wraps a BitSlice around a slice of bytes
+    /// with the given endianness.
+    #[inline]
+    pub fn endian(data: &'a [u8], _endian: E) -> BitSlice<'a, E> {
+        BitSlice::new(data)
+    }
+
+    /// Returns the number of bits read since the slice was wrapped
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{LittleEndian, BitSlice};
+    /// let mut reader = BitSlice::endian(&[0xFF, 0x00], LittleEndian);
+    /// reader.skip(3).unwrap();
+    /// assert_eq!(reader.bits_read(), 3);
+    /// reader.byte_align();
+    /// assert_eq!(reader.bits_read(), 8);
+    /// ```
+    #[inline]
+    pub fn bits_read(&self) -> u64 {
+        (self.len - self.data.len()) as u64 * 8 - u64::from(self.bitqueue.len())
+    }
+
+    /// Returns the number of bits left to read
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        self.data.len() as u64 * 8 + u64::from(self.bitqueue.len())
+    }
+
+    /// Returns the whole bytes which have not been read from at all,
+    /// which does not include any unread partial byte
+    #[inline]
+    pub fn unread_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Converts `BitSlice` to an async `BitReader` over the rest
+    /// of the slice in the same endianness.
+    ///
+    /// # Warning
+    ///
+    /// Any unread partial bits are discarded.
+    #[inline]
+    pub fn into_bitreader(self) -> BitReader<&'a [u8], E> {
+        BitReader::new(self.data)
+    }
+
+    /// Reads a single bit from the slice.
+    /// `true` indicates 1, `false` indicates 0
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` at the end of the slice.
+    #[inline]
+    pub fn read_bit(&mut self) -> io::Result<bool> {
+        if self.bitqueue.is_empty() {
+            let byte = self.next_byte()?;
+            self.bitqueue.set(byte, 8);
+        }
+        Ok(self.bitqueue.pop(1) == 1)
+    }
+
+    /// Reads an unsigned value from the slice with
+    /// the given number of bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if fewer bits remain,
+    /// in which case nothing is consumed.
+    /// Also returns an error if the output type is too small
+    /// to hold the requested number of bits.
+    ///
+    /// # Examples
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitSlice};
+    /// let mut reader = BitSlice::<BigEndian>::new(&[0b1011_0111, 0b0101_1010]);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1011);
+    /// assert!(reader.read::<u16>(13).is_err());
+    /// assert_eq!(reader.read::<u16>(12).unwrap(), 0b0111_0101_1010);
+    /// ```
+    ///
+    /// ```
+    /// use tokio_bitstream_io::{LittleEndian, BitSlice};
+    /// let mut reader = BitSlice::<LittleEndian>::new(&[0b1011_0111, 0b0101_1010]);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b0111);
+    /// assert_eq!(reader.read::<u16>(12).unwrap(), 0b0101_1010_1011);
+    /// ```
+    pub fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        if bits > U::BITS_SIZE {
+            return Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type read",
+            ));
+        }
+        self.check_remaining(bits)?;
+
+        let queued = self.bitqueue.len();
+        if bits <= queued {
+            return Ok(U::from_u8(self.bitqueue.pop(bits)));
+        }
+        let mut acc = BitQueue::<E, U>::from_value(U::from_u8(self.bitqueue.pop_all()), queued);
+        let bits = bits - queued;
+        let (whole, rest) = self.data.split_at((bits / 8) as usize);
+        for byte in whole {
+            acc.push(8, U::from_u8(*byte));
+        }
+        self.data = rest;
+        if bits % 8 != 0 {
+            let byte = self.next_byte()?;
+            self.bitqueue.set(byte, 8);
+            acc.push(bits % 8, U::from_u8(self.bitqueue.pop(bits % 8)));
+        }
+        Ok(acc.value())
+    }
+
+    /// Reads a twos-complement signed value from the slice with
+    /// the given number of bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if fewer bits remain,
+    /// in which case nothing is consumed.
+    /// Also returns an error if the output type is too small
+    /// to hold the requested number of bits, or if that number is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitSlice};
+    /// let mut reader = BitSlice::<BigEndian>::new(&[0b1011_0111]);
+    /// assert_eq!(reader.read_signed::<i8>(4).unwrap(), -5);
+    /// assert_eq!(reader.read_signed::<i8>(4).unwrap(), 7);
+    /// ```
+    pub fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        if bits == 0 || bits > S::BITS_SIZE {
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type read",
+            ))
+        } else if bits == S::BITS_SIZE {
+            // read the whole bit pattern without shifting
+            // anything into the sign bit along the way
+            let value = self.read::<u128>(bits)?;
+            Ok(numeric_from_u128(value).unwrap())
+        } else {
+            // in either endianness, the sign bit ends up
+            // as the most significant of those read
+            let value = self.read::<S>(bits)?;
+            let magnitude = S::ONE << (bits - 1);
+            if value >= magnitude {
+                Ok((value % magnitude).as_negative(bits))
+            } else {
+                Ok(value)
+            }
+        }
+    }
+
+    /// Counts the number of 1 bits in the slice until the next
+    /// 0 bit and returns the amount read.
+    /// Because this field is variably-sized and may be large,
+    /// its output is always a `u32` type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if no 0 bit remains,
+    /// in which case nothing is consumed.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitSlice};
+    /// let mut reader = BitSlice::<BigEndian>::new(&[0b0111_0111, 0b1111_1110]);
+    /// assert_eq!(reader.read_unary0().unwrap(), 0);
+    /// assert_eq!(reader.read_unary0().unwrap(), 3);
+    /// assert_eq!(reader.read_unary0().unwrap(), 10);
+    /// ```
+    #[inline]
+    pub fn read_unary0(&mut self) -> io::Result<u32> {
+        self.read_unary(true)
+    }
+
+    /// Counts the number of 0 bits in the slice until the next
+    /// 1 bit and returns the amount read.
+    /// Because this field is variably-sized and may be large,
+    /// its output is always a `u32` type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if no 1 bit remains,
+    /// in which case nothing is consumed.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{LittleEndian, BitSlice};
+    /// let mut reader = BitSlice::<LittleEndian>::new(&[0b0001_0001, 0b1000_0000]);
+    /// assert_eq!(reader.read_unary1().unwrap(), 0);
+    /// assert_eq!(reader.read_unary1().unwrap(), 3);
+    /// assert_eq!(reader.read_unary1().unwrap(), 10);
+    /// ```
+    #[inline]
+    pub fn read_unary1(&mut self) -> io::Result<u32> {
+        self.read_unary(false)
+    }
+
+    /// Counts the run of `ones` bits (or 0 bits if false)
+    /// up to and including the bit which ends it
+    fn read_unary(&mut self, ones: bool) -> io::Result<u32> {
+        let mut queue = self.bitqueue.clone();
+        let mut data = self.data;
+        let mut run = 0;
+        loop {
+            if queue.is_empty() {
+                let (byte, rest) = data.split_first().ok_or_else(early_eof)?;
+                queue.set(*byte, 8);
+                data = rest;
+            }
+            if ones && queue.all_1() || !ones && queue.all_0() {
+                run += queue.len();
+                queue.clear();
+            } else {
+                run += if ones { queue.pop_1() } else { queue.pop_0() };
+                self.bitqueue = queue;
+                self.data = data;
+                return Ok(run);
+            }
+        }
+    }
+
+    /// Skips the given number of bits in the slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if fewer bits remain,
+    /// in which case nothing is consumed.
+    pub fn skip(&mut self, bits: u32) -> io::Result<()> {
+        self.check_remaining(bits)?;
+        let dropped = self.bitqueue.len().min(bits);
+        self.bitqueue.drop(dropped);
+        let bits = bits - dropped;
+        self.data = &self.data[(bits / 8) as usize..];
+        if bits % 8 != 0 {
+            let byte = self.next_byte()?;
+            self.bitqueue.set(byte, 8);
+            self.bitqueue.drop(bits % 8);
+        }
+        Ok(())
+    }
+
+    /// Completely fills the given buffer with whole bytes.
+    /// If the slice is already byte-aligned, the bytes are copied
+    /// directly.  Otherwise, they are read 8 bits at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if too few bits remain,
+    /// in which case nothing is consumed.
+    pub fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if self.remaining() < buf.len() as u64 * 8 {
+            Err(early_eof())
+        } else if self.byte_aligned() {
+            let (bytes, rest) = self.data.split_at(buf.len());
+            buf.copy_from_slice(bytes);
+            self.data = rest;
+            Ok(())
+        } else {
+            for b in buf.iter_mut() {
+                *b = self.read(8)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Given a compiled Huffman tree, reads bits from the slice
+    /// until the next symbol is encountered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if the slice ends
+    /// partway through a code, in which case nothing is consumed.
+    /// Reading bits which have no symbol assigned to them in the tree
+    /// consumes those bits and returns an error of kind `InvalidData`.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitSlice};
+    /// use tokio_bitstream_io::huffman::compile_read_tree;
+    /// let tree = compile_read_tree(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1, 0]),
+    ///          ('d', vec![1, 1, 1])]).unwrap();
+    /// let mut reader = BitSlice::<BigEndian>::new(&[0b1011_0111]);
+    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'b');
+    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'c');
+    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'd');
+    /// ```
+    pub fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> io::Result<T>
+    where
+        T: Clone,
+    {
+        let mut bytes = 0;
+        let mut result = tree
+            .get(self.bitqueue.to_state())
+            .unwrap_or(&ReadHuffmanTree::InvalidState);
+        loop {
+            match result {
+                ReadHuffmanTree::Done(ref value, ref queue_val, ref queue_bits, _) => {
+                    self.data = &self.data[bytes..];
+                    self.bitqueue.set(*queue_val, *queue_bits);
+                    return Ok(value.clone());
+                }
+                ReadHuffmanTree::Continue(ref tree) => {
+                    let byte = self.data.get(bytes).ok_or_else(early_eof)?;
+                    result = &tree[*byte as usize];
+                    bytes += 1;
+                }
+                ReadHuffmanTree::Unassigned(ref prefix, ref queue_val, ref queue_bits, _) => {
+                    self.data = &self.data[bytes..];
+                    self.bitqueue.set(*queue_val, *queue_bits);
+                    return Err(unassigned_code(prefix));
+                }
+                ReadHuffmanTree::InvalidState => {
+                    return Err(io_error(
+                        BitstreamErrorKind::InvalidCode,
+                        "invalid Huffman reader state",
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Reads the next symbol using a table from
+    /// [`widen_read_tree`](crate::huffman::widen_read_tree),
+    /// decoding codes which fit in the table with a single lookup.
+    ///
+    /// # Errors
+    ///
+    /// As with [`BitSlice::read_huffman`].
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitSlice};
+    /// use tokio_bitstream_io::huffman::{compile_read_tree, widen_read_tree};
+    /// let tree = compile_read_tree(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1, 0]),
+    ///          ('d', vec![1, 1, 1])]).unwrap();
+    /// let table = widen_read_tree(tree, 4).unwrap();
+    /// let mut reader = BitSlice::<BigEndian>::new(&[0b1011_0111]);
+    /// assert_eq!(reader.read_huffman_wide(&table).unwrap(), 'b');
+    /// assert_eq!(reader.read_huffman_wide(&table).unwrap(), 'c');
+    /// assert_eq!(reader.read_huffman_wide(&table).unwrap(), 'd');
+    /// ```
+    pub fn read_huffman_wide<T>(&mut self, table: &WideHuffmanTable<E, T>) -> io::Result<T>
+    where
+        T: Clone,
+    {
+        match self.lookup_huffman_wide(table) {
+            Some(result) => result,
+            None => self.read_huffman(table.tree()),
+        }
+    }
+
+    /// Reads a Huffman code with a single lookup in a wide table,
+    /// returning `None` without consuming anything if the code
+    /// is longer than the table or too few bytes remain to fill it
+    fn lookup_huffman_wide<T>(&mut self, table: &WideHuffmanTable<E, T>) -> Option<io::Result<T>>
+    where
+        T: Clone,
+    {
+        let queued = self.bitqueue.len();
+        let bytes = table.width().saturating_sub(queued).div_ceil(8) as usize;
+        if self.data.len() < bytes {
+            return None;
+        }
+
+        let window = table.window(self.bitqueue.clone().value(), queued, &self.data[0..bytes]);
+        let (len, result) = match table.entry(&window) {
+            WideEntry::Done(ref value, len) => (*len, Ok(value.clone())),
+            WideEntry::Unassigned(ref prefix, len) => (*len, Err(unassigned_code(prefix))),
+            WideEntry::Long => return None,
+        };
+        let (whole, queue_val, queue_bits) = table.consume(&window, len);
+        self.data = &self.data[bytes - whole..];
+        self.bitqueue.set(queue_val, queue_bits);
+        Some(result)
+    }
+
+    /// Returns true if the slice is aligned at a whole byte.
+    #[inline]
+    pub fn byte_aligned(&self) -> bool {
+        self.bitqueue.is_empty()
+    }
+
+    /// Throws away all unread bit values until the next whole byte.
+    /// Does nothing if the slice is already aligned.
+    #[inline]
+    pub fn byte_align(&mut self) {
+        self.bitqueue.clear()
+    }
+
+    #[inline]
+    fn check_remaining(&self, bits: u32) -> io::Result<()> {
+        if u64::from(bits) > self.remaining() {
+            Err(early_eof())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn next_byte(&mut self) -> io::Result<u8> {
+        let (byte, rest) = self.data.split_first().ok_or_else(early_eof)?;
+        self.data = rest;
+        Ok(*byte)
+    }
+}
+
+fn early_eof() -> io::Error {
+    io_error(BitstreamErrorKind::UnexpectedEof, "early eof")
+}
+
+/// For writing bit values to a growable vector of bytes in a given endianness.
+///
+/// Writes cannot fail on account of the vector,
+/// only on account of the values given to them.
+/// **Partial bytes will be lost** if the writer is disposed of
+/// before they are aligned.
+pub struct BitVecWriter<E: Endianness> {
+    data: Vec<u8>,
+    start: usize,
+    bitqueue: BitQueue<E, u8>,
+}
+
+impl<E: Endianness> Default for BitVecWriter<E> {
+    #[inline]
+    fn default() -> Self {
+        BitVecWriter::new()
+    }
+}
+
+impl<E: Endianness> BitVecWriter<E> {
+    /// Creates a writer to a new, empty vector
+    #[inline]
+    pub fn new() -> BitVecWriter<E> {
+        BitVecWriter::from_vec(Vec::new())
+    }
+
+    /// Creates a writer to a new, empty vector
+    /// with the given endianness.
+    #[inline]
+    pub fn endian(_endian: E) -> BitVecWriter<E> {
+        BitVecWriter::new()
+    }
+
+    /// Creates a writer to a new vector with room for
+    /// the given number of bytes before it reallocates
+    #[inline]
+    pub fn with_capacity(bytes: usize) -> BitVecWriter<E> {
+        BitVecWriter::from_vec(Vec::with_capacity(bytes))
+    }
+
+    /// Creates a writer which appends to the given vector
+    #[inline]
+    pub fn from_vec(data: Vec<u8>) -> BitVecWriter<E> {
+        BitVecWriter {
+            start: data.len(),
+            data,
+            bitqueue: BitQueue::new(),
+        }
+    }
+
+    /// Returns the number of bits written since the writer was created,
+    /// including any partial byte
+    #[inline]
+    pub fn bits_written(&self) -> u64 {
+        (self.data.len() - self.start) as u64 * 8 + u64::from(self.bitqueue.len())
+    }
+
+    /// Returns the whole bytes in the vector so far
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Unwraps the vector and disposes of the writer.
+    ///
+    /// # Warning
+    ///
+    /// Any unwritten partial bits are discarded.
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Converts `BitVecWriter` to an async `BitWriter`
+    /// which appends to the same vector in the same endianness.
+    ///
+    /// # Warning
+    ///
+    /// Any unwritten partial bits are discarded.
+    #[inline]
+    pub fn into_bitwriter(self) -> BitWriter<Vec<u8>, E> {
+        BitWriter::new(self.data)
+    }
+
+    /// Writes a single bit.
+    /// `true` indicates 1, `false` indicates 0
+    #[inline]
+    pub fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.bitqueue.push(1, u8::from(bit));
+        if self.bitqueue.is_full() {
+            self.data.push(self.bitqueue.pop(8));
+        }
+        Ok(())
+    }
+
+    /// Writes an unsigned value with the given number of bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input type is too small
+    /// to hold the given number of bits,
+    /// or if the value is too large to fit them.
+    ///
+    /// # Examples
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitVecWriter};
+    /// let mut writer = BitVecWriter::<BigEndian>::new();
+    /// writer.write(1, 0b1).unwrap();
+    /// writer.write(2, 0b01).unwrap();
+    /// writer.write(5, 0b10111).unwrap();
+    /// assert!(writer.write(2, 4).is_err());
+    /// assert_eq!(writer.into_vec(), [0b1011_0111]);
+    /// ```
+    ///
+    /// ```
+    /// use tokio_bitstream_io::{LittleEndian, BitVecWriter};
+    /// let mut writer = BitVecWriter::<LittleEndian>::new();
+    /// writer.write(1, 0b1).unwrap();
+    /// writer.write(2, 0b11).unwrap();
+    /// writer.write(5, 0b10110).unwrap();
+    /// assert_eq!(writer.into_vec(), [0b1011_0111]);
+    /// ```
+    pub fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    where
+        U: Numeric,
+    {
+        if bits > U::BITS_SIZE {
+            return Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type written",
+            ));
+        } else if (bits < U::BITS_SIZE) && (value >= (U::ONE << bits)) {
+            return Err(io_error(
+                BitstreamErrorKind::ExcessiveValue,
+                "excessive value for bits written",
+            ));
+        } else if bits < self.bitqueue.remaining_len() {
+            self.bitqueue.push(bits, value.to_u8());
+            return Ok(());
+        }
+
+        let mut acc = BitQueue::<E, U>::from_value(value, bits);
+        let fill = self.bitqueue.remaining_len();
+        self.bitqueue.push(fill, acc.pop(fill).to_u8());
+        self.data.push(self.bitqueue.pop(8));
+        while acc.len() >= 8 {
+            self.data.push(acc.pop(8).to_u8());
+        }
+        self.bitqueue.push(acc.len(), acc.value().to_u8());
+        Ok(())
+    }
+
+    /// Writes a twos-complement signed value with the given number of bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input type is too small
+    /// to hold the given number of bits, if that number is 0,
+    /// or if the value is too large to fit them.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{LittleEndian, BitVecWriter};
+    /// let mut writer = BitVecWriter::<LittleEndian>::new();
+    /// writer.write_signed(4, 7).unwrap();
+    /// writer.write_signed(4, -5).unwrap();
+    /// assert_eq!(writer.into_vec(), [0b1011_0111]);
+    /// ```
+    pub fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
+    where
+        S: SignedNumeric,
+    {
+        if bits == 0 || bits > S::BITS_SIZE {
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type written",
+            ))
+        } else if bits == S::BITS_SIZE {
+            // write the whole bit pattern without
+            // shifting the sign bit along the way
+            self.write(bits, numeric_to_u128(value))
+        } else {
+            let negative = value.is_negative();
+            let magnitude = if negative {
+                value.as_unsigned(bits)
+            } else {
+                value
+            };
+            if magnitude.is_negative() || magnitude >= S::ONE << (bits - 1) {
+                Err(io_error(
+                    BitstreamErrorKind::ExcessiveValue,
+                    "excessive value for bits written",
+                ))
+            } else if bits + 1 < S::BITS_SIZE {
+                // in either endianness, the sign bit goes
+                // in the most significant of those written
+                let mut pattern = magnitude;
+                if negative {
+                    pattern |= S::ONE << (bits - 1);
+                }
+                self.write(bits, pattern)
+            } else {
+                // which is too close to the type's own sign bit
+                // to be range-checked in that type
+                let sign = u128::from(negative) << (bits - 1);
+                self.write(bits, numeric_to_u128(magnitude) | sign)
+            }
+        }
+    }
+
+    /// Writes `value` number of 1 bits to the vector
+    /// and then writes a 0 bit.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitVecWriter};
+    /// let mut writer = BitVecWriter::<BigEndian>::new();
+    /// writer.write_unary0(0).unwrap();
+    /// writer.write_unary0(3).unwrap();
+    /// writer.write_unary0(10).unwrap();
+    /// assert_eq!(writer.into_vec(), [0b0111_0111, 0b1111_1110]);
+    /// ```
+    pub fn write_unary0(&mut self, mut value: u32) -> io::Result<()> {
+        while value > 32 {
+            self.write(32, u32::MAX)?;
+            value -= 32;
+        }
+        self.write(value, u32::MAX.checked_shr(32 - value).unwrap_or(0))?;
+        self.write_bit(false)
+    }
+
+    /// Writes `value` number of 0 bits to the vector
+    /// and then writes a 1 bit.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{LittleEndian, BitVecWriter};
+    /// let mut writer = BitVecWriter::<LittleEndian>::new();
+    /// writer.write_unary1(0).unwrap();
+    /// writer.write_unary1(3).unwrap();
+    /// writer.write_unary1(10).unwrap();
+    /// assert_eq!(writer.into_vec(), [0b0001_0001, 0b1000_0000]);
+    /// ```
+    pub fn write_unary1(&mut self, mut value: u32) -> io::Result<()> {
+        while value > 32 {
+            self.write(32, 0u32)?;
+            value -= 32;
+        }
+        self.write(value, 0u32)?;
+        self.write_bit(true)
+    }
+
+    /// Writes the entirety of a byte buffer to the vector.
+    /// If the writer is already byte-aligned, the bytes are copied
+    /// directly.  Otherwise, they are written 8 bits at a time.
+    pub fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.byte_aligned() {
+            self.data.extend_from_slice(buf);
+        } else {
+            for b in buf {
+                self.write(8, *b)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the given symbol's code from a compiled Huffman tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidInput`
+    /// if the symbol is not in the tree.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitVecWriter};
+    /// use tokio_bitstream_io::huffman::compile_write_tree;
+    /// let tree = compile_write_tree(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1, 0]),
+    ///          ('d', vec![1, 1, 1])]).unwrap();
+    /// let mut writer = BitVecWriter::<BigEndian>::new();
+    /// writer.write_huffman(&tree, 'b').unwrap();
+    /// writer.write_huffman(&tree, 'c').unwrap();
+    /// writer.write_huffman(&tree, 'd').unwrap();
+    /// assert_eq!(writer.into_vec(), [0b1011_0111]);
+    /// ```
+    pub fn write_huffman<C, S>(&mut self, tree: &C, symbol: S) -> io::Result<()>
+    where
+        C: WriteHuffmanCodes<E> + ?Sized,
+        S: Borrow<C::Symbol>,
+    {
+        for (bits, value) in huffman_code(tree, symbol.borrow())? {
+            self.write(*bits, *value)?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the writer is aligned at a whole byte.
+    #[inline]
+    pub fn byte_aligned(&self) -> bool {
+        self.bitqueue.is_empty()
+    }
+
+    /// Pads the vector with 0 bits until it is aligned at a whole byte.
+    /// Does nothing if the writer is already aligned.
+    #[inline]
+    pub fn byte_align(&mut self) {
+        if !self.bitqueue.is_empty() {
+            let fill = self.bitqueue.remaining_len();
+            self.bitqueue.push(fill, 0);
+            self.data.push(self.bitqueue.pop(8));
+        }
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A structured error type for bitstream parsers.
+//!
+//! Readers and writers report failures as `std::io::Error`,
+//! which says little about where in a large parser something went wrong.
+//! A [`BitstreamError`] records what kind of failure it was,
+//! the bit offset it happened at, and the names of the fields
+//! being handled, which callers add as the error travels outward.
+//!
+//! Any `io::Error` converts to a `BitstreamError`,
+//! and those raised by readers and writers keep their precise kind.
+//! A `BitstreamError` converts back to an `io::Error`
+//! of the matching `io::ErrorKind` without losing anything,
+//! so parsers may use either one in their signatures.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::io::Cursor;
+//! use tokio::io::AsyncRead;
+//! use tokio_bitstream_io::error::{BitstreamError, BitstreamErrorKind, ErrorContext};
+//! use tokio_bitstream_io::{BigEndian, BitRead, BitReader};
+//!
+//! async fn read_header<R>(r: &mut BitReader<R, BigEndian>) -> Result<u16, BitstreamError>
+//! where
+//!     R: AsyncRead + Unpin + Send + Sync,
+//! {
+//!     let version = r.read::<u8>(4).await.at_bit(r.bits_read()).field("version")?;
+//!     if version != 1 {
+//!         return Err(BitstreamError::constraint("unsupported version")
+//!             .at(r.bits_read() - 4)
+//!             .in_field("version"));
+//!     }
+//!     r.read::<u16>(16).await.at_bit(r.bits_read()).field("length")
+//! }
+//!
+//! # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+//! let mut reader = BitReader::endian(Cursor::new([0x10, 0x02]), BigEndian);
+//! let err = read_header(&mut reader).await.field("header").unwrap_err();
+//! assert_eq!(err.kind(), BitstreamErrorKind::UnexpectedEof);
+//! assert_eq!(err.offset(), Some(4));
+//! assert_eq!(err.fields(), ["header", "length"]);
+//! assert_eq!(err.to_string(), "early eof at bit 4 in header.length");
+//!
+//! let mut reader = BitReader::endian(Cursor::new([0x20, 0x00, 0x01]), BigEndian);
+//! let err = read_header(&mut reader).await.unwrap_err();
+//! assert_eq!(err.kind(), BitstreamErrorKind::Constraint);
+//! assert_eq!(err.to_string(), "unsupported version at bit 0 in version");
+//!
+//! // the error survives a trip through io::Error
+//! let err = BitstreamError::from(std::io::Error::from(err));
+//! assert_eq!(err.fields(), ["version"]);
+//! # });
+//! ```
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use super::huffman::HuffmanTreeError;
+
+/// The kind of failure a [`BitstreamError`] records
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum BitstreamErrorKind {
+    /// The stream ended before the value did
+    UnexpectedEof,
+    /// More bits were requested than the value's type holds
+    ExcessiveBits,
+    /// A value doesn't fit in the bits or type it was given
+    ExcessiveValue,
+    /// The stream holds a code which has no meaning,
+    /// such as bits with no Huffman symbol assigned to them
+    InvalidCode,
+    /// A value was read successfully but broke
+    /// some rule of the format being parsed
+    Constraint,
+    /// Any other I/O error from the underlying stream
+    Io,
+}
+
+impl BitstreamErrorKind {
+    fn io_kind(self) -> io::ErrorKind {
+        match self {
+            BitstreamErrorKind::UnexpectedEof => io::ErrorKind::UnexpectedEof,
+            BitstreamErrorKind::ExcessiveBits | BitstreamErrorKind::ExcessiveValue => {
+                io::ErrorKind::InvalidInput
+            }
+            BitstreamErrorKind::InvalidCode | BitstreamErrorKind::Constraint => {
+                io::ErrorKind::InvalidData
+            }
+            BitstreamErrorKind::Io => io::ErrorKind::Other,
+        }
+    }
+}
+
+/// An error with the kind of failure, the bit offset
+/// it happened at and the fields being handled at the time.
+///
+/// The offset and fields are both optional and are added by callers,
+/// usually with the methods of [`ErrorContext`].
+#[derive(Debug)]
+pub struct BitstreamError {
+    kind: BitstreamErrorKind,
+    io_kind: io::ErrorKind,
+    message: Cow<'static, str>,
+    offset: Option<u64>,
+    fields: Vec<Cow<'static, str>>,
+    source: Option<io::Error>,
+}
+
+impl BitstreamError {
+    /// Creates a new error of the given kind with the given message
+    pub fn new<M>(kind: BitstreamErrorKind, message: M) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        BitstreamError {
+            kind,
+            io_kind: kind.io_kind(),
+            message: message.into(),
+            offset: None,
+            fields: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Creates a new error for a value which breaks
+    /// some rule of the format being parsed
+    #[inline]
+    pub fn constraint<M>(message: M) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        BitstreamError::new(BitstreamErrorKind::Constraint, message)
+    }
+
+    /// Returns the kind of failure
+    #[inline]
+    pub fn kind(&self) -> BitstreamErrorKind {
+        self.kind
+    }
+
+    /// Returns the message describing the failure,
+    /// without any offset or fields
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the bit offset the failure happened at, if known
+    #[inline]
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// Returns the names of the fields being handled,
+    /// from the outermost to the innermost
+    #[inline]
+    pub fn fields(&self) -> &[Cow<'static, str>] {
+        &self.fields
+    }
+
+    /// Records the bit offset the failure happened at.
+    ///
+    /// An offset which has already been recorded is kept,
+    /// since one closer to the failure is more precise.
+    #[inline]
+    pub fn at(mut self, offset: u64) -> Self {
+        self.offset.get_or_insert(offset);
+        self
+    }
+
+    /// Records the name of a field which encloses
+    /// any that have been recorded already
+    pub fn in_field<F>(mut self, field: F) -> Self
+    where
+        F: Into<Cow<'static, str>>,
+    {
+        self.fields.insert(0, field.into());
+        self
+    }
+
+    /// Overrides the kind of `io::Error` this converts to
+    #[inline]
+    pub(crate) fn with_io_kind(mut self, io_kind: io::ErrorKind) -> Self {
+        self.io_kind = io_kind;
+        self
+    }
+}
+
+impl fmt::Display for BitstreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if let Some(offset) = self.offset {
+            write!(f, " at bit {}", offset)?;
+        }
+        if let Some((first, rest)) = self.fields.split_first() {
+            write!(f, " in {}", first)?;
+            for field in rest {
+                write!(f, ".{}", field)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for BitstreamError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|err| err as &(dyn Error + 'static))
+    }
+}
+
+impl From<io::Error> for BitstreamError {
+    fn from(err: io::Error) -> Self {
+        if err
+            .get_ref()
+            .is_some_and(|inner| inner.is::<BitstreamError>())
+        {
+            // one which started out as a BitstreamError
+            // comes back as it was
+            return *err.into_inner().unwrap().downcast().unwrap();
+        }
+        let kind = match err.kind() {
+            io::ErrorKind::UnexpectedEof => BitstreamErrorKind::UnexpectedEof,
+            _ => BitstreamErrorKind::Io,
+        };
+        BitstreamError {
+            kind,
+            io_kind: err.kind(),
+            message: err.to_string().into(),
+            offset: None,
+            fields: Vec::new(),
+            source: Some(err),
+        }
+    }
+}
+
+impl From<BitstreamError> for io::Error {
+    fn from(mut err: BitstreamError) -> Self {
+        // an I/O error with nothing added to it goes back unchanged
+        if err.offset.is_none() && err.fields.is_empty() {
+            if let Some(source) = err.source.take() {
+                return source;
+            }
+        }
+        io::Error::new(err.io_kind, err)
+    }
+}
+
+impl From<HuffmanTreeError> for BitstreamError {
+    fn from(err: HuffmanTreeError) -> Self {
+        BitstreamError::new(BitstreamErrorKind::InvalidCode, err.to_string())
+    }
+}
+
+/// Adds context to the error of a failed `Result`,
+/// converting it to a [`BitstreamError`] along the way.
+pub trait ErrorContext<T> {
+    /// Records the bit offset the failure happened at,
+    /// as with [`BitstreamError::at`]
+    fn at_bit(self, offset: u64) -> Result<T, BitstreamError>;
+
+    /// Records the name of the field which failed,
+    /// as with [`BitstreamError::in_field`]
+    fn field<F>(self, field: F) -> Result<T, BitstreamError>
+    where
+        F: Into<Cow<'static, str>>;
+}
+
+impl<T, E: Into<BitstreamError>> ErrorContext<T> for Result<T, E> {
+    #[inline]
+    fn at_bit(self, offset: u64) -> Result<T, BitstreamError> {
+        self.map_err(|err| err.into().at(offset))
+    }
+
+    #[inline]
+    fn field<F>(self, field: F) -> Result<T, BitstreamError>
+    where
+        F: Into<Cow<'static, str>>,
+    {
+        self.map_err(|err| err.into().in_field(field))
+    }
+}
+
+/// Returns an I/O error carrying a `BitstreamError` of the given kind
+#[inline]
+pub(crate) fn io_error(kind: BitstreamErrorKind, message: &'static str) -> io::Error {
+    BitstreamError::new(kind, message).into()
+}
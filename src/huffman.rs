@@ -13,8 +13,12 @@
 
 use super::BitQueue;
 use super::Endianness;
-use std::collections::BTreeMap;
+use super::{BitRead, BitWrite};
+use std::cmp::{min, Reverse};
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
 use std::fmt;
+use std::hash::Hash;
+use std::io;
 use std::marker::PhantomData;
 
 /// A compiled Huffman tree element for use with the `read_huffman` method.
@@ -27,11 +31,19 @@ use std::marker::PhantomData;
 /// in the compiled tree.  If symbols require a nontrivial amount of space,
 /// consider using reference counting so that they may be cloned
 /// more efficiently.
+///
+/// New kinds of elements may be added in later releases,
+/// so matches on this enum need a wildcard arm.
+#[non_exhaustive]
 pub enum ReadHuffmanTree<E: Endianness, T: Clone> {
     /// The final value and new reader state
     Done(T, u8, u32, PhantomData<E>),
     /// Another byte is necessary to determine final value
     Continue(Box<[ReadHuffmanTree<E, T>]>),
+    /// No symbol is assigned to the bits read so far,
+    /// which are given along with the new reader state.
+    /// Only found in trees from `compile_incomplete_read_tree`.
+    Unassigned(Box<[u8]>, u8, u32, PhantomData<E>),
     /// An invalid reader state has been used
     InvalidState,
 }
@@ -81,27 +93,76 @@ where
     E: Endianness,
     T: Clone,
 {
-    let tree = FinalHuffmanTree::new(values)?;
+    compile_final_tree(FinalHuffmanTree::new(values, false)?)
+}
 
+/// Given a vector of symbol/code pairs, compiles a Huffman tree
+/// for reading which need not assign every possible code a symbol.
+///
+/// This is otherwise the same as [`compile_read_tree`],
+/// but reading a code with no symbol assigned to it fails
+/// with an error of kind `InvalidData` once its bits have been read.
+///
+/// ## Example
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use tokio_bitstream_io::{BigEndian, BitReader, BitRead, HuffmanRead};
+/// use tokio_bitstream_io::huffman::compile_incomplete_read_tree;
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let tree = compile_incomplete_read_tree(
+///     vec![('a', vec![0]),
+///          ('b', vec![1, 0])]).unwrap();
+/// let data = [0b0_10_11_0_10];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'a');
+/// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'b');
+/// let err = reader.read_huffman(&tree).await.unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidData);
+/// assert_eq!(err.to_string(), "no Huffman code for bits 11");
+/// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'a');
+/// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'b');
+/// # });
+/// ```
+pub fn compile_incomplete_read_tree<E, T>(
+    values: Vec<(T, Vec<u8>)>,
+) -> Result<Box<[ReadHuffmanTree<E, T>]>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Clone,
+{
+    compile_final_tree(FinalHuffmanTree::new(values, true)?)
+}
+
+fn compile_final_tree<E, T>(
+    tree: FinalHuffmanTree<T>,
+) -> Result<Box<[ReadHuffmanTree<E, T>]>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Clone,
+{
     let mut result = Vec::with_capacity(256);
     result.extend((0..256).map(|_| ReadHuffmanTree::InvalidState));
+    let mut prefix = Vec::new();
     let queue = BitQueue::from_value(0, 0);
     let i = queue.to_state();
-    result[i] = compile_queue(queue, &tree);
+    result[i] = compile_queue(queue, &tree, &mut prefix);
     for bits in 1..8 {
         for value in 0..(1 << bits) {
             let queue = BitQueue::from_value(value, bits);
             let i = queue.to_state();
-            result[i] = compile_queue(queue, &tree);
+            result[i] = compile_queue(queue, &tree, &mut prefix);
         }
     }
     assert_eq!(result.len(), 256);
     Ok(result.into_boxed_slice())
 }
 
+/// Compiles the entry for the given reader state at a node of the tree,
+/// where `prefix` holds the bits leading to that node
 fn compile_queue<E, T>(
     mut queue: BitQueue<E, u8>,
     tree: &FinalHuffmanTree<T>,
+    prefix: &mut Vec<u8>,
 ) -> ReadHuffmanTree<E, T>
 where
     E: Endianness,
@@ -112,38 +173,262 @@ where
             let len = queue.len();
             ReadHuffmanTree::Done(value.clone(), queue.value(), len, PhantomData)
         }
+        FinalHuffmanTree::Unassigned => {
+            let len = queue.len();
+            ReadHuffmanTree::Unassigned(
+                prefix.clone().into_boxed_slice(),
+                queue.value(),
+                len,
+                PhantomData,
+            )
+        }
         FinalHuffmanTree::Tree(ref bit0, ref bit1) => {
             if queue.is_empty() {
                 ReadHuffmanTree::Continue(
                     (0..256)
-                        .map(|byte| compile_queue(BitQueue::from_value(byte as u8, 8), tree))
+                        .map(|byte| {
+                            compile_queue(BitQueue::from_value(byte as u8, 8), tree, prefix)
+                        })
                         .collect::<Vec<ReadHuffmanTree<E, T>>>()
                         .into_boxed_slice(),
                 )
-            } else if queue.pop(1) == 0 {
-                compile_queue(queue, bit0)
             } else {
-                compile_queue(queue, bit1)
+                let bit = queue.pop(1);
+                prefix.push(bit);
+                let compiled = compile_queue(queue, if bit == 0 { bit0 } else { bit1 }, prefix);
+                prefix.pop();
+                compiled
+            }
+        }
+    }
+}
+
+/// The largest table width accepted by [`widen_read_tree`]
+pub const MAX_TABLE_WIDTH: u32 = 16;
+
+/// Builds a primary lookup table for a compiled Huffman tree
+/// so that codes of up to `width` bits may be decoded
+/// with a single lookup by
+/// [`HuffmanRead::read_huffman_wide`](crate::HuffmanRead::read_huffman_wide).
+///
+/// Without one, a `BitReader` walks through the tree a byte at a time,
+/// which costs an additional lookup and read for every byte a code
+/// spans beyond the bits left over from the previous read.
+/// With one, the reader looks ahead `width` bits in its buffer
+/// and decodes any code no longer than that at once,
+/// falling back to the regular tree for longer codes
+/// or when too few bits remain in the stream to fill the table.
+///
+/// Widths of 9 to 12 bits suit most formats, such as DEFLATE or JPEG,
+/// where almost all codes fit in the table while the table itself
+/// (which has `2 ^ width` entries) stays small.
+/// The tree may come from any of this module's `compile_*` functions,
+/// and is kept in the returned table for those longer codes,
+/// so it reads exactly the same values with or without the table.
+///
+/// Returns [`HuffmanTreeError::InvalidTableWidth`] if `width`
+/// is 0 or larger than [`MAX_TABLE_WIDTH`].
+///
+/// ## Example
+/// ```
+/// use std::io::Cursor;
+/// use tokio_bitstream_io::{BigEndian, BitReader, HuffmanRead};
+/// use tokio_bitstream_io::huffman::{compile_read_tree, widen_read_tree};
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let tree = compile_read_tree(
+///     vec![('a', vec![0]),
+///          ('b', vec![1, 0]),
+///          ('c', vec![1, 1, 0]),
+///          ('d', vec![1, 1, 1])]).unwrap();
+/// let table = widen_read_tree(tree, 9).unwrap();
+/// let data = [0b10110111];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 'b');
+/// assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 'c');
+/// assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 'd');
+/// # });
+/// ```
+pub fn widen_read_tree<E, T>(
+    tree: Box<[ReadHuffmanTree<E, T>]>,
+    width: u32,
+) -> Result<WideHuffmanTable<E, T>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Clone,
+{
+    if width == 0 || width > MAX_TABLE_WIDTH {
+        return Err(HuffmanTreeError::InvalidTableWidth);
+    }
+    let entries = (0..(1u32 << width))
+        .map(|index| wide_entry(&tree, BitQueue::from_value(index, width)))
+        .collect();
+    let mut first = BitQueue::<E, u8>::from_value(0b10, 2);
+    Ok(WideHuffmanTable {
+        width,
+        msb_first: first.pop(1) == 1,
+        entries,
+        tree,
+    })
+}
+
+/// Determines the code at the start of the given bits
+/// by walking the compiled tree from an empty reader state
+fn wide_entry<E, T>(tree: &[ReadHuffmanTree<E, T>], mut bits: BitQueue<E, u32>) -> WideEntry<T>
+where
+    E: Endianness,
+    T: Clone,
+{
+    let width = bits.len();
+    let mut read = 0;
+    let mut node = &tree[BitQueue::<E, u8>::new().to_state()];
+    loop {
+        match node {
+            ReadHuffmanTree::Done(ref value, _, ref queue_bits, _) => {
+                let len = read - queue_bits;
+                return if len <= width {
+                    WideEntry::Done(value.clone(), len)
+                } else {
+                    WideEntry::Long
+                };
+            }
+            ReadHuffmanTree::Unassigned(ref prefix, _, ref queue_bits, _) => {
+                let len = read - queue_bits;
+                return if len <= width {
+                    WideEntry::Unassigned(prefix.clone(), len)
+                } else {
+                    WideEntry::Long
+                };
+            }
+            ReadHuffmanTree::Continue(ref next) if !bits.is_empty() => {
+                // pad a partial final byte with zeros,
+                // which only matter if the code turns out
+                // to be longer than the table
+                let available = min(bits.len(), 8);
+                let mut byte = BitQueue::<E, u8>::from_value(bits.pop(available) as u8, available);
+                if available < 8 {
+                    byte.push(8 - available, 0);
+                }
+                read += 8;
+                node = &next[byte.value() as usize];
+            }
+            _ => return WideEntry::Long,
+        }
+    }
+}
+
+/// A primary lookup table for decoding Huffman codes
+/// several bits at a time, along with the compiled tree
+/// it falls back to for longer codes.
+/// Returned by [`widen_read_tree`] for use with
+/// the `read_huffman_wide` method.
+pub struct WideHuffmanTable<E: Endianness, T: Clone> {
+    width: u32,
+    // whether the stream's first bit is the most significant
+    // so that the table can be indexed without going through BitQueue
+    msb_first: bool,
+    entries: Box<[WideEntry<T>]>,
+    tree: Box<[ReadHuffmanTree<E, T>]>,
+}
+
+impl<E: Endianness, T: Clone> WideHuffmanTable<E, T> {
+    /// Returns the number of bits looked up at once
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the compiled tree used for codes longer than the table
+    #[inline]
+    pub fn tree(&self) -> &[ReadHuffmanTree<E, T>] {
+        &self.tree
+    }
+
+    /// Discards the table, returning the compiled tree
+    #[inline]
+    pub fn into_tree(self) -> Box<[ReadHuffmanTree<E, T>]> {
+        self.tree
+    }
+
+    /// Given the reader's queued bits followed by enough whole bytes
+    /// to fill the table, returns the window of those bits
+    #[inline]
+    pub(crate) fn window(&self, queue_val: u8, queue_bits: u32, bytes: &[u8]) -> Window {
+        let mut bits = u64::from(queue_val);
+        let mut len = queue_bits;
+        for byte in bytes {
+            if self.msb_first {
+                bits = (bits << 8) | u64::from(*byte);
+            } else {
+                bits |= u64::from(*byte) << len;
             }
+            len += 8;
         }
+        Window { bits, len }
+    }
+
+    /// Returns the code at the start of the window
+    #[inline]
+    pub(crate) fn entry(&self, window: &Window) -> &WideEntry<T> {
+        let index = if self.msb_first {
+            window.bits >> (window.len - self.width)
+        } else {
+            window.bits & ((1 << self.width) - 1)
+        };
+        &self.entries[index as usize]
+    }
+
+    /// Removes the given number of bits from the start of the window,
+    /// returning the number of whole bytes left over along with
+    /// the value and length of the partial byte which precedes them
+    #[inline]
+    pub(crate) fn consume(&self, window: &Window, bits: u32) -> (usize, u8, u32) {
+        let left = window.len - bits;
+        let (whole, partial) = (left / 8, left % 8);
+        let value = if self.msb_first {
+            (window.bits >> (whole * 8)) & ((1 << partial) - 1)
+        } else {
+            (window.bits >> bits) & ((1 << partial) - 1)
+        };
+        (whole as usize, value as u8, partial)
     }
 }
 
-// A complete Huffman tree with no empty nodes
+/// Upcoming bits in a stream, in the order of its endianness
+pub(crate) struct Window {
+    bits: u64,
+    len: u32,
+}
+
+/// The code found at the start of a table's worth of bits
+pub(crate) enum WideEntry<T> {
+    /// The symbol and the length of its code
+    Done(T, u32),
+    /// Bits with no symbol assigned to them, and their length
+    Unassigned(Box<[u8]>, u32),
+    /// The code is longer than the table
+    Long,
+}
+
+// A finished Huffman tree whose only empty nodes
+// are those explicitly allowed to be unassigned
 enum FinalHuffmanTree<T: Clone> {
     Leaf(T),
+    Unassigned,
     Tree(Box<FinalHuffmanTree<T>>, Box<FinalHuffmanTree<T>>),
 }
 
 impl<T: Clone> FinalHuffmanTree<T> {
-    fn new(values: Vec<(T, Vec<u8>)>) -> Result<FinalHuffmanTree<T>, HuffmanTreeError> {
+    fn new(
+        values: Vec<(T, Vec<u8>)>,
+        incomplete: bool,
+    ) -> Result<FinalHuffmanTree<T>, HuffmanTreeError> {
         let mut tree = WipHuffmanTree::new_empty();
 
         for (symbol, code) in values {
             tree.add(code.as_slice(), symbol)?;
         }
 
-        tree.into_read_tree()
+        tree.into_read_tree(incomplete)
     }
 }
 
@@ -170,13 +455,14 @@ impl<T: Clone> WipHuffmanTree<T> {
         WipHuffmanTree::Tree(Box::new(Self::new_empty()), Box::new(Self::new_empty()))
     }
 
-    fn into_read_tree(self) -> Result<FinalHuffmanTree<T>, HuffmanTreeError> {
+    fn into_read_tree(self, incomplete: bool) -> Result<FinalHuffmanTree<T>, HuffmanTreeError> {
         match self {
+            WipHuffmanTree::Empty if incomplete => Ok(FinalHuffmanTree::Unassigned),
             WipHuffmanTree::Empty => Err(HuffmanTreeError::MissingLeaf),
             WipHuffmanTree::Leaf(v) => Ok(FinalHuffmanTree::Leaf(v)),
             WipHuffmanTree::Tree(zero, one) => {
-                let zero = zero.into_read_tree()?;
-                let one = one.into_read_tree()?;
+                let zero = zero.into_read_tree(incomplete)?;
+                let one = one.into_read_tree(incomplete)?;
                 Ok(FinalHuffmanTree::Tree(Box::new(zero), Box::new(one)))
             }
         }
@@ -224,6 +510,13 @@ pub enum HuffmanTreeError {
     DuplicateLeaf,
     /// A Huffman code is the prefix of some longer code
     OrphanedLeaf,
+    /// There are too many code lengths too short
+    /// for each to be given a distinct code
+    OverSubscribed,
+    /// The code lengths leave some codes without a symbol
+    Incomplete,
+    /// A lookup table's width is 0 or too large
+    InvalidTableWidth,
 }
 
 impl fmt::Display for HuffmanTreeError {
@@ -233,6 +526,9 @@ impl fmt::Display for HuffmanTreeError {
             HuffmanTreeError::MissingLeaf => write!(f, "missing leaf node in specification"),
             HuffmanTreeError::DuplicateLeaf => write!(f, "duplicate leaf node in specification"),
             HuffmanTreeError::OrphanedLeaf => write!(f, "orphaned leaf node in specification"),
+            HuffmanTreeError::OverSubscribed => write!(f, "over-subscribed code lengths"),
+            HuffmanTreeError::Incomplete => write!(f, "incomplete code lengths"),
+            HuffmanTreeError::InvalidTableWidth => write!(f, "invalid lookup table width"),
         }
     }
 }
@@ -289,21 +585,8 @@ where
     let mut map = BTreeMap::new();
 
     for (symbol, code) in values {
-        let mut encoded = Vec::new();
-        for bits in code.chunks(32) {
-            let mut acc = BitQueue::<E, u32>::new();
-            for bit in bits {
-                match *bit {
-                    0 => acc.push(1, 0),
-                    1 => acc.push(1, 1),
-                    _ => return Err(HuffmanTreeError::InvalidBit),
-                }
-            }
-            let len = acc.len();
-            encoded.push((len, acc.value()))
-        }
-        map.entry(symbol)
-            .or_insert_with(|| encoded.into_boxed_slice());
+        let encoded = encode_code::<E>(&code)?;
+        map.entry(symbol).or_insert(encoded);
     }
 
     Ok(WriteHuffmanTree {
@@ -312,6 +595,42 @@ where
     })
 }
 
+/// A code as (bits, value) pairs for writing
+type EncodedCode = Box<[(u32, u32)]>;
+
+/// Converts a code into (bits, value) pairs for writing
+fn encode_code<E: Endianness>(code: &[u8]) -> Result<EncodedCode, HuffmanTreeError> {
+    let mut encoded = Vec::new();
+    for bits in code.chunks(32) {
+        let mut acc = BitQueue::<E, u32>::new();
+        for bit in bits {
+            match *bit {
+                0 => acc.push(1, 0),
+                1 => acc.push(1, 1),
+                _ => return Err(HuffmanTreeError::InvalidBit),
+            }
+        }
+        let len = acc.len();
+        encoded.push((len, acc.value()))
+    }
+    Ok(encoded.into_boxed_slice())
+}
+
+/// A compiled Huffman tree for use with the `write_huffman` method,
+/// which looks up the code to write for a given symbol.
+///
+/// [`WriteHuffmanTree`] works with any `Ord` symbols,
+/// [`DenseWriteHuffmanTree`] with small integer symbols
+/// and [`HashWriteHuffmanTree`] with any `Hash` symbols.
+pub trait WriteHuffmanCodes<E: Endianness> {
+    /// The type of symbol the tree holds codes for
+    type Symbol: ?Sized;
+
+    /// Returns the code for the given symbol as (bits, value) pairs
+    /// to be written in order, or `None` if the symbol has no code
+    fn get_code(&self, symbol: &Self::Symbol) -> Option<&[(u32, u32)]>;
+}
+
 /// A compiled Huffman tree for use with the `write_huffman` method.
 /// Returned by `compiled_write_tree`.
 pub struct WriteHuffmanTree<E: Endianness, T: Ord> {
@@ -333,4 +652,961 @@ impl<E: Endianness, T: Ord + Clone> WriteHuffmanTree<E, T> {
     pub fn get(&self, symbol: &T) -> impl Iterator<Item = &(u32, u32)> {
         self.map[symbol].iter()
     }
+
+    /// Returns the table of symbols and codes the tree was compiled from
+    ///
+    /// ## Example
+    /// ```
+    /// use tokio_bitstream_io::BigEndian;
+    /// use tokio_bitstream_io::huffman::{compile_write_tree, HuffmanTable};
+    /// let codes = vec![('a', vec![0]), ('b', vec![1, 0]), ('c', vec![1, 1])];
+    /// let tree = compile_write_tree::<BigEndian, char>(codes.clone()).unwrap();
+    /// assert_eq!(tree.table(), HuffmanTable::new(codes));
+    /// ```
+    pub fn table(&self) -> HuffmanTable<T> {
+        HuffmanTable::new(
+            self.map
+                .iter()
+                .map(|(symbol, encoded)| {
+                    let mut code = Vec::new();
+                    for (len, value) in encoded.iter() {
+                        let mut acc = BitQueue::<E, u32>::from_value(*value, *len);
+                        code.extend((0..*len).map(|_| acc.pop(1) as u8));
+                    }
+                    (symbol.clone(), code)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<E: Endianness, T: Ord> WriteHuffmanCodes<E> for WriteHuffmanTree<E, T> {
+    type Symbol = T;
+
+    #[inline]
+    fn get_code(&self, symbol: &T) -> Option<&[(u32, u32)]> {
+        self.map.get(symbol).map(|code| &code[..])
+    }
+}
+
+/// Given a vector of symbol/code pairs with small integer symbols,
+/// compiles a Huffman tree for writing which finds each symbol's
+/// code by indexing an array rather than searching a map.
+///
+/// This suits dense alphabets such as DEFLATE's literal/length codes
+/// from 0 to 285.  The array has an entry for every symbol from 0
+/// to the largest one given, so symbols should be kept small.
+/// As with [`compile_write_tree`], if a symbol occurs more than once
+/// only its first code is used.
+///
+/// ## Example
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// use tokio_bitstream_io::{BigEndian, BitWriter, HuffmanWrite};
+/// use tokio_bitstream_io::huffman::compile_dense_write_tree;
+/// let tree = compile_dense_write_tree(
+///     vec![(0u16, vec![0]),
+///          (1u16, vec![1, 0]),
+///          (2u16, vec![1, 1, 0]),
+///          (3u16, vec![1, 1, 1])]).unwrap();
+/// let mut data = Vec::new();
+/// {
+///     let mut writer = BitWriter::endian(&mut data, BigEndian);
+///     writer.write_huffman(&tree, 1u16).await.unwrap();
+///     writer.write_huffman(&tree, 2u16).await.unwrap();
+///     writer.write_huffman(&tree, 3u16).await.unwrap();
+/// }
+/// assert_eq!(data, [0b10110111]);
+/// # });
+/// ```
+pub fn compile_dense_write_tree<E, T>(
+    values: Vec<(T, Vec<u8>)>,
+) -> Result<DenseWriteHuffmanTree<E, T>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Copy + Into<usize>,
+{
+    let mut codes: Vec<Option<EncodedCode>> = Vec::new();
+
+    for (symbol, code) in values {
+        let encoded = encode_code::<E>(&code)?;
+        let index = symbol.into();
+        if index >= codes.len() {
+            codes.resize(index + 1, None);
+        }
+        codes[index].get_or_insert(encoded);
+    }
+
+    Ok(DenseWriteHuffmanTree {
+        codes: codes.into_boxed_slice(),
+        phantom: PhantomData,
+    })
+}
+
+/// A compiled Huffman tree for use with the `write_huffman` method
+/// whose symbols are small integers.
+/// Returned by `compile_dense_write_tree`.
+pub struct DenseWriteHuffmanTree<E: Endianness, T> {
+    codes: Box<[Option<EncodedCode>]>,
+    phantom: PhantomData<(E, T)>,
+}
+
+impl<E: Endianness, T: Copy + Into<usize>> DenseWriteHuffmanTree<E, T> {
+    /// Returns true if symbol is in tree.
+    #[inline]
+    pub fn has_symbol(&self, symbol: T) -> bool {
+        self.get_code(&symbol).is_some()
+    }
+}
+
+impl<E: Endianness, T: Copy + Into<usize>> WriteHuffmanCodes<E> for DenseWriteHuffmanTree<E, T> {
+    type Symbol = T;
+
+    #[inline]
+    fn get_code(&self, symbol: &T) -> Option<&[(u32, u32)]> {
+        match self.codes.get((*symbol).into()) {
+            Some(Some(code)) => Some(code),
+            _ => None,
+        }
+    }
+}
+
+/// Given a vector of symbol/code pairs, compiles a Huffman tree
+/// for writing which finds each symbol's code in a hash map.
+///
+/// Unlike [`compile_write_tree`], symbols need only be `Hash` and `Eq`,
+/// which suits symbols such as `String` tokens.
+/// If a symbol occurs more than once only its first code is used.
+///
+/// ## Example
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// use tokio_bitstream_io::{BigEndian, BitWriter, HuffmanWrite};
+/// use tokio_bitstream_io::huffman::compile_hash_write_tree;
+/// let tree = compile_hash_write_tree(
+///     vec![("the".to_string(), vec![0]),
+///          ("quick".to_string(), vec![1, 0]),
+///          ("brown".to_string(), vec![1, 1, 0]),
+///          ("fox".to_string(), vec![1, 1, 1])]).unwrap();
+/// let mut data = Vec::new();
+/// {
+///     let mut writer = BitWriter::endian(&mut data, BigEndian);
+///     for word in "quick brown fox".split(' ').map(String::from) {
+///         writer.write_huffman(&tree, &word).await.unwrap();
+///     }
+/// }
+/// assert_eq!(data, [0b10110111]);
+/// # });
+/// ```
+pub fn compile_hash_write_tree<E, T>(
+    values: Vec<(T, Vec<u8>)>,
+) -> Result<HashWriteHuffmanTree<E, T>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Hash + Eq,
+{
+    let mut map = HashMap::new();
+
+    for (symbol, code) in values {
+        let encoded = encode_code::<E>(&code)?;
+        map.entry(symbol).or_insert(encoded);
+    }
+
+    Ok(HashWriteHuffmanTree {
+        map,
+        phantom: PhantomData,
+    })
+}
+
+/// A compiled Huffman tree for use with the `write_huffman` method
+/// whose symbols are kept in a hash map.
+/// Returned by `compile_hash_write_tree`.
+pub struct HashWriteHuffmanTree<E: Endianness, T: Hash + Eq> {
+    map: HashMap<T, EncodedCode>,
+    phantom: PhantomData<E>,
+}
+
+impl<E: Endianness, T: Hash + Eq> HashWriteHuffmanTree<E, T> {
+    /// Returns true if symbol is in tree.
+    #[inline]
+    pub fn has_symbol(&self, symbol: &T) -> bool {
+        self.map.contains_key(symbol)
+    }
+}
+
+impl<E: Endianness, T: Hash + Eq> WriteHuffmanCodes<E> for HashWriteHuffmanTree<E, T> {
+    type Symbol = T;
+
+    #[inline]
+    fn get_code(&self, symbol: &T) -> Option<&[(u32, u32)]> {
+        self.map.get(symbol).map(|code| &code[..])
+    }
+}
+
+/// Given a vector of symbol/code length pairs,
+/// assigns each symbol its canonical Huffman code,
+/// as used by DEFLATE, JPEG, Brotli and others.
+///
+/// Shorter codes come first, and symbols with codes of the same length
+/// are given consecutive codes in the order they are listed.
+/// Symbols with a length of 0 are unused and are left out.
+///
+/// The codes are returned as lists of bits for
+/// [`compile_read_tree`] or [`compile_write_tree`],
+/// with each code's most-significant bit first.
+///
+/// # Errors
+///
+/// Returns [`HuffmanTreeError::OverSubscribed`] if there are
+/// more short codes than there is room for, and
+/// [`HuffmanTreeError::Incomplete`] if some codes are left unassigned.
+/// As in DEFLATE, a single symbol of length 1 is allowed
+/// even though it leaves the code `1` unassigned.
+///
+/// ## Example
+/// ```
+/// use tokio_bitstream_io::huffman::canonical_codes;
+/// assert_eq!(
+///     canonical_codes(vec![('a', 2), ('b', 1), ('c', 3), ('d', 3), ('e', 0)]).unwrap(),
+///     vec![('b', vec![0]), ('a', vec![1, 0]), ('c', vec![1, 1, 0]), ('d', vec![1, 1, 1])]
+/// );
+/// ```
+pub fn canonical_codes<T>(lengths: Vec<(T, u32)>) -> Result<Vec<(T, Vec<u8>)>, HuffmanTreeError> {
+    let mut lengths: Vec<(T, u32)> = lengths.into_iter().filter(|(_, len)| *len > 0).collect();
+    // a stable sort keeps symbols of the same length in the order given
+    lengths.sort_by_key(|(_, len)| *len);
+
+    let single = lengths.len() == 1 && lengths[0].1 == 1;
+    let mut codes = Vec::with_capacity(lengths.len());
+    let mut code: Vec<u8> = Vec::new();
+    let mut exhausted = false;
+    for (symbol, len) in lengths {
+        if exhausted {
+            return Err(HuffmanTreeError::OverSubscribed);
+        }
+        code.resize(len as usize, 0);
+        codes.push((symbol, code.clone()));
+        exhausted = !increment_code(&mut code);
+    }
+
+    if exhausted || single {
+        Ok(codes)
+    } else {
+        Err(HuffmanTreeError::Incomplete)
+    }
+}
+
+/// Adds 1 to a code, returning false if it overflows
+fn increment_code(code: &mut [u8]) -> bool {
+    for bit in code.iter_mut().rev() {
+        if *bit == 0 {
+            *bit = 1;
+            return true;
+        }
+        *bit = 0;
+    }
+    false
+}
+
+/// Given a vector of symbol/code length pairs,
+/// compiles a Huffman tree of their canonical codes for reading.
+///
+/// See [`canonical_codes`] for how codes are assigned.
+/// In the case of a single symbol of length 1,
+/// reading the unassigned code `1` fails as it would for
+/// a tree from [`compile_incomplete_read_tree`].
+///
+/// DEFLATE packs each code most-significant bit first
+/// into its least-significant-bit-first stream,
+/// which is why decoders which look up codes from whole integers
+/// bit-reverse them.
+/// Since codes here are read a bit at a time, no reversing is needed;
+/// simply read such streams with [`LittleEndian`](crate::LittleEndian).
+///
+/// ## Example
+/// ```
+/// use std::io::Cursor;
+/// use tokio_bitstream_io::{LittleEndian, BitReader, BitRead, HuffmanRead};
+/// use tokio_bitstream_io::huffman::compile_canonical_read_tree;
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// // DEFLATE's fixed literal/length code
+/// let lengths = (0..288u16).map(|symbol| match symbol {
+///     0..=143 => (symbol, 8),
+///     144..=255 => (symbol, 9),
+///     256..=279 => (symbol, 7),
+///     _ => (symbol, 8),
+/// });
+/// let tree = compile_canonical_read_tree::<LittleEndian, u16>(lengths.collect()).unwrap();
+///
+/// // the raw DEFLATE stream of "a"
+/// let data = [0x4B, 0x04, 0x00];
+/// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+/// assert_eq!(reader.read_bit().await.unwrap(), true);   // final block
+/// assert_eq!(reader.read::<u8>(2).await.unwrap(), 1);   // fixed codes
+/// assert_eq!(reader.read_huffman(&tree).await.unwrap(), u16::from(b'a'));
+/// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 256);  // end of block
+/// # });
+/// ```
+pub fn compile_canonical_read_tree<E, T>(
+    lengths: Vec<(T, u32)>,
+) -> Result<Box<[ReadHuffmanTree<E, T>]>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Clone,
+{
+    let codes = canonical_codes(lengths)?;
+    if codes.len() == 1 {
+        compile_incomplete_read_tree(codes)
+    } else {
+        compile_read_tree(codes)
+    }
+}
+
+/// Given a vector of symbol/code length pairs,
+/// compiles a Huffman tree of their canonical codes for writing.
+///
+/// See [`canonical_codes`] for how codes are assigned
+/// and [`compile_canonical_read_tree`] for a note on DEFLATE.
+///
+/// ## Example
+/// ```
+/// use tokio_bitstream_io::{BigEndian, BitWriter, HuffmanWrite};
+/// use tokio_bitstream_io::huffman::compile_canonical_write_tree;
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let tree = compile_canonical_write_tree::<BigEndian, char>(
+///     vec![('a', 2), ('b', 1), ('c', 3), ('d', 3)]).unwrap();
+/// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+/// for symbol in "abdbb".chars() {
+///     writer.write_huffman(&tree, symbol).await.unwrap();
+/// }
+/// assert_eq!(writer.into_writer(), [0b10_0_111_0_0]);
+/// # });
+/// ```
+pub fn compile_canonical_write_tree<E, T>(
+    lengths: Vec<(T, u32)>,
+) -> Result<WriteHuffmanTree<E, T>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Ord + Clone,
+{
+    compile_write_tree(canonical_codes(lengths)?)
+}
+
+/// Given symbols and how often each occurs,
+/// returns the length of each symbol's code in an optimal Huffman code,
+/// as symbol/code length pairs for [`canonical_codes`] and
+/// [`compile_canonical_read_tree`] or [`compile_canonical_write_tree`].
+///
+/// Frequencies may come from a `HashMap` or any other iterator;
+/// those of symbols listed more than once are added together.
+/// The result is ordered by symbol, and symbols with
+/// a frequency of 0 are given a length of 0, meaning they are unused.
+/// A lone symbol is given a code of length 1.
+///
+/// If `max_length` is given, no code is longer than that
+/// and the lengths are optimal given that limit,
+/// as found by the package-merge algorithm.
+///
+/// # Errors
+///
+/// Returns [`HuffmanTreeError::OverSubscribed`] if there are
+/// more used symbols than codes of the maximum length.
+///
+/// ## Example
+/// ```
+/// use tokio_bitstream_io::huffman::code_lengths;
+/// let frequencies = vec![('a', 45), ('b', 13), ('c', 12), ('d', 16), ('e', 9), ('f', 5), ('g', 0)];
+/// assert_eq!(
+///     code_lengths(frequencies.clone(), None).unwrap(),
+///     vec![('a', 1), ('b', 3), ('c', 3), ('d', 3), ('e', 4), ('f', 4), ('g', 0)]
+/// );
+/// assert_eq!(
+///     code_lengths(frequencies, Some(3)).unwrap(),
+///     vec![('a', 2), ('b', 3), ('c', 3), ('d', 2), ('e', 3), ('f', 3), ('g', 0)]
+/// );
+/// ```
+pub fn code_lengths<T, I>(
+    frequencies: I,
+    max_length: Option<u32>,
+) -> Result<Vec<(T, u32)>, HuffmanTreeError>
+where
+    T: Ord,
+    I: IntoIterator<Item = (T, u64)>,
+{
+    let mut totals: BTreeMap<T, u64> = BTreeMap::new();
+    for (symbol, frequency) in frequencies {
+        *totals.entry(symbol).or_insert(0) += frequency;
+    }
+    let weights: Vec<u64> = totals.values().copied().filter(|w| *w > 0).collect();
+
+    let mut lengths = match weights.len() {
+        0 => Vec::new(),
+        1 => vec![1],
+        _ => huffman_lengths(&weights),
+    };
+    if let Some(max_length) = max_length {
+        let room = match max_length {
+            0 => 0,
+            1..=63 => 1 << max_length,
+            _ => u64::MAX,
+        };
+        if weights.len() as u64 > room {
+            return Err(HuffmanTreeError::OverSubscribed);
+        }
+        if lengths.iter().any(|len| *len > max_length) {
+            lengths = package_merge_lengths(&weights, max_length);
+        }
+    }
+
+    let mut lengths = lengths.into_iter();
+    Ok(totals
+        .into_iter()
+        .map(|(symbol, frequency)| match frequency {
+            0 => (symbol, 0),
+            _ => (symbol, lengths.next().unwrap()),
+        })
+        .collect())
+}
+
+/// Returns the code lengths of an unlimited Huffman code
+/// for two or more nonzero weights
+fn huffman_lengths(weights: &[u64]) -> Vec<u32> {
+    // nodes are the leaves followed by the internal nodes
+    // in the order they are made, each with the index of its parent
+    let mut parents = vec![0; weights.len() * 2 - 1];
+    let mut queue: BinaryHeap<Reverse<(u128, usize)>> = weights
+        .iter()
+        .enumerate()
+        .map(|(i, w)| Reverse((u128::from(*w), i)))
+        .collect();
+    let mut next = weights.len();
+    while let (Some(Reverse((w0, n0))), Some(Reverse((w1, n1)))) = (queue.pop(), queue.pop()) {
+        parents[n0] = next;
+        parents[n1] = next;
+        queue.push(Reverse((w0 + w1, next)));
+        next += 1;
+    }
+
+    // since parents come after their children,
+    // depths can be filled in from the root down
+    let mut depths = vec![0; parents.len()];
+    for node in (0..parents.len() - 1).rev() {
+        depths[node] = depths[parents[node]] + 1;
+    }
+    depths.truncate(weights.len());
+    depths
+}
+
+/// Returns the code lengths of an optimal code no longer than
+/// `max_length` for two or more nonzero weights,
+/// where there are no more than `1 << max_length` of them
+fn package_merge_lengths(weights: &[u64], max_length: u32) -> Vec<u32> {
+    #[derive(Copy, Clone)]
+    enum Item {
+        Leaf(usize),
+        // the first of a pair of items in the next deeper list
+        Package(usize),
+    }
+
+    let mut leaves: Vec<(u128, Item)> = weights
+        .iter()
+        .enumerate()
+        .map(|(i, w)| (u128::from(*w), Item::Leaf(i)))
+        .collect();
+    leaves.sort_by_key(|(w, _)| *w);
+
+    // lists[0] is the deepest, for codes of length max_length
+    let mut lists: Vec<Vec<(u128, Item)>> = vec![leaves.clone()];
+    for _ in 1..max_length {
+        let deeper = lists.last().unwrap();
+        let packages = deeper
+            .chunks_exact(2)
+            .enumerate()
+            .map(|(i, pair)| (pair[0].0 + pair[1].0, Item::Package(i * 2)));
+
+        // merge the packages with the leaves, leaves first on ties
+        let mut merged = Vec::with_capacity(leaves.len() + deeper.len() / 2);
+        let mut leaves_iter = leaves.iter().copied().peekable();
+        for package in packages {
+            while let Some(leaf) = leaves_iter.next_if(|leaf| leaf.0 <= package.0) {
+                merged.push(leaf);
+            }
+            merged.push(package);
+        }
+        merged.extend(leaves_iter);
+        lists.push(merged);
+    }
+
+    // each time a leaf occurs among the first 2n - 2 items
+    // of the shallowest list, its code grows by 1 bit
+    let mut lengths = vec![0; weights.len()];
+    let shallowest = lists.len() - 1;
+    let mut stack: Vec<(usize, Item)> = lists[shallowest][..weights.len() * 2 - 2]
+        .iter()
+        .map(|(_, item)| (shallowest, *item))
+        .collect();
+    while let Some((list, item)) = stack.pop() {
+        match item {
+            Item::Leaf(i) => lengths[i] += 1,
+            Item::Package(i) => {
+                stack.push((list - 1, lists[list - 1][i].1));
+                stack.push((list - 1, lists[list - 1][i + 1].1));
+            }
+        }
+    }
+    lengths
+}
+
+/// Given symbols and how often each occurs, returns the
+/// canonical codes of an optimal Huffman code as symbol/code pairs
+/// for [`compile_read_tree`] or [`compile_write_tree`].
+///
+/// See [`code_lengths`] for how the code is built
+/// and [`canonical_codes`] for how codes are assigned.
+///
+/// ## Example
+/// ```
+/// use tokio_bitstream_io::huffman::optimal_codes;
+/// assert_eq!(
+///     optimal_codes(vec![('a', 10), ('b', 1), ('c', 1)], None).unwrap(),
+///     vec![('a', vec![0]), ('b', vec![1, 0]), ('c', vec![1, 1])]
+/// );
+/// ```
+pub fn optimal_codes<T, I>(
+    frequencies: I,
+    max_length: Option<u32>,
+) -> Result<Vec<(T, Vec<u8>)>, HuffmanTreeError>
+where
+    T: Ord,
+    I: IntoIterator<Item = (T, u64)>,
+{
+    canonical_codes(code_lengths(frequencies, max_length)?)
+}
+
+/// Given symbols and how often each occurs,
+/// compiles a Huffman tree of an optimal code for reading.
+///
+/// See [`code_lengths`] for how the code is built.
+/// Since a read tree must cover every possible code,
+/// at least one symbol must have a nonzero frequency.
+pub fn compile_optimal_read_tree<E, T, I>(
+    frequencies: I,
+    max_length: Option<u32>,
+) -> Result<Box<[ReadHuffmanTree<E, T>]>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Ord + Clone,
+    I: IntoIterator<Item = (T, u64)>,
+{
+    compile_canonical_read_tree(code_lengths(frequencies, max_length)?)
+}
+
+/// Given symbols and how often each occurs,
+/// compiles a Huffman tree of an optimal code for writing.
+///
+/// See [`code_lengths`] for how the code is built.
+///
+/// ## Example
+/// ```
+/// use std::collections::HashMap;
+/// use std::io::Cursor;
+/// use tokio_bitstream_io::{BigEndian, BitReader, BitWriter, BitWrite, HuffmanRead, HuffmanWrite};
+/// use tokio_bitstream_io::huffman::{compile_optimal_read_tree, compile_optimal_write_tree};
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let message = "abracadabra";
+/// let mut frequencies = HashMap::new();
+/// for c in message.chars() {
+///     *frequencies.entry(c).or_insert(0) += 1;
+/// }
+///
+/// let tree = compile_optimal_write_tree::<BigEndian, char, _>(frequencies.clone(), Some(3)).unwrap();
+/// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+/// for c in message.chars() {
+///     writer.write_huffman(&tree, c).await.unwrap();
+/// }
+/// assert_eq!(writer.bits_written(), 23);
+/// writer.byte_align().await.unwrap();
+/// let data = writer.into_writer();
+///
+/// let tree = compile_optimal_read_tree::<BigEndian, char, _>(frequencies, Some(3)).unwrap();
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// for c in message.chars() {
+///     assert_eq!(reader.read_huffman(&tree).await.unwrap(), c);
+/// }
+/// # });
+/// ```
+pub fn compile_optimal_write_tree<E, T, I>(
+    frequencies: I,
+    max_length: Option<u32>,
+) -> Result<WriteHuffmanTree<E, T>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Ord + Clone,
+    I: IntoIterator<Item = (T, u64)>,
+{
+    compile_canonical_write_tree(code_lengths(frequencies, max_length)?)
+}
+
+/// The symbols and codes of a Huffman tree
+/// in a form which may be stored or transmitted
+/// and compiled back into trees for reading or writing.
+///
+/// A table can be taken from an existing tree with
+/// [`HuffmanTable::from_read_tree`] or [`WriteHuffmanTree::table`].
+/// Tables of canonical codes may be written to a stream in a compact form
+/// with [`HuffmanTable::write_lengths`], and with the `serde` feature
+/// any table may be serialized as its list of symbols and codes.
+///
+/// Codes are kept in order, so two tables with the same
+/// symbols and codes are equal however they were built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HuffmanTable<T> {
+    codes: Vec<(T, Vec<u8>)>,
+}
+
+/// The longest code length [`HuffmanTable::write_lengths`] can store
+pub const MAX_STORED_LENGTH: u32 = 15;
+
+/// The order in which the lengths of the code length code are stored,
+/// most likely to be used first, as in DEFLATE
+const LENGTH_CODE_ORDER: [u8; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+impl<T> HuffmanTable<T> {
+    /// Builds a table from a vector of symbol/code pairs,
+    /// such as would be given to [`compile_read_tree`]
+    pub fn new(mut codes: Vec<(T, Vec<u8>)>) -> Self {
+        codes.sort_by(|(_, a), (_, b)| a.cmp(b));
+        HuffmanTable { codes }
+    }
+
+    /// Builds a table of canonical codes from a vector of
+    /// symbol/code length pairs, as assigned by [`canonical_codes`]
+    pub fn from_lengths(lengths: Vec<(T, u32)>) -> Result<Self, HuffmanTreeError> {
+        canonical_codes(lengths).map(HuffmanTable::new)
+    }
+
+    /// Returns the table's symbol/code pairs in order of their codes
+    #[inline]
+    pub fn codes(&self) -> &[(T, Vec<u8>)] {
+        &self.codes
+    }
+
+    /// Returns the table's symbol/code pairs in order of their codes
+    #[inline]
+    pub fn into_codes(self) -> Vec<(T, Vec<u8>)> {
+        self.codes
+    }
+}
+
+impl<T: Clone> HuffmanTable<T> {
+    /// Recovers the table of symbols and codes from a compiled read tree.
+    /// Unassigned codes in incomplete trees are left out.
+    ///
+    /// ## Example
+    /// ```
+    /// use tokio_bitstream_io::LittleEndian;
+    /// use tokio_bitstream_io::huffman::{compile_read_tree, HuffmanTable};
+    /// let codes = vec![('a', vec![0]), ('b', vec![1, 0]), ('c', vec![1, 1])];
+    /// let tree = compile_read_tree::<LittleEndian, char>(codes.clone()).unwrap();
+    /// assert_eq!(HuffmanTable::from_read_tree(&tree), HuffmanTable::new(codes));
+    /// ```
+    pub fn from_read_tree<E: Endianness>(tree: &[ReadHuffmanTree<E, T>]) -> Self {
+        let mut codes = BTreeMap::new();
+        match tree.get(BitQueue::<E, u8>::new().to_state()) {
+            Some(ReadHuffmanTree::Done(ref value, ..)) => {
+                codes.insert(Vec::new(), value.clone());
+            }
+            Some(ReadHuffmanTree::Continue(ref next)) => {
+                collect_codes(next, &mut Vec::new(), &mut codes);
+            }
+            _ => {}
+        }
+        HuffmanTable {
+            codes: codes
+                .into_iter()
+                .map(|(code, symbol)| (symbol, code))
+                .collect(),
+        }
+    }
+
+    /// Compiles the table into a tree for reading,
+    /// as with [`compile_read_tree`]
+    #[inline]
+    pub fn read_tree<E: Endianness>(
+        &self,
+    ) -> Result<Box<[ReadHuffmanTree<E, T>]>, HuffmanTreeError> {
+        compile_read_tree(self.codes.clone())
+    }
+
+    /// Reads a table stored by [`HuffmanTable::write_lengths`]
+    /// for the given alphabet of symbols.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if the stored
+    /// lengths are malformed, are for more symbols than the alphabet has,
+    /// or do not make up a valid canonical code.
+    pub async fn read_lengths<R>(reader: &mut R, alphabet: &[T]) -> io::Result<Self>
+    where
+        R: BitRead + ?Sized,
+        T: Send + Sync,
+    {
+        let lengths = read_code_lengths(reader, alphabet.len()).await?;
+        HuffmanTable::from_lengths(alphabet.iter().cloned().zip(lengths).collect())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+}
+
+impl<T: Ord + Clone> HuffmanTable<T> {
+    /// Compiles the table into a tree for writing,
+    /// as with [`compile_write_tree`]
+    #[inline]
+    pub fn write_tree<E: Endianness>(&self) -> Result<WriteHuffmanTree<E, T>, HuffmanTreeError> {
+        compile_write_tree(self.codes.clone())
+    }
+
+    /// Writes the table's code lengths to the stream
+    /// in a compact form which [`HuffmanTable::read_lengths`]
+    /// turns back into the same table.
+    ///
+    /// The alphabet lists every symbol the table may contain,
+    /// in the order canonical codes are assigned to them,
+    /// and is needed again to read the table back.
+    /// Its lengths are stored much as in a DEFLATE block header:
+    /// runs of lengths are run-length coded and the results
+    /// are themselves Huffman coded, with that code's lengths
+    /// stored in 3 bits each.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` if the table
+    /// has a symbol which is not in the alphabet,
+    /// has a code longer than [`MAX_STORED_LENGTH`],
+    /// or does not hold the canonical codes for its lengths.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{LittleEndian, BitReader, BitWriter, BitWrite};
+    /// use tokio_bitstream_io::huffman::HuffmanTable;
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let alphabet: Vec<u16> = (0..288).collect();
+    /// let table = HuffmanTable::from_lengths(
+    ///     alphabet.iter().map(|symbol| (*symbol, match symbol {
+    ///         0..=143 => 8,
+    ///         144..=255 => 9,
+    ///         256..=279 => 7,
+    ///         _ => 8,
+    ///     })).collect()).unwrap();
+    ///
+    /// let mut data = Vec::new();
+    /// let mut writer = BitWriter::endian(&mut data, LittleEndian);
+    /// table.write_lengths(&mut writer, &alphabet).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// // 288 code lengths take up 25 bytes
+    /// assert_eq!(data.len(), 25);
+    ///
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(HuffmanTable::read_lengths(&mut reader, &alphabet).await.unwrap(), table);
+    /// # });
+    /// ```
+    pub async fn write_lengths<W>(&self, writer: &mut W, alphabet: &[T]) -> io::Result<()>
+    where
+        W: BitWrite + ?Sized,
+        T: Send + Sync,
+    {
+        let indexes: BTreeMap<&T, usize> =
+            alphabet.iter().enumerate().map(|(i, s)| (s, i)).collect();
+        let mut lengths = vec![0; alphabet.len()];
+        for (symbol, code) in self.codes.iter() {
+            let index = *indexes.get(symbol).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "symbol not in alphabet")
+            })?;
+            if code.len() as u32 > MAX_STORED_LENGTH {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "code too long to store",
+                ));
+            }
+            lengths[index] = code.len() as u32;
+        }
+        let canonical =
+            HuffmanTable::from_lengths(alphabet.iter().cloned().zip(lengths.clone()).collect());
+        if canonical.as_ref() != Ok(self) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "codes are not canonical",
+            ));
+        }
+        write_code_lengths(writer, &lengths).await
+    }
+}
+
+/// Gathers the codes below a `Continue` node of a compiled read tree
+fn collect_codes<E, T>(
+    tree: &[ReadHuffmanTree<E, T>],
+    prefix: &mut Vec<u8>,
+    codes: &mut BTreeMap<Vec<u8>, T>,
+) where
+    E: Endianness,
+    T: Clone,
+{
+    for (byte, node) in tree.iter().enumerate() {
+        let consumed = match node {
+            ReadHuffmanTree::Done(_, _, ref queue_bits, _) => 8 - queue_bits,
+            ReadHuffmanTree::Continue(_) => 8,
+            _ => continue,
+        };
+        let mut bits = BitQueue::<E, u8>::from_value(byte as u8, 8);
+        let len = prefix.len();
+        prefix.extend((0..consumed).map(|_| bits.pop(1)));
+        match node {
+            ReadHuffmanTree::Done(ref value, ..) => {
+                codes.entry(prefix.clone()).or_insert_with(|| value.clone());
+            }
+            ReadHuffmanTree::Continue(ref next) => collect_codes(next, prefix, codes),
+            _ => {}
+        }
+        prefix.truncate(len);
+    }
+}
+
+/// Writes code lengths, trimmed of trailing zeros,
+/// as run-length coded symbols of a Huffman code
+async fn write_code_lengths<W: BitWrite + ?Sized>(
+    writer: &mut W,
+    lengths: &[u32],
+) -> io::Result<()> {
+    let count = lengths
+        .iter()
+        .rposition(|len| *len > 0)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    writer.write_leb128(count as u64).await?;
+    if count == 0 {
+        return Ok(());
+    }
+
+    // each run becomes a symbol from 0 to 18 along with
+    // the size and value of its extra bits
+    let mut symbols: Vec<(u8, u32, u32)> = Vec::new();
+    let mut lengths = &lengths[0..count];
+    while let Some(len) = lengths.first().copied() {
+        let run = lengths.iter().take_while(|l| **l == len).count();
+        let mut left = run;
+        if len == 0 {
+            while left >= 11 {
+                let repeat = min(left, 138);
+                symbols.push((18, 7, (repeat - 11) as u32));
+                left -= repeat;
+            }
+            if left >= 3 {
+                symbols.push((17, 3, (left - 3) as u32));
+                left = 0;
+            }
+        } else {
+            symbols.push((len as u8, 0, 0));
+            left -= 1;
+            while left >= 3 {
+                let repeat = min(left, 6);
+                symbols.push((16, 2, (repeat - 3) as u32));
+                left -= repeat;
+            }
+        }
+        symbols.extend((0..left).map(|_| (len as u8, 0, 0)));
+        lengths = &lengths[run..];
+    }
+
+    let mut frequencies = [0u64; 19];
+    for (symbol, _, _) in symbols.iter() {
+        frequencies[*symbol as usize] += 1;
+    }
+    let code_lengths = code_lengths((0..19u8).zip(frequencies.iter().copied()), Some(7))
+        .expect("19 symbols always fit in 7 bits");
+    let stored = LENGTH_CODE_ORDER
+        .iter()
+        .rposition(|symbol| code_lengths[*symbol as usize].1 > 0)
+        .map(|i| i + 1)
+        .unwrap_or(0)
+        .max(4);
+    writer.write(4, (stored - 4) as u32).await?;
+    for symbol in LENGTH_CODE_ORDER[0..stored].iter() {
+        writer.write(3, code_lengths[*symbol as usize].1).await?;
+    }
+
+    let mut codes = vec![Vec::new(); 19];
+    for (symbol, code) in canonical_codes(code_lengths).expect("lengths are from code_lengths") {
+        codes[symbol as usize] = code;
+    }
+    for (symbol, extra_bits, extra) in symbols {
+        for bit in codes[symbol as usize].iter() {
+            writer.write_bit(*bit == 1).await?;
+        }
+        if extra_bits > 0 {
+            writer.write(extra_bits, extra).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads code lengths stored by `write_code_lengths`,
+/// padded with zeros to the given count
+async fn read_code_lengths<R: BitRead + ?Sized>(
+    reader: &mut R,
+    max_count: usize,
+) -> io::Result<Vec<u32>> {
+    fn invalid(message: &'static str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, message)
+    }
+
+    let count = reader.read_leb128::<u64>().await?;
+    if count > max_count as u64 {
+        return Err(invalid("too many code lengths"));
+    }
+    let mut lengths = Vec::with_capacity(max_count);
+    if count > 0 {
+        let stored = reader.read::<u32>(4).await? as usize + 4;
+        let mut code_lengths: Vec<(u8, u32)> = (0..19).map(|symbol| (symbol, 0)).collect();
+        for symbol in LENGTH_CODE_ORDER[0..stored].iter() {
+            code_lengths[*symbol as usize].1 = reader.read::<u32>(3).await?;
+        }
+        let codes: BTreeMap<Vec<u8>, u8> = canonical_codes(code_lengths)
+            .map_err(|_| invalid("invalid code length code"))?
+            .into_iter()
+            .map(|(symbol, code)| (code, symbol))
+            .collect();
+
+        while (lengths.len() as u64) < count {
+            let mut code = Vec::new();
+            let symbol = loop {
+                if code.len() == 7 {
+                    return Err(invalid("invalid code length code"));
+                }
+                code.push(reader.read_bit().await? as u8);
+                if let Some(symbol) = codes.get(&code) {
+                    break *symbol;
+                }
+            };
+            let (len, repeat) = match symbol {
+                16 => match lengths.last() {
+                    Some(previous) => (*previous, reader.read::<u32>(2).await? + 3),
+                    None => return Err(invalid("repeated code length with no previous length")),
+                },
+                17 => (0, reader.read::<u32>(3).await? + 3),
+                18 => (0, reader.read::<u32>(7).await? + 11),
+                len => (u32::from(len), 1),
+            };
+            if lengths.len() as u64 + u64::from(repeat) > count {
+                return Err(invalid("code lengths overrun"));
+            }
+            lengths.extend((0..repeat).map(|_| len));
+        }
+    }
+    lengths.resize(max_count, 0);
+    Ok(lengths)
 }
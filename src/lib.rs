@@ -23,35 +23,97 @@
 //!
 //! In addition, reader streams do not consume any more bytes
 //! from the underlying reader than necessary, buffering only a
-//! single partial byte as needed.
+//! single partial byte as needed, except for operations which
+//! read ahead: peeks, Huffman reads with a wide table,
+//! and cancel-safe reads, which buffer the bytes they need
+//! before consuming any.
+//! Readers created via `BitReader::with_capacity` read ahead in large chunks.
+//! Bytes read ahead stay in the reader, so `BitReader::reader`
+//! returns `None` while any are held and `BitReader::into_parts`
+//! hands them back along with the underlying reader.
 //! Writer streams also write out all whole bytes as they are accumulated.
 //!
 //! Readers and writers are also designed to work with integer
 //! types of any possible size.
 //! Many of Rust's built-in integer types are supported by default.
 //!
-//! Traits are implemented with [async_trait](https://docs.rs/async_trait/latest) macro that is re-exported from this library
+//! For data which is already in memory, `BitSlice` and `BitVecWriter`
+//! offer the same operations as plain synchronous methods.
+//!
+//! Failures are reported as `std::io::Error`,
+//! which converts to and from `BitstreamError` for parsers
+//! which need to know what kind of failure it was,
+//! the bit offset it happened at and the fields it happened in.
+//!
+//! The reading and writing traits' methods return futures
+//! directly rather than boxing them, so calls on concrete readers
+//! and writers don't allocate.
+//! Where the stream's type isn't known until runtime,
+//! `DynBitRead` and `DynBitWrite` offer the same core methods
+//! with boxed futures, for use as `dyn DynBitRead` and `dyn DynBitWrite`.
+//! `FromBitStream`, `ToBitStream` and their `With` variants
+//! work the same way, so implementations are written with plain `async fn`.
+//!
+//! With the `derive` feature enabled, the `BitDecode` and `BitEncode`
+//! derive macros generate `read` and `write` functions for structs
+//! from field attributes such as `#[bits(7)]` or `#[signed(5)]`.
+//! See [tokio-bitstream-io-derive](https://docs.rs/tokio-bitstream-io-derive/)
+//! for the full list of attributes.
+//!
+//! With the `serde` feature enabled, `huffman::HuffmanTable`
+//! implements serde's `Serialize` and `Deserialize`.
+//!
+//! With the `codec` feature enabled, `codec::BitCodec` frames
+//! `tokio-util` streams with any `FromBitStream` and `ToBitStream` types.
+//!
+//! With the `futures-io` feature enabled, `compat` re-exports
+//! `tokio_util::compat`, whose `Compat` wraps `futures-io` streams,
+//! such as those of async-std, for use with readers and writers.
+//! The tokio dependency supplies only the I/O traits, not its runtime.
 
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
 use std::io;
 use std::fmt::Debug;
+use std::future::Future;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use std::marker::PhantomData;
 use std::mem;
+use std::pin::Pin;
 use std::ops::{BitOrAssign, BitXor, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub};
 
-pub use async_trait::async_trait;
+use error::io_error;
 
+pub mod checksum;
+#[cfg(feature = "codec")]
+pub mod codec;
+#[cfg(feature = "futures-io")]
+pub mod compat;
+pub mod cursor;
+pub mod error;
 pub mod huffman;
+pub mod rbsp;
 pub mod read;
 pub mod write;
-pub use read::{BitRead, BitReader, ByteRead, ByteReader, HuffmanRead};
+pub use cursor::{BitSlice, BitVecWriter};
+pub use error::{BitstreamError, BitstreamErrorKind, ErrorContext};
+pub use read::{
+    BitRead, BitReader, BitTake, ByteRead, ByteReader, DynBitRead, FromBitStream,
+    FromBitStreamWith, HuffmanRead,
+};
 pub use write::{
-    BitCounter, BitRecorder, BitWrite, BitWriter, ByteWrite, ByteWriter, HuffmanWrite,
+    BitCounter, BitRecorder, BitWrite, BitWriter, ByteWrite, ByteWriter, DynBitWrite,
+    HuffmanWrite, Placeholder, ToBitStream, ToBitStreamWith,
 };
 
+#[cfg(feature = "derive")]
+pub use tokio_bitstream_io_derive::{BitDecode, BitEncode};
+
+/// A boxed future, as returned by the dyn-compatible
+/// [`DynBitRead`] and [`DynBitWrite`] traits
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
 /// This trait extends many common integer types (both unsigned and signed)
 /// with a few trivial methods so that they can be used
 /// with the bitstream handling traits.
@@ -239,6 +301,61 @@ define_signed_numeric!(i32);
 define_signed_numeric!(i64);
 define_signed_numeric!(i128);
 
+// Returns a numeric value's bits zero-extended to a u128
+// for variable-length integer coding
+fn numeric_to_u128<N: Numeric>(value: N) -> u128 {
+    let bytes = value.to_be_bytes();
+    let bytes = bytes.as_ref();
+    bytes[bytes.len().saturating_sub(16)..]
+        .iter()
+        .fold(0, |acc, b| (acc << 8) | u128::from(*b))
+}
+
+// Returns a signed value sign-extended to an i128
+// for variable-length integer coding
+fn numeric_to_i128<S: SignedNumeric>(value: S) -> i128 {
+    let unsigned = numeric_to_u128(value) as i128;
+    if value.is_negative() && S::BITS_SIZE < 128 {
+        unsigned | (!0 << S::BITS_SIZE)
+    } else {
+        unsigned
+    }
+}
+
+// Returns the given value as a numeric type
+// if it fits in that type's bits
+fn numeric_from_u128<N: Numeric>(value: u128) -> Option<N> {
+    if N::BITS_SIZE < 128 && (value >> N::BITS_SIZE) != 0 {
+        return None;
+    }
+    let source = value.to_be_bytes();
+    let mut buffer = N::buffer();
+    let len = buffer.as_ref().len();
+    for (i, b) in buffer.as_mut().iter_mut().enumerate() {
+        *b = (i + 16).checked_sub(len).map(|j| source[j]).unwrap_or(0);
+    }
+    Some(N::from_be_bytes(buffer))
+}
+
+// Returns the given value as a signed numeric type
+// if it fits in that type's twos-complement range
+fn numeric_from_i128<S: SignedNumeric>(value: i128) -> Option<S> {
+    if S::BITS_SIZE < 128 {
+        let limit = 1i128 << (S::BITS_SIZE - 1);
+        if value < -limit || value >= limit {
+            return None;
+        }
+    }
+    let source = value.to_be_bytes();
+    let fill = if value < 0 { 0xFF } else { 0 };
+    let mut buffer = S::buffer();
+    let len = buffer.as_ref().len();
+    for (i, b) in buffer.as_mut().iter_mut().enumerate() {
+        *b = (i + 16).checked_sub(len).map(|j| source[j]).unwrap_or(fill);
+    }
+    Some(S::from_be_bytes(buffer))
+}
+
 /// A stream's endianness, or byte order, for determining
 /// how bits should be read.
 ///
@@ -246,7 +363,6 @@ define_signed_numeric!(i128);
 /// (which may be shortened to `BE` and `LE`)
 /// and is not something programmers should have to implement
 /// in most cases.
-#[async_trait::async_trait]
 pub trait Endianness: Sized + Send + Sync {
     /// Pushes the given bits and value onto an accumulator
     /// with the given bits and value.
@@ -279,25 +395,25 @@ pub trait Endianness: Sized + Send + Sync {
         N: Numeric;
 
     /// Reads signed value from reader in this endianness
-    async fn read_signed<R, S>(r: &mut R, bits: u32) -> io::Result<S>
+    fn read_signed<R, S>(r: &mut R, bits: u32) -> impl Future<Output = io::Result<S>> + Send
     where
         R: BitRead,
         S: SignedNumeric;
 
     /// Writes signed value to writer in this endianness
-    async fn write_signed<W, S>(w: &mut W, bits: u32, value: S) -> io::Result<()>
+    fn write_signed<W, S>(w: &mut W, bits: u32, value: S) -> impl Future<Output = io::Result<()>> + Send
     where
         W: BitWrite,
         S: SignedNumeric;
 
     /// Reads entire numeric value from reader in this endianness
-    async fn read_numeric<R, N>(r: R) -> io::Result<N>
+    fn read_numeric<R, N>(r: R) -> impl Future<Output = io::Result<N>> + Send
     where
         R: AsyncRead + Unpin + Send + Sync,
         N: Numeric;
 
     /// Writes entire numeric value from reader in this endianness
-    async fn write_numeric<W, N>(w: W, value: N) -> io::Result<()>
+    fn write_numeric<W, N>(w: W, value: N) -> impl Future<Output = io::Result<()>> + Send
     where
         W: AsyncWrite + Unpin + Send + Sync,
         N: Numeric;
@@ -310,7 +426,6 @@ pub struct BigEndian;
 /// Big-endian, or most significant bits first
 pub type BE = BigEndian;
 
-#[async_trait::async_trait]
 impl Endianness for BigEndian {
     #[inline]
     fn push<N>(queue: &mut BitQueue<Self, N>, bits: u32, value: N)
@@ -392,8 +507,8 @@ impl Endianness for BigEndian {
                 unsigned
             })
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
                 "excessive bits for type read",
             ))
         }
@@ -405,8 +520,8 @@ impl Endianness for BigEndian {
         S: SignedNumeric,
     {
         if bits > S::BITS_SIZE {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
                 "excessive bits for type written",
             ))
         } else if bits == S::BITS_SIZE {
@@ -450,7 +565,6 @@ pub struct LittleEndian;
 /// Little-endian, or least significant bits first
 pub type LE = LittleEndian;
 
-#[async_trait::async_trait]
 impl Endianness for LittleEndian {
     #[inline]
     fn push<N>(queue: &mut BitQueue<Self, N>, bits: u32, mut value: N)
@@ -526,8 +640,8 @@ impl Endianness for LittleEndian {
                 unsigned
             })
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
                 "excessive bits for type read",
             ))
         }
@@ -539,8 +653,8 @@ impl Endianness for LittleEndian {
         S: SignedNumeric,
     {
         if bits > S::BITS_SIZE {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
                 "excessive bits for type written",
             ))
         } else if bits == S::BITS_SIZE {
@@ -579,13 +693,24 @@ impl Endianness for LittleEndian {
 
 /// A queue for efficiently pushing bits onto a value
 /// and popping them off a value.
-#[derive(Clone, Default)]
+#[derive(Default)]
 pub struct BitQueue<E: Endianness, N: Numeric> {
     phantom: PhantomData<E>,
     value: N,
     bits: u32,
 }
 
+impl<E: Endianness, N: Numeric> Clone for BitQueue<E, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        BitQueue {
+            phantom: PhantomData,
+            value: self.value,
+            bits: self.bits,
+        }
+    }
+}
+
 impl<E: Endianness, N: Numeric> BitQueue<E, N> {
     /// Returns a new empty queue
     #[inline]
@@ -0,0 +1,305 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Adapters for removing and inserting emulation-prevention bytes.
+//!
+//! H.264, HEVC and VVC NAL units never contain the byte sequences
+//! `00 00 00`, `00 00 01` or `00 00 02`, so the encoder inserts an
+//! emulation-prevention `03` byte after any `00 00` pair which would
+//! otherwise be followed by a byte of `03` or less.
+//! The unescaped payload is known as the raw byte sequence payload (RBSP).
+//!
+//! [`RbspReader`] wraps an escaped stream and removes those bytes as it reads,
+//! while [`RbspWriter`] wraps an output stream and inserts them as it writes,
+//! so either may be used with `BitReader` and `BitWriter` directly.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::io::Cursor;
+//! use tokio::io::AsyncWriteExt;
+//! use tokio_bitstream_io::rbsp::{RbspReader, RbspWriter};
+//! use tokio_bitstream_io::{BigEndian, BitReader, BitRead, BitWriter, BitWrite};
+//!
+//! # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+//! let escaped = [0x00, 0x00, 0x03, 0x01, 0x80];
+//!
+//! let mut reader = BitReader::endian(RbspReader::new(Cursor::new(&escaped)), BigEndian);
+//! assert_eq!(reader.read::<u32>(24).await.unwrap(), 0x000001);
+//! assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x80);
+//! assert_eq!(reader.reader().unwrap().removed(), 1);
+//!
+//! let mut writer = BitWriter::endian(RbspWriter::new(Vec::new()), BigEndian);
+//! writer.write(24, 0x000001).await.unwrap();
+//! writer.write(8, 0x80).await.unwrap();
+//! let mut rbsp = writer.into_writer();
+//! rbsp.shutdown().await.unwrap();
+//! assert_eq!(rbsp.inserted(), 1);
+//! assert_eq!(rbsp.into_inner(), escaped);
+//! # });
+//! ```
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// The emulation-prevention byte
+const EMULATION_PREVENTION: u8 = 0x03;
+
+/// An `AsyncRead` adapter which removes emulation-prevention bytes
+/// from an escaped stream as it is read.
+///
+/// Every `03` byte which follows a `00 00` pair is dropped,
+/// and the total number of dropped bytes is available from
+/// [`RbspReader::removed`] in order to map positions in the
+/// unescaped stream back to the escaped one.
+pub struct RbspReader<R> {
+    reader: R,
+    zeros: u8,
+    removed: u64,
+}
+
+impl<R: AsyncRead + Unpin> RbspReader<R> {
+    /// Wraps an escaped stream
+    pub fn new(reader: R) -> Self {
+        RbspReader {
+            reader,
+            zeros: 0,
+            removed: 0,
+        }
+    }
+
+    /// Returns the number of emulation-prevention bytes removed so far
+    ///
+    /// Adding this to the number of bytes read from this adapter
+    /// gives the number of bytes consumed from the escaped stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use tokio::io::AsyncReadExt;
+    /// use tokio_bitstream_io::rbsp::RbspReader;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let escaped = [0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x00];
+    /// let mut reader = RbspReader::new(Cursor::new(&escaped));
+    /// let mut rbsp = Vec::new();
+    /// reader.read_to_end(&mut rbsp).await.unwrap();
+    /// assert_eq!(rbsp, [0x00, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(reader.removed(), 2);
+    /// # });
+    /// ```
+    #[inline]
+    pub fn removed(&self) -> u64 {
+        self.removed
+    }
+
+    /// Returns a reference to the wrapped stream
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the wrapped stream
+    ///
+    /// Reading from it directly will confuse the detection
+    /// of emulation-prevention bytes.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Unwraps the escaped stream
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for RbspReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let start = buf.filled().len();
+
+        loop {
+            match Pin::new(&mut this.reader).poll_read(cx, buf) {
+                Poll::Ready(Ok(())) => {}
+                other => return other,
+            }
+
+            let end = buf.filled().len();
+            if end == start {
+                // end of stream
+                return Poll::Ready(Ok(()));
+            }
+
+            let filled = buf.filled_mut();
+            let mut kept = start;
+            for i in start..end {
+                let byte = filled[i];
+                if this.zeros >= 2 && byte == EMULATION_PREVENTION {
+                    this.zeros = 0;
+                    this.removed += 1;
+                } else {
+                    this.zeros = if byte == 0 {
+                        (this.zeros + 1).min(2)
+                    } else {
+                        0
+                    };
+                    filled[kept] = byte;
+                    kept += 1;
+                }
+            }
+            buf.set_filled(kept);
+
+            // an empty read would look like the end of the stream,
+            // so keep going if everything read was removed
+            if kept > start {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+}
+
+/// An `AsyncWrite` adapter which inserts emulation-prevention bytes
+/// into a stream as it is written.
+///
+/// An `03` byte is inserted whenever a `00 00` pair would otherwise
+/// be followed by a byte of `03` or less.
+/// In addition, if the stream ends with a `00 00` pair,
+/// a final `03` byte is appended when it is shut down.
+/// Escaped bytes are held in an internal buffer until the wrapped
+/// stream accepts them, so the adapter should be flushed or shut down
+/// once writing is complete.
+pub struct RbspWriter<W> {
+    writer: W,
+    zeros: u8,
+    inserted: u64,
+    pending: Vec<u8>,
+    written: usize,
+}
+
+impl<W: AsyncWrite + Unpin> RbspWriter<W> {
+    /// Wraps an output stream
+    pub fn new(writer: W) -> Self {
+        RbspWriter {
+            writer,
+            zeros: 0,
+            inserted: 0,
+            pending: Vec::new(),
+            written: 0,
+        }
+    }
+
+    /// Returns the number of emulation-prevention bytes inserted so far
+    #[inline]
+    pub fn inserted(&self) -> u64 {
+        self.inserted
+    }
+
+    /// Returns a reference to the wrapped stream
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the wrapped stream
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Unwraps the output stream
+    ///
+    /// Any escaped bytes not yet accepted by the stream are lost,
+    /// so this should only be called after a flush or shutdown.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn escape(&mut self, buf: &[u8]) {
+        self.pending.reserve(buf.len());
+        for byte in buf.iter().copied() {
+            if self.zeros >= 2 && byte <= EMULATION_PREVENTION {
+                self.pending.push(EMULATION_PREVENTION);
+                self.inserted += 1;
+                self.zeros = 0;
+            }
+            self.zeros = if byte == 0 { self.zeros + 1 } else { 0 };
+            self.pending.push(byte);
+        }
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.written < self.pending.len() {
+            match Pin::new(&mut self.writer).poll_write(cx, &self.pending[self.written..]) {
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write escaped bytes",
+                    )))
+                }
+                Poll::Ready(Ok(written)) => self.written += written,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        self.pending.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for RbspWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        match this.poll_pending(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        }
+        this.escape(buf);
+        // the bytes are accepted once they are buffered;
+        // any which the stream cannot take yet are sent on the next call
+        if let Poll::Ready(Err(err)) = this.poll_pending(cx) {
+            return Poll::Ready(Err(err));
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match this.poll_pending(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.writer).poll_flush(cx),
+            other => other,
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.zeros >= 2 {
+            this.pending.push(EMULATION_PREVENTION);
+            this.inserted += 1;
+            this.zeros = 0;
+        }
+        match this.poll_pending(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.writer).poll_shutdown(cx),
+            other => other,
+        }
+    }
+}
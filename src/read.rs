@@ -170,14 +170,23 @@
 
 #![warn(missing_docs)]
 
+use std::borrow::BorrowMut;
+use std::cmp::{max, min};
+use std::convert::TryFrom;
+use std::future::{poll_fn, Future};
 use std::io;
-use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, ReadBuf};
 
-use super::{huffman::ReadHuffmanTree, BitQueue, Endianness, Numeric, PhantomData, SignedNumeric};
+use super::error::{io_error, BitstreamError, BitstreamErrorKind};
+use super::{
+    huffman::{ReadHuffmanTree, WideEntry, WideHuffmanTable}, numeric_from_i128, numeric_from_u128, BitQueue, BoxFuture,
+    Endianness, Numeric, PhantomData, SignedNumeric,
+};
 
 /// A trait for anything that can read a variable number of
 /// potentially un-aligned values from an input stream
-#[async_trait::async_trait]
 pub trait BitRead: Send {
     /// Reads a single bit from the stream.
     /// `true` indicates 1, `false` indicates 0
@@ -185,7 +194,7 @@ pub trait BitRead: Send {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn read_bit(&mut self) -> io::Result<bool>;
+    fn read_bit(&mut self) -> impl Future<Output = io::Result<bool>> + Send;
 
     /// Reads an unsigned value from the stream with
     /// the given number of bits.
@@ -195,7 +204,7 @@ pub trait BitRead: Send {
     /// Passes along any I/O error from the underlying stream.
     /// Also returns an error if the output type is too small
     /// to hold the requested number of bits.
-    async fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    fn read<U>(&mut self, bits: u32) -> impl Future<Output = io::Result<U>> + Send
     where
         U: Numeric;
 
@@ -207,10 +216,30 @@ pub trait BitRead: Send {
     /// Passes along any I/O error from the underlying stream.
     /// Also returns an error if the output type is too small
     /// to hold the requested number of bits.
-    async fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    fn read_signed<S>(&mut self, bits: u32) -> impl Future<Output = io::Result<S>> + Send
     where
         S: SignedNumeric;
 
+    /// Returns the next bit in the stream without consuming it.
+    /// `true` indicates 1, `false` indicates 0
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    fn peek_bit(&mut self) -> impl Future<Output = io::Result<bool>> + Send;
+
+    /// Returns an unsigned value made of the given number of
+    /// upcoming bits in the stream without consuming them.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the output type is too small
+    /// to hold the requested number of bits.
+    fn peek<U>(&mut self, bits: u32) -> impl Future<Output = io::Result<U>> + Send
+    where
+        U: Numeric;
+
     /// Skips the given number of bits in the stream.
     /// Since this method does not need an accumulator,
     /// it may be slightly faster than reading to an empty variable.
@@ -223,7 +252,7 @@ pub trait BitRead: Send {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn skip(&mut self, bits: u32) -> io::Result<()>;
+    fn skip(&mut self, bits: u32) -> impl Future<Output = io::Result<()>> + Send;
 
     /// Completely fills the given buffer with whole bytes.
     /// If the stream is already byte-aligned, it will map
@@ -233,84 +262,824 @@ pub trait BitRead: Send {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
-        for b in buf.iter_mut() {
-            *b = self.read(8).await?;
+    fn read_bytes(&mut self, buf: &mut [u8]) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            for b in buf.iter_mut() {
+                *b = self.read(8).await?;
+            }
+            Ok(())
         }
-        Ok(())
     }
 
-    /// Completely fills a whole buffer with bytes and returns it.
-    /// If the stream is already byte-aligned, it will map
-    /// to a faster `read_exact` call.  Otherwise it will read
-    /// bytes individually in 8-bit increments.
-    ///
-    /// # Errors
-    ///
-    /// Passes along any I/O error from the underlying stream.
-    async fn read_to_bytes<const SIZE: usize>(&mut self) -> io::Result<[u8; SIZE]> {
-        let mut buf = [0; SIZE];
-        self.read_bytes(&mut buf).await?;
-        Ok(buf)
+    /// Completely fills a whole buffer with bytes and returns it.
+    /// If the stream is already byte-aligned, it will map
+    /// to a faster `read_exact` call.  Otherwise it will read
+    /// bytes individually in 8-bit increments.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    fn read_to_bytes<const SIZE: usize>(&mut self) -> impl Future<Output = io::Result<[u8; SIZE]>> + Send {
+        async move {
+            let mut buf = [0; SIZE];
+            self.read_bytes(&mut buf).await?;
+            Ok(buf)
+        }
+    }
+
+    /// Completely fills a vector of bytes and returns it.
+    /// If the stream is already byte-aligned, it will map
+    /// to a faster `read_exact` call.  Otherwise it will read
+    /// bytes individually in 8-bit increments.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    fn read_to_vec(&mut self, bytes: usize) -> impl Future<Output = io::Result<Vec<u8>>> + Send {
+        async move {
+            let mut buf = vec![0; bytes];
+            self.read_bytes(&mut buf).await?;
+            Ok(buf)
+        }
+    }
+
+    /// Counts the number of 1 bits in the stream until the next
+    /// 0 bit and returns the amount read.
+    /// Because this field is variably-sized and may be large,
+    /// its output is always a `u32` type.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    fn read_unary0(&mut self) -> impl Future<Output = io::Result<u32>> + Send {
+        async move {
+            let mut unary = 0;
+            while self.read_bit().await? {
+                unary += 1;
+            }
+            Ok(unary)
+        }
+    }
+
+    /// Counts the number of 0 bits in the stream until the next
+    /// 1 bit and returns the amount read.
+    /// Because this field is variably-sized and may be large,
+    /// its output is always a `u32` type.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    fn read_unary1(&mut self) -> impl Future<Output = io::Result<u32>> + Send {
+        async move {
+            let mut unary = 0;
+            while !(self.read_bit().await?) {
+                unary += 1;
+            }
+            Ok(unary)
+        }
+    }
+
+    /// Reads an unsigned Exp-Golomb code of order `k`,
+    /// such as the `ue(v)` fields of H.264 and HEVC (where `k` is 0).
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 31.
+    /// Returns an `InvalidData` error if the code has
+    /// more than 32 leading zeros.
+    ///
+    /// # Examples
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1_010_011_0, 0b0100_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_exp_golomb(0).await.unwrap(), 0);
+    /// assert_eq!(reader.read_exp_golomb(0).await.unwrap(), 1);
+    /// assert_eq!(reader.read_exp_golomb(0).await.unwrap(), 2);
+    /// assert_eq!(reader.read_exp_golomb(0).await.unwrap(), 3);
+    /// # });
+    /// ```
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10_11_0100, 0b0111_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_exp_golomb(1).await.unwrap(), 0);
+    /// assert_eq!(reader.read_exp_golomb(1).await.unwrap(), 1);
+    /// assert_eq!(reader.read_exp_golomb(1).await.unwrap(), 2);
+    /// assert_eq!(reader.read_exp_golomb(1).await.unwrap(), 5);
+    /// # });
+    /// ```
+    fn read_exp_golomb(&mut self, k: u32) -> impl Future<Output = io::Result<u64>> + Send {
+        async move {
+            if k > 31 {
+                return Err(io_error(
+                    BitstreamErrorKind::ExcessiveBits,
+                    "excessive order for Exp-Golomb code",
+                ));
+            }
+            // a bit at a time, so as to stop as soon as the prefix is too long
+            let mut zeros = 0;
+            while !self.read_bit().await? {
+                zeros += 1;
+                if zeros > 32 {
+                    return Err(io_error(
+                        BitstreamErrorKind::InvalidCode,
+                        "excessive leading zeros in Exp-Golomb code",
+                    ));
+                }
+            }
+            let suffix = if zeros + k > 0 {
+                self.read::<u64>(zeros + k).await?
+            } else {
+                0
+            };
+            Ok((((1u64 << zeros) - 1) << k) + suffix)
+        }
+    }
+
+    /// Reads a signed Exp-Golomb code of order `k`,
+    /// such as the `se(v)` fields of H.264 and HEVC (where `k` is 0).
+    ///
+    /// Unsigned codes 0, 1, 2, 3, 4... map to 0, 1, -1, 2, -2...
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 31.
+    /// Returns an `InvalidData` error if the code has
+    /// more than 32 leading zeros.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1_010_011_0, 0b0100_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_signed_exp_golomb(0).await.unwrap(), 0);
+    /// assert_eq!(reader.read_signed_exp_golomb(0).await.unwrap(), 1);
+    /// assert_eq!(reader.read_signed_exp_golomb(0).await.unwrap(), -1);
+    /// assert_eq!(reader.read_signed_exp_golomb(0).await.unwrap(), 2);
+    /// # });
+    /// ```
+    fn read_signed_exp_golomb(&mut self, k: u32) -> impl Future<Output = io::Result<i64>> + Send {
+        async move {
+            let unsigned = self.read_exp_golomb(k).await?;
+            if unsigned & 1 == 1 {
+                Ok((unsigned >> 1) as i64 + 1)
+            } else {
+                Ok(-((unsigned >> 1) as i64))
+            }
+        }
+    }
+
+    /// Reads a Rice code with parameter `k`, which is
+    /// a unary quotient (as read by `read_unary0`)
+    /// followed by a `k`-bit remainder.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 63.
+    /// Returns an `InvalidData` error if the value does not fit in a `u64`.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b0_00_0_11_10, 0b01_110_10_0];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_rice(2).await.unwrap(), 0);
+    /// assert_eq!(reader.read_rice(2).await.unwrap(), 3);
+    /// assert_eq!(reader.read_rice(2).await.unwrap(), 5);
+    /// assert_eq!(reader.read_rice(2).await.unwrap(), 10);
+    /// # });
+    /// ```
+    fn read_rice(&mut self, k: u32) -> impl Future<Output = io::Result<u64>> + Send {
+        async move {
+            if k > 63 {
+                return Err(io_error(
+                    BitstreamErrorKind::ExcessiveBits,
+                    "excessive parameter for Rice code",
+                ));
+            }
+            let quotient = u64::from(self.read_unary0().await?);
+            if quotient > (u64::MAX >> k) {
+                return Err(io_error(
+                    BitstreamErrorKind::InvalidCode,
+                    "excessive quotient in Rice code",
+                ));
+            }
+            let remainder = if k > 0 { self.read::<u64>(k).await? } else { 0 };
+            Ok((quotient << k) | remainder)
+        }
+    }
+
+    /// Reads a signed Rice code with parameter `k`
+    /// whose unsigned value is zigzag-encoded,
+    /// such that 0, 1, 2, 3, 4... map to 0, -1, 1, -2, 2...
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 63.
+    /// Returns an `InvalidData` error if the value does not fit in a `u64`.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b00_01_100_1, 0b01_000000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_signed_rice(1).await.unwrap(), 0);
+    /// assert_eq!(reader.read_signed_rice(1).await.unwrap(), -1);
+    /// assert_eq!(reader.read_signed_rice(1).await.unwrap(), 1);
+    /// assert_eq!(reader.read_signed_rice(1).await.unwrap(), -2);
+    /// # });
+    /// ```
+    fn read_signed_rice(&mut self, k: u32) -> impl Future<Output = io::Result<i64>> + Send {
+        async move {
+            let unsigned = self.read_rice(k).await?;
+            Ok(((unsigned >> 1) as i64) ^ -((unsigned & 1) as i64))
+        }
+    }
+
+    /// Reads a Golomb code with divisor `m`, which is
+    /// a unary quotient (as read by `read_unary0`)
+    /// followed by a truncated binary remainder.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `m` is 0.
+    /// Returns an `InvalidData` error if the value does not fit in a `u64`.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b000_0111_1, 0b001_00000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_golomb(5).await.unwrap(), 0);
+    /// assert_eq!(reader.read_golomb(5).await.unwrap(), 4);
+    /// assert_eq!(reader.read_golomb(5).await.unwrap(), 6);
+    /// # });
+    /// ```
+    fn read_golomb(&mut self, m: u64) -> impl Future<Output = io::Result<u64>> + Send {
+        async move {
+            if m == 0 {
+                return Err(io_error(
+                    BitstreamErrorKind::ExcessiveValue,
+                    "Golomb code divisor must be nonzero",
+                ));
+            }
+            let quotient = u64::from(self.read_unary0().await?);
+            let bits = golomb_remainder_bits(m);
+            let remainder = if bits > 0 {
+                let cutoff = (1u128 << bits) - u128::from(m);
+                let short = u128::from(self.read::<u64>(bits - 1).await?);
+                if short < cutoff {
+                    short
+                } else {
+                    ((short << 1) | u128::from(self.read_bit().await?)) - cutoff
+                }
+            } else {
+                0
+            };
+            quotient
+                .checked_mul(m)
+                .and_then(|v| v.checked_add(remainder as u64))
+                .ok_or_else(|| {
+                    io_error(BitstreamErrorKind::InvalidCode, "excessive quotient in Golomb code")
+                })
+        }
+    }
+
+    /// Reads an unsigned LEB128 value, which need not be byte-aligned.
+    /// See [`ByteRead::read_leb128`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1111_1110, 0b0101_1000, 0b1110_0010, 0b0110_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).await.unwrap(), 0b1111);
+    /// assert_eq!(reader.read_leb128::<u32>().await.unwrap(), 624485);
+    /// # });
+    /// ```
+    fn read_leb128<N: Numeric>(&mut self) -> impl Future<Output = io::Result<N>> + Send {
+        async move {
+            let mut leb128 = Leb128::default();
+            while !leb128.push(self.read::<u8>(8).await?, false)? {}
+            unsigned_varint(leb128.value)
+        }
+    }
+
+    /// Reads a signed LEB128 value, which need not be byte-aligned.
+    /// See [`ByteRead::read_signed_leb128`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    fn read_signed_leb128<S: SignedNumeric>(&mut self) -> impl Future<Output = io::Result<S>> + Send {
+        async move {
+            let mut leb128 = Leb128::default();
+            while !leb128.push(self.read::<u8>(8).await?, true)? {}
+            signed_varint(leb128.value)
+        }
+    }
+
+    /// Reads a big-endian variable-length quantity,
+    /// which need not be byte-aligned.
+    /// See [`ByteRead::read_vlq`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    fn read_vlq<N: Numeric>(&mut self) -> impl Future<Output = io::Result<N>> + Send {
+        async move {
+            let mut value = 0;
+            while !push_vlq(&mut value, self.read::<u8>(8).await?)? {}
+            unsigned_varint(value)
+        }
+    }
+
+    /// Reads a QUIC variable-length integer,
+    /// which need not be byte-aligned.
+    /// See [`ByteRead::read_quic_varint`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    fn read_quic_varint<N: Numeric>(&mut self) -> impl Future<Output = io::Result<N>> + Send {
+        async move {
+            let first = self.read::<u8>(8).await?;
+            let mut rest = [0; 7];
+            let rest = &mut rest[0..quic_varint_len(first) - 1];
+            self.read_bytes(rest).await?;
+            unsigned_varint(
+                rest.iter()
+                    .fold(u128::from(first & 0x3F), |acc, b| (acc << 8) | u128::from(*b)),
+            )
+        }
+    }
+
+    /// Parses and returns a complete value from the stream
+    /// using its [`FromBitStream`] implementation.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let data = [0b1000_0001, 0x12, 0x34];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let (flag, value): (bool, u8) = reader.parse().await.unwrap();
+    /// assert!(flag);
+    /// assert_eq!(value, 0b000_0001_0);
+    /// assert_eq!(reader.parse::<[u8; 2]>().await.unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Passes along any error from the value's implementation.
+    fn parse<F: FromBitStream>(&mut self) -> impl Future<Output = Result<F, F::Error>> + Send {
+        async move {
+            F::from_reader(self).await
+        }
+    }
+
+    /// Parses and returns a complete value from the stream
+    /// using its [`FromBitStreamWith`] implementation
+    /// and the given context.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let data = [0x00, 0x01, 0x00, 0x02, 0x00, 0x03];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.parse_with::<Vec<u16>, _>(&3).await.unwrap(), vec![1, 2, 3]);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Passes along any error from the value's implementation.
+    fn parse_with<F, C>(&mut self, context: &C) -> impl Future<Output = Result<F, F::Error>> + Send
+    where
+        F: FromBitStreamWith<C>,
+        C: Sync + ?Sized,
+    {
+        async move {
+            F::from_reader(self, context).await
+        }
+    }
+
+    /// Returns true if the stream is aligned at a whole byte.
+    fn byte_aligned(&self) -> bool;
+
+    /// Throws away all unread bit values until the next whole byte.
+    /// Does nothing if the stream is already aligned.
+    fn byte_align(&mut self);
+}
+
+/// A dyn-compatible version of [`BitRead`] whose methods
+/// return boxed futures, for when the concrete reader
+/// isn't known until runtime.
+///
+/// It is implemented for every [`BitRead`] and
+/// `dyn DynBitRead` implements [`BitRead`] in turn,
+/// so a trait object may be passed anywhere a reader is expected.
+/// Values travel through the boxed methods as 128-bit integers.
+///
+/// Readers used directly through [`BitRead`] don't box
+/// their futures, so prefer it when the reader's type is known.
+///
+/// # Example
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// use std::io::Cursor;
+/// use tokio_bitstream_io::{BigEndian, LittleEndian, BitReader, BitRead, DynBitRead};
+/// let data = [0b1011_0001];
+/// let mut readers: Vec<Box<dyn DynBitRead>> = vec![
+///     Box::new(BitReader::endian(Cursor::new(&data), BigEndian)),
+///     Box::new(BitReader::endian(Cursor::new(&data), LittleEndian)),
+/// ];
+/// assert_eq!(readers[0].read::<u8>(4).await.unwrap(), 0b1011);
+/// assert_eq!(readers[1].read::<u8>(4).await.unwrap(), 0b0001);
+/// # });
+/// ```
+pub trait DynBitRead: Send {
+    /// Reads a single bit from the stream
+    fn dyn_read_bit(&mut self) -> BoxFuture<'_, io::Result<bool>>;
+
+    /// Reads an unsigned value of up to 128 bits from the stream
+    fn dyn_read(&mut self, bits: u32) -> BoxFuture<'_, io::Result<u128>>;
+
+    /// Reads a twos-complement signed value of up to 128 bits from the stream
+    fn dyn_read_signed(&mut self, bits: u32) -> BoxFuture<'_, io::Result<i128>>;
+
+    /// Peeks at a single upcoming bit in the stream
+    fn dyn_peek_bit(&mut self) -> BoxFuture<'_, io::Result<bool>>;
+
+    /// Peeks at an unsigned value of up to 128 upcoming bits in the stream
+    fn dyn_peek(&mut self, bits: u32) -> BoxFuture<'_, io::Result<u128>>;
+
+    /// Skips the given number of bits in the stream
+    fn dyn_skip(&mut self, bits: u32) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Completely fills the given buffer with whole bytes
+    fn dyn_read_bytes<'a>(&'a mut self, buf: &'a mut [u8]) -> BoxFuture<'a, io::Result<()>>;
+
+    /// Counts the number of 1 bits until the next 0 bit
+    fn dyn_read_unary0(&mut self) -> BoxFuture<'_, io::Result<u32>>;
+
+    /// Counts the number of 0 bits until the next 1 bit
+    fn dyn_read_unary1(&mut self) -> BoxFuture<'_, io::Result<u32>>;
+
+    /// Returns true if the stream is aligned at a whole byte
+    fn dyn_byte_aligned(&self) -> bool;
+
+    /// Throws away all unread bit values until the next whole byte
+    fn dyn_byte_align(&mut self);
+}
+
+impl<R: BitRead> DynBitRead for R {
+    fn dyn_read_bit(&mut self) -> BoxFuture<'_, io::Result<bool>> {
+        Box::pin(self.read_bit())
+    }
+
+    fn dyn_read(&mut self, bits: u32) -> BoxFuture<'_, io::Result<u128>> {
+        Box::pin(self.read(bits))
+    }
+
+    fn dyn_read_signed(&mut self, bits: u32) -> BoxFuture<'_, io::Result<i128>> {
+        Box::pin(self.read_signed(bits))
+    }
+
+    fn dyn_peek_bit(&mut self) -> BoxFuture<'_, io::Result<bool>> {
+        Box::pin(self.peek_bit())
+    }
+
+    fn dyn_peek(&mut self, bits: u32) -> BoxFuture<'_, io::Result<u128>> {
+        Box::pin(self.peek(bits))
+    }
+
+    fn dyn_skip(&mut self, bits: u32) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(self.skip(bits))
+    }
+
+    fn dyn_read_bytes<'a>(&'a mut self, buf: &'a mut [u8]) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(self.read_bytes(buf))
+    }
+
+    fn dyn_read_unary0(&mut self) -> BoxFuture<'_, io::Result<u32>> {
+        Box::pin(self.read_unary0())
+    }
+
+    fn dyn_read_unary1(&mut self) -> BoxFuture<'_, io::Result<u32>> {
+        Box::pin(self.read_unary1())
+    }
+
+    #[inline]
+    fn dyn_byte_aligned(&self) -> bool {
+        self.byte_aligned()
+    }
+
+    #[inline]
+    fn dyn_byte_align(&mut self) {
+        self.byte_align()
+    }
+}
+
+impl BitRead for dyn DynBitRead + '_ {
+    #[inline]
+    async fn read_bit(&mut self) -> io::Result<bool> {
+        self.dyn_read_bit().await
+    }
+
+    async fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        if bits <= U::BITS_SIZE {
+            let value = self.dyn_read(bits).await?;
+            Ok(numeric_from_u128(value).unwrap())
+        } else {
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type read",
+            ))
+        }
+    }
+
+    async fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        if bits <= S::BITS_SIZE {
+            let value = self.dyn_read_signed(bits).await?;
+            Ok(numeric_from_i128(value).unwrap())
+        } else {
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type read",
+            ))
+        }
+    }
+
+    #[inline]
+    async fn peek_bit(&mut self) -> io::Result<bool> {
+        self.dyn_peek_bit().await
+    }
+
+    async fn peek<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        if bits <= U::BITS_SIZE {
+            let value = self.dyn_peek(bits).await?;
+            Ok(numeric_from_u128(value).unwrap())
+        } else {
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type read",
+            ))
+        }
+    }
+
+    #[inline]
+    async fn skip(&mut self, bits: u32) -> io::Result<()> {
+        self.dyn_skip(bits).await
+    }
+
+    #[inline]
+    async fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.dyn_read_bytes(buf).await
+    }
+
+    #[inline]
+    async fn read_unary0(&mut self) -> io::Result<u32> {
+        self.dyn_read_unary0().await
+    }
+
+    #[inline]
+    async fn read_unary1(&mut self) -> io::Result<u32> {
+        self.dyn_read_unary1().await
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.dyn_byte_aligned()
     }
 
-    /// Completely fills a vector of bytes and returns it.
-    /// If the stream is already byte-aligned, it will map
-    /// to a faster `read_exact` call.  Otherwise it will read
-    /// bytes individually in 8-bit increments.
-    ///
-    /// # Errors
-    ///
-    /// Passes along any I/O error from the underlying stream.
-    async fn read_to_vec(&mut self, bytes: usize) -> io::Result<Vec<u8>> {
-        let mut buf = vec![0; bytes];
-        self.read_bytes(&mut buf).await?;
-        Ok(buf)
+    #[inline]
+    fn byte_align(&mut self) {
+        self.dyn_byte_align()
     }
+}
 
-    /// Counts the number of 1 bits in the stream until the next
-    /// 0 bit and returns the amount read.
-    /// Because this field is variably-sized and may be large,
-    /// its output is always a `u32` type.
-    ///
-    /// # Errors
-    ///
-    /// Passes along any I/O error from the underlying stream.
-    async fn read_unary0(&mut self) -> io::Result<u32> {
-        let mut unary = 0;
-        while self.read_bit().await? {
-            unary += 1;
-        }
-        Ok(unary)
+/// A trait for types which can be parsed from a bitstream
+/// on their own, without any outside information.
+///
+/// This is implemented for `bool` (a single bit),
+/// for all built-in integer types (at their full width),
+/// and for tuples and arrays of other implementations,
+/// so nested structures may be parsed with a single
+/// [`BitRead::parse`] call.
+///
+/// # Example
+/// ```
+/// use std::io::Cursor;
+/// use tokio_bitstream_io::{BigEndian, BitReader, BitRead, FromBitStream};
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct BlockHeader {
+///     last_block: bool,
+///     block_type: u8,
+///     block_size: u32,
+/// }
+///
+/// impl FromBitStream for BlockHeader {
+///     type Error = std::io::Error;
+///
+///     async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> std::io::Result<Self> {
+///         Ok(Self {
+///             last_block: r.read_bit().await?,
+///             block_type: r.read(7).await?,
+///             block_size: r.read(24).await?,
+///         })
+///     }
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let data = [0x04, 0x00, 0x00, 0x22, 0x84, 0x00, 0x00, 0x7A];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// assert_eq!(
+///     reader.parse::<[BlockHeader; 2]>().await.unwrap(),
+///     [
+///         BlockHeader { last_block: false, block_type: 4, block_size: 34 },
+///         BlockHeader { last_block: true, block_type: 4, block_size: 122 },
+///     ]
+/// );
+/// # });
+/// ```
+pub trait FromBitStream: Sized + Send {
+    /// The error type returned when parsing fails
+    type Error;
+
+    /// Parses a value from the given reader
+    fn from_reader<R: BitRead + ?Sized>(
+        r: &mut R,
+    ) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+}
+
+/// A trait for types which need some outside context
+/// in order to be parsed from a bitstream,
+/// such as a length read from an earlier header.
+///
+/// `Vec<T>` implements this with a `usize` context
+/// giving the number of elements to read.
+pub trait FromBitStreamWith<C: Sync + ?Sized>: Sized + Send {
+    /// The error type returned when parsing fails
+    type Error;
+
+    /// Parses a value from the given reader using the given context
+    fn from_reader<R: BitRead + ?Sized>(
+        r: &mut R,
+        context: &C,
+    ) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+}
+
+impl FromBitStream for bool {
+    type Error = io::Error;
+
+    #[inline]
+    async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> io::Result<Self> {
+        r.read_bit().await
     }
+}
 
-    /// Counts the number of 0 bits in the stream until the next
-    /// 1 bit and returns the amount read.
-    /// Because this field is variably-sized and may be large,
-    /// its output is always a `u32` type.
-    ///
-    /// # Errors
-    ///
-    /// Passes along any I/O error from the underlying stream.
-    async fn read_unary1(&mut self) -> io::Result<u32> {
-        let mut unary = 0;
-        while !(self.read_bit().await?) {
-            unary += 1;
+macro_rules! define_from_bitstream_unsigned {
+    ($t:ty) => {
+        impl FromBitStream for $t {
+            type Error = io::Error;
+
+            #[inline]
+            async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> io::Result<Self> {
+                r.read(<$t as Numeric>::BITS_SIZE).await
+            }
+        }
+    };
+}
+
+macro_rules! define_from_bitstream_signed {
+    ($t:ty) => {
+        impl FromBitStream for $t {
+            type Error = io::Error;
+
+            #[inline]
+            async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> io::Result<Self> {
+                r.read_signed(<$t as Numeric>::BITS_SIZE).await
+            }
+        }
+    };
+}
+
+define_from_bitstream_unsigned!(u8);
+define_from_bitstream_unsigned!(u16);
+define_from_bitstream_unsigned!(u32);
+define_from_bitstream_unsigned!(u64);
+define_from_bitstream_unsigned!(u128);
+define_from_bitstream_signed!(i8);
+define_from_bitstream_signed!(i16);
+define_from_bitstream_signed!(i32);
+define_from_bitstream_signed!(i64);
+define_from_bitstream_signed!(i128);
+
+macro_rules! define_from_bitstream_tuple {
+    ($first:ident $first_value:ident $(, $rest:ident $rest_value:ident)*) => {
+        impl<$first: FromBitStream $(, $rest: FromBitStream)*> FromBitStream for ($first, $($rest,)*)
+        where
+            $($rest::Error: Into<$first::Error>,)*
+        {
+            type Error = $first::Error;
+
+            async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> Result<Self, Self::Error> {
+                let $first_value = $first::from_reader(r).await?;
+                $(let $rest_value = $rest::from_reader(r).await.map_err(Into::into)?;)*
+                Ok(($first_value, $($rest_value,)*))
+            }
+        }
+    };
+}
+
+define_from_bitstream_tuple!(A a);
+define_from_bitstream_tuple!(A a, B b);
+define_from_bitstream_tuple!(A a, B b, C c);
+define_from_bitstream_tuple!(A a, B b, C c, D d);
+define_from_bitstream_tuple!(A a, B b, C c, D d, E e);
+define_from_bitstream_tuple!(A a, B b, C c, D d, E e, F f);
+define_from_bitstream_tuple!(A a, B b, C c, D d, E e, F f, G g);
+define_from_bitstream_tuple!(A a, B b, C c, D d, E e, F f, G g, H h);
+
+impl<T: FromBitStream, const SIZE: usize> FromBitStream for [T; SIZE] {
+    type Error = T::Error;
+
+    async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> Result<Self, Self::Error> {
+        let mut values = Vec::with_capacity(SIZE);
+        for _ in 0..SIZE {
+            values.push(T::from_reader(r).await?);
+        }
+        match <[T; SIZE]>::try_from(values) {
+            Ok(array) => Ok(array),
+            Err(_) => unreachable!(),
         }
-        Ok(unary)
     }
+}
 
-    /// Returns true if the stream is aligned at a whole byte.
-    fn byte_aligned(&self) -> bool;
+impl<T: FromBitStream> FromBitStreamWith<usize> for Vec<T> {
+    type Error = T::Error;
 
-    /// Throws away all unread bit values until the next whole byte.
-    /// Does nothing if the stream is already aligned.
-    fn byte_align(&mut self);
+    async fn from_reader<R: BitRead + ?Sized>(r: &mut R, len: &usize) -> Result<Self, Self::Error> {
+        let mut values = Vec::with_capacity(*len);
+        for _ in 0..*len {
+            values.push(T::from_reader(r).await?);
+        }
+        Ok(values)
+    }
 }
 
 /// A trait for anything that can read Huffman codes
 /// of a given endianness from an input stream
-#[async_trait::async_trait]
 pub trait HuffmanRead<E: Endianness> {
     /// Given a compiled Huffman tree, reads bits from the stream
     /// until the next symbol is encountered.
@@ -318,20 +1087,127 @@ pub trait HuffmanRead<E: Endianness> {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> io::Result<T>
+    /// If the stream ends partway through a code, nothing is consumed.
+    /// Reading bits which have no symbol assigned to them in the tree
+    /// consumes those bits and returns an error of kind `InvalidData`.
+    fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> impl Future<Output = io::Result<T>> + Send
     where
         T: Clone + Send + Sync;
+
+    /// Given a table from [`widen_read_tree`](crate::huffman::widen_read_tree),
+    /// reads the next symbol from the stream,
+    /// decoding codes which fit in the table with a single lookup.
+    ///
+    /// This reads exactly the same values as [`HuffmanRead::read_huffman`]
+    /// with the table's tree, which the default implementation uses.
+    ///
+    /// # Errors
+    ///
+    /// As with [`HuffmanRead::read_huffman`].
+    fn read_huffman_wide<T>(
+        &mut self,
+        table: &WideHuffmanTable<E, T>,
+    ) -> impl Future<Output = io::Result<T>> + Send
+    where
+        T: Clone + Send + Sync,
+    {
+        self.read_huffman(table.tree())
+    }
 }
 
 /// For reading non-aligned bits from a stream of bytes in a given endianness.
 ///
-/// This will read exactly as many whole bytes needed to return
-/// the requested number of bits.  It may cache up to a single partial byte
-/// but no more.
+/// By default, this will read exactly as many whole bytes needed to return
+/// the requested number of bits, caching up to a single partial byte.
+/// Some operations read further ahead than the bits they consume:
+///
+/// * [`BitRead::peek_bit`] and [`BitRead::peek`] read the bytes
+///   they look at without consuming them
+/// * [`HuffmanRead::read_huffman_wide`] looks ahead
+///   by the width of its table
+/// * the cancel-safe methods listed below buffer every byte they need
+///   before consuming any, so those bytes stay buffered
+///   if the read fails or is cancelled
+///
+/// A reader created with [`BitReader::with_capacity`] goes further,
+/// filling an internal buffer from the underlying stream in large chunks
+/// and serving bits from memory, which is much faster for streams
+/// where each read is costly, such as files or sockets.
+///
+/// In each case, bytes read ahead are kept in the reader.
+/// [`BitReader::reader`] returns `None` while any are held,
+/// and [`BitReader::into_parts`] hands them back
+/// along with the underlying reader.
+///
+/// The third type parameter holds those buffered bytes
+/// and the count of bytes consumed.
+/// A reader owns its [`ReadBuffer`], while the temporary one returned
+/// by [`ByteReader::bitreader`] borrows its parent's.
+///
+/// # Cancel safety
+///
+/// These methods are cancel safe, so they may be used
+/// as branches of `tokio::select!`:
+///
+/// * [`BitRead::read_bit`], [`BitRead::read`] and [`BitRead::read_signed`]
+/// * [`BitRead::peek_bit`] and [`BitRead::peek`]
+/// * [`BitRead::read_unary0`] and [`BitRead::read_unary1`]
+/// * [`HuffmanRead::read_huffman`] and [`HuffmanRead::read_huffman_wide`]
+///
+/// Each one buffers every byte it needs inside the reader
+/// before consuming any of them.
+/// If its future is dropped before it completes, nothing is consumed,
+/// and the same call may simply be made again.
+///
+/// So that a long run of identical bytes can't make it buffer
+/// an entire stream, a unary read buffers no more than 4096 bytes
+/// of its run, and consumes any longer one as it counts it.
+/// Unary reads of runs longer than that are therefore not cancel safe.
+///
+/// A cancelled [`BitRead::skip`] loses nothing either,
+/// but it is not undone.
+/// As with a dropped [`BitTake`], whatever it had left to skip
+/// is skipped at the start of the next read,
+/// so it should not be repeated.
+///
+/// Other methods, such as [`BitRead::read_bytes`], [`BitRead::parse`]
+/// and the variable-length codes like [`BitRead::read_exp_golomb`],
+/// are made of several reads and are not cancel safe.
+/// Neither are the reads of a [`BitTake`] or a [`ByteReader`].
+///
+/// # Example
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// use std::time::Duration;
+/// use tokio::io::AsyncWriteExt;
+/// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+/// let (mut tx, rx) = tokio::io::duplex(64);
+/// let mut reader = BitReader::endian(rx, BigEndian);
+/// tx.write_all(&[0b1011_0001]).await.unwrap();
+///
+/// // only part of the value is available, so the read times out
+/// tokio::select! {
+///     _ = reader.read::<u16>(12) => unreachable!(),
+///     _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+/// }
+///
+/// // but the byte it had read is still there for the retry
+/// tx.write_all(&[0b1100_0000]).await.unwrap();
+/// assert_eq!(reader.read::<u16>(12).await.unwrap(), 0b1011_0001_1100);
+/// # });
+/// ```
 #[derive(Clone)]
-pub struct BitReader<R: AsyncRead + Unpin + Send + Sync, E: Endianness> {
+pub struct BitReader<
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync = ReadBuffer,
+> {
     reader: R,
+    buffer: B,
     bitqueue: BitQueue<E, u8>,
+    /// Bits left over by a dropped `BitTake`,
+    /// which are skipped before anything else is read
+    skip: u64,
 }
 
 impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitReader<R, E> {
@@ -339,7 +1215,9 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitReader<R, E> {
     pub fn new(reader: R) -> BitReader<R, E> {
         BitReader {
             reader,
+            buffer: ReadBuffer::with_capacity(0),
             bitqueue: BitQueue::new(),
+            skip: 0,
         }
     }
 
@@ -348,7 +1226,39 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitReader<R, E> {
     pub fn endian(reader: R, _endian: E) -> BitReader<R, E> {
         BitReader {
             reader,
+            buffer: ReadBuffer::with_capacity(0),
+            bitqueue: BitQueue::new(),
+            skip: 0,
+        }
+    }
+
+    /// Wraps a BitReader around something that implements `Read`
+    /// which reads ahead from it in chunks of up to `capacity` bytes.
+    ///
+    /// Bytes read ahead but not yet consumed are available
+    /// from [`BitReader::buffer`] and are handed back by
+    /// [`BitReader::into_parts`].
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010, 0xFF];
+    /// let mut reader: BitReader<_, BigEndian> = BitReader::with_capacity(64, Cursor::new(&data));
+    /// assert_eq!(reader.read::<u8>(4).await.unwrap(), 0b1010);
+    /// assert_eq!(reader.buffer(), &[0b0101_1010, 0xFF]);
+    /// assert_eq!(reader.read::<u16>(12).await.unwrap(), 0b0101_0101_1010);
+    /// let (_, rest) = reader.into_parts();
+    /// assert_eq!(rest, [0xFF]);
+    /// # });
+    /// ```
+    pub fn with_capacity(capacity: usize, reader: R) -> BitReader<R, E> {
+        BitReader {
+            reader,
+            buffer: ReadBuffer::with_capacity(capacity),
             bitqueue: BitQueue::new(),
+            skip: 0,
         }
     }
 
@@ -356,17 +1266,85 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitReader<R, E> {
     ///
     /// # Warning
     ///
-    /// Any unread partial bits are discarded.
+    /// Any unread partial bits are discarded,
+    /// as are any whole bytes which have been read ahead
+    /// from the underlying reader but not yet consumed.
+    /// Use [`BitReader::into_parts`] to keep those bytes.
     #[inline]
     pub fn into_reader(self) -> R {
         self.reader
     }
 
-    /// If stream is byte-aligned, provides mutable reference
-    /// to internal reader.  Otherwise returns `None`
+    /// Unwraps internal reader and disposes of BitReader,
+    /// returning it along with any whole bytes which have been
+    /// read ahead from it but not yet consumed.
+    ///
+    /// Those bytes come before whatever is left in the reader,
+    /// so nothing is lost.
+    ///
+    /// # Warning
+    ///
+    /// Any unread partial bits are discarded.
+    #[inline]
+    pub fn into_parts(self) -> (R, Vec<u8>) {
+        (self.reader, self.buffer.into_buffered())
+    }
+}
+
+impl<R, E, B> BitReader<R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    /// Returns the whole bytes which have been read ahead
+    /// from the underlying reader but not yet consumed.
+    ///
+    /// This does not include any unread partial byte.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        self.buffer.borrow().buffered()
+    }
+
+    /// Returns the number of bits consumed since the reader was created,
+    /// which works with any underlying reader, seekable or not.
+    ///
+    /// Bytes read ahead into the buffer are not counted until they
+    /// are consumed, nor are any bytes read directly from
+    /// the underlying reader via [`BitReader::reader`].
+    /// Bytes read by a temporary [`BitReader::bytereader`] are counted,
+    /// and the count is carried over by [`BitReader::into_bytereader`].
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead, ByteRead};
+    /// let data = [0x00, 0xFF, 0x12, 0x34, 0x56];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.bits_read(), 0);
+    /// reader.read::<u8>(5).await.unwrap();
+    /// assert_eq!(reader.bits_read(), 5);
+    /// reader.byte_align();
+    /// assert_eq!(reader.bits_read(), 8);
+    /// reader.skip(9).await.unwrap();
+    /// assert_eq!(reader.bits_read(), 17);
+    /// reader.byte_align();
+    /// reader.bytereader().unwrap().read::<u8>().await.unwrap();
+    /// assert_eq!(reader.bits_read(), 32);
+    /// # });
+    /// ```
+    #[inline]
+    pub fn bits_read(&self) -> u64 {
+        self.buffer.borrow().consumed * 8 - u64::from(self.bitqueue.len()) + self.skip
+    }
+
+    /// If stream is byte-aligned and no bytes have been read ahead,
+    /// provides mutable reference to internal reader.
+    /// Otherwise returns `None`
     #[inline]
     pub fn reader(&mut self) -> Option<&mut R> {
-        if self.byte_aligned() {
+        if self.bitqueue.is_empty() && self.buffer.borrow().is_empty() && self.skip == 0 {
             Some(&mut self.reader)
         } else {
             None
@@ -375,28 +1353,164 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitReader<R, E> {
 
     /// Converts `BitReader` to `ByteReader` in the same endianness.
     ///
+    /// Any bytes which have been read ahead are carried over,
+    /// and the `ByteReader` reads those first.
+    ///
     /// # Warning
     ///
     /// Any unread partial bits are discarded.
     #[inline]
-    pub fn into_bytereader(self) -> ByteReader<R, E> {
-        ByteReader::new(self.into_reader())
+    pub fn into_bytereader(self) -> ByteReader<R, E, B> {
+        ByteReader {
+            phantom: PhantomData,
+            reader: self.reader,
+            buffer: self.buffer,
+        }
     }
 
     /// If stream is byte-aligned, provides temporary `ByteReader`
-    /// in the same endianness.  Otherwise returns `None`
+    /// in the same endianness, which reads any bytes
+    /// read ahead by this reader first.
+    /// Otherwise returns `None`
+    #[inline]
+    pub fn bytereader(&mut self) -> Option<ByteReader<&mut R, E, &mut ReadBuffer>> {
+        if self.bitqueue.is_empty() && self.skip == 0 {
+            Some(ByteReader {
+                phantom: PhantomData,
+                reader: &mut self.reader,
+                buffer: self.buffer.borrow_mut(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reader limited to the next `bits` bits of this one,
+    /// which may start at any position, aligned or not.
     ///
-    /// # Warning
+    /// Reads past the limit fail with `UnexpectedEof`,
+    /// and whatever is left unread
+    /// is skipped once the `BitTake` is finished or dropped,
+    /// leaving this reader just past the limit.
     ///
-    /// Any reader bits left over when `ByteReader` is dropped are lost.
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0110, 0b1111_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(2).await.unwrap(), 0b10);
+    /// {
+    ///     let mut take = reader.take(10);
+    ///     assert_eq!(take.read::<u8>(4).await.unwrap(), 0b1101);
+    ///     assert_eq!(take.remaining(), 6);
+    ///     assert!(take.read::<u8>(7).await.is_err());
+    ///     assert_eq!(take.read::<u8>(3).await.unwrap(), 0b101);
+    /// }
+    /// assert_eq!(reader.read::<u8>(4).await.unwrap(), 0b0000);
+    /// # });
+    /// ```
+    #[inline]
+    pub fn take(&mut self, bits: u64) -> BitTake<'_, R, E, B> {
+        BitTake {
+            reader: self,
+            remaining: bits,
+        }
+    }
+
+    /// Returns a reader limited to the next `bytes` bytes of this one,
+    /// as with [`BitReader::take`].
     #[inline]
-    pub fn bytereader(&mut self) -> Option<ByteReader<&mut R, E>> {
-        self.reader().map(ByteReader::new)
+    pub fn take_bytes(&mut self, bytes: u64) -> BitTake<'_, R, E, B> {
+        self.take(bytes * 8)
+    }
+
+    /// Skips as many of the given bits as possible without reading,
+    /// and leaves the rest to be skipped before the next read.
+    fn defer_skip(&mut self, mut bits: u64) {
+        // the queue and buffer are always empty while a skip is outstanding
+        let queued = min(u64::from(self.bitqueue.len()), bits);
+        if queued > 0 {
+            self.bitqueue.drop(queued as u32);
+            bits -= queued;
+        }
+        let bytes = min(bits / 8, self.buffer.borrow().len() as u64);
+        self.buffer.borrow_mut().consume(bytes as usize);
+        bits -= bytes * 8;
+        if bits > 0 && !self.buffer.borrow().is_empty() {
+            // any bits remaining lie within the next buffered byte
+            self.bitqueue.set(self.buffer.borrow_mut().consume(1)[0], 8);
+            self.bitqueue.drop(bits as u32);
+            bits = 0;
+        }
+        self.skip += bits;
+    }
+
+    /// Performs any outstanding skip, such as one left over by a dropped `BitTake`.
+    ///
+    /// The skip shrinks as bytes arrive,
+    /// so one which is cancelled picks up where it left off.
+    #[inline(always)]
+    async fn settle(&mut self) -> io::Result<()> {
+        while self.skip > 0 {
+            let bytes = min(self.skip.div_ceil(8), max(self.buffer.borrow().capacity, 8) as u64);
+            self.buffer.borrow_mut().fill(&mut self.reader, bytes as usize).await?;
+            let bits = self.skip;
+            self.skip = 0;
+            self.defer_skip(bits);
+        }
+        Ok(())
+    }
+
+    /// Buffers enough bytes for the next `bits` bits without consuming any,
+    /// so that reading that many bits afterward never waits.
+    #[inline(always)]
+    async fn prepare(&mut self, bits: u32) -> io::Result<()> {
+        self.settle().await?;
+        let queued = self.bitqueue.len();
+        if bits > queued {
+            self.buffer.borrow_mut()
+                .fill(&mut self.reader, (bits - queued).div_ceil(8) as usize)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Counts the whole bytes equal to `continue_val`
+    /// and replaces the queue with the first byte which isn't,
+    /// buffering up to [`UNARY_LOOKAHEAD`] of them before consuming any.
+    async fn read_aligned_unary(&mut self, continue_val: u8) -> io::Result<u32> {
+        let mut unary = 0;
+        let mut bytes = 0;
+        loop {
+            if bytes == UNARY_LOOKAHEAD {
+                // a run this long is consumed as it is counted,
+                // so no more than this much of it is held at once
+                self.buffer.borrow_mut().consume(bytes);
+                unary += bytes as u32 * 8;
+                bytes = 0;
+            }
+            self.buffer
+                .borrow_mut()
+                .fill(&mut self.reader, bytes + 1)
+                .await?;
+            if self.buffer.borrow().buffered()[bytes] != continue_val {
+                break;
+            }
+            bytes += 1;
+        }
+        self.bitqueue
+            .set(self.buffer.borrow_mut().consume(bytes + 1)[bytes], 8);
+        Ok(unary + bytes as u32 * 8)
     }
 
     /// Consumes reader and returns any un-read partial byte
     /// as a `(bits, value)` tuple.
     ///
+    /// Whole bytes which have been read ahead are not included;
+    /// see [`BitReader::buffer`].
+    ///
     /// # Examples
     /// ```
     /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
@@ -429,8 +1543,12 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitReader<R, E> {
     }
 }
 
-#[async_trait::async_trait]
-impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R, E> {
+impl<R, E, B> BitRead for BitReader<R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
     /// # Examples
     ///
     /// ```
@@ -468,8 +1586,10 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     /// ```
     #[inline(always)]
     async fn read_bit(&mut self) -> io::Result<bool> {
+        self.settle().await?;
         if self.bitqueue.is_empty() {
-            self.bitqueue.set(read_byte(&mut self.reader).await?, 8);
+            self.bitqueue
+                .set(self.buffer.borrow_mut().read_byte(&mut self.reader).await?, 8);
         }
         Ok(self.bitqueue.pop(1) == 1)
     }
@@ -516,6 +1636,9 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
         U: Numeric,
     {
         if bits <= U::BITS_SIZE {
+            // with every byte buffered beforehand, nothing below waits,
+            // so a cancelled read leaves the stream untouched
+            self.prepare(bits).await?;
             let bitqueue_len = self.bitqueue.len();
             if bits <= bitqueue_len {
                 Ok(U::from_u8(self.bitqueue.pop(bits)))
@@ -524,13 +1647,20 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
                     BitQueue::from_value(U::from_u8(self.bitqueue.pop_all()), bitqueue_len);
                 bits -= bitqueue_len;
 
-                read_aligned(&mut self.reader, bits / 8, &mut acc).await?;
-                read_unaligned(&mut self.reader, bits % 8, &mut acc, &mut self.bitqueue).await?;
+                read_aligned(&mut self.reader, self.buffer.borrow_mut(), bits / 8, &mut acc).await?;
+                read_unaligned(
+                    &mut self.reader,
+                    self.buffer.borrow_mut(),
+                    bits % 8,
+                    &mut acc,
+                    &mut self.bitqueue,
+                )
+                .await?;
                 Ok(acc.value())
             }
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
                 "excessive bits for type read",
             ))
         }
@@ -576,7 +1706,97 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     where
         S: SignedNumeric,
     {
-        E::read_signed(self, bits).await
+        // the sign and the rest are read separately,
+        // so both must be buffered before either is consumed
+        if bits <= S::BITS_SIZE {
+            self.prepare(bits).await?;
+        }
+        E::read_signed(self, bits).await
+    }
+
+    /// # Examples
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10110111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.peek_bit().await.unwrap(), true);
+    /// assert_eq!(reader.read_bit().await.unwrap(), true);
+    /// assert_eq!(reader.peek_bit().await.unwrap(), false);
+    /// assert_eq!(reader.read_bit().await.unwrap(), false);
+    /// # });
+    /// ```
+    async fn peek_bit(&mut self) -> io::Result<bool> {
+        self.settle().await?;
+        if self.bitqueue.is_empty() {
+            self.buffer.borrow_mut().fill(&mut self.reader, 1).await?;
+            let mut next = BitQueue::<E, u8>::from_value(self.buffer.borrow().buffered()[0], 8);
+            Ok(next.pop(1) == 1)
+        } else {
+            Ok(self.bitqueue.clone().pop(1) == 1)
+        }
+    }
+
+    /// Any whole bytes needed to complete the value are read ahead
+    /// and kept in the reader's buffer until they are consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0111, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).await.unwrap(), 0b1011);
+    /// assert_eq!(reader.peek::<u16>(9).await.unwrap(), 0b0111_0101_1);
+    /// assert_eq!(reader.read::<u16>(12).await.unwrap(), 0b0111_0101_1010);
+    /// # });
+    /// ```
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b1011_0111, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read::<u8>(4).await.unwrap(), 0b0111);
+    /// assert_eq!(reader.peek::<u16>(9).await.unwrap(), 0b1_1010_1011);
+    /// assert_eq!(reader.read::<u16>(12).await.unwrap(), 0b0101_1010_1011);
+    /// # });
+    /// ```
+    async fn peek<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        if bits > U::BITS_SIZE {
+            return Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type read",
+            ));
+        }
+
+        self.settle().await?;
+        let mut queue = self.bitqueue.clone();
+        if bits <= queue.len() {
+            return Ok(U::from_u8(queue.pop(bits)));
+        }
+
+        let queue_len = queue.len();
+        let mut acc = BitQueue::<E, U>::from_value(U::from_u8(queue.pop_all()), queue_len);
+        let needed = bits - queue_len;
+        self.buffer.borrow_mut()
+            .fill(&mut self.reader, needed.div_ceil(8) as usize)
+            .await?;
+        let buffered = self.buffer.borrow().buffered();
+        for b in &buffered[0..(needed / 8) as usize] {
+            acc.push(8, U::from_u8(*b));
+        }
+        if needed % 8 != 0 {
+            let mut rem = BitQueue::<E, u8>::from_value(buffered[(needed / 8) as usize], 8);
+            acc.push(needed % 8, U::from_u8(rem.pop(needed % 8)));
+        }
+        Ok(acc.value())
     }
 
     /// # Examples
@@ -601,17 +1821,9 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     /// assert_eq!(reader.read::<u8>(5).await.unwrap(), 0b10110);
     /// # });
     /// ```
-    async fn skip(&mut self, mut bits: u32) -> io::Result<()> {
-        use std::cmp::min;
-
-        let to_drop = min(self.bitqueue.len(), bits);
-        if to_drop != 0 {
-            self.bitqueue.drop(to_drop);
-            bits -= to_drop;
-        }
-
-        skip_aligned(&mut self.reader, bits / 8).await?;
-        skip_unaligned(&mut self.reader, bits % 8, &mut self.bitqueue).await
+    async fn skip(&mut self, bits: u32) -> io::Result<()> {
+        self.defer_skip(u64::from(bits));
+        self.settle().await
     }
 
     /// # Example
@@ -628,9 +1840,9 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     /// # });
     /// ```
     async fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.settle().await?;
         if self.byte_aligned() {
-            self.reader.read_exact(buf).await?;
-            Ok(())
+            self.buffer.borrow_mut().read_exact(&mut self.reader, buf).await
         } else {
             for b in buf.iter_mut() {
                 *b = self.read(8).await?;
@@ -664,14 +1876,12 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     /// # });
     /// ```
     async fn read_unary0(&mut self) -> io::Result<u32> {
-        if self.bitqueue.is_empty() {
-            read_aligned_unary(&mut self.reader, 0b1111_1111, &mut self.bitqueue).await
-                .map(|u| u + self.bitqueue.pop_1())
-        } else if self.bitqueue.all_1() {
+        self.settle().await?;
+        if self.bitqueue.is_empty() || self.bitqueue.all_1() {
+            // the queued bits are replaced only once the rest are buffered
             let base = self.bitqueue.len();
-            self.bitqueue.clear();
-            read_aligned_unary(&mut self.reader, 0b1111_1111, &mut self.bitqueue).await
-                .map(|u| base + u + self.bitqueue.pop_1())
+            let unary = self.read_aligned_unary(0b1111_1111).await?;
+            Ok(base + unary + self.bitqueue.pop_1())
         } else {
             Ok(self.bitqueue.pop_1())
         }
@@ -702,14 +1912,12 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     /// # });
     /// ```
     async fn read_unary1(&mut self) -> io::Result<u32> {
-        if self.bitqueue.is_empty() {
-            read_aligned_unary(&mut self.reader, 0b0000_0000, &mut self.bitqueue).await
-                .map(|u| u + self.bitqueue.pop_0())
-        } else if self.bitqueue.all_0() {
+        self.settle().await?;
+        if self.bitqueue.is_empty() || self.bitqueue.all_0() {
+            // the queued bits are replaced only once the rest are buffered
             let base = self.bitqueue.len();
-            self.bitqueue.clear();
-            read_aligned_unary(&mut self.reader, 0b0000_0000, &mut self.bitqueue).await
-                .map(|u| base + u + self.bitqueue.pop_0())
+            let unary = self.read_aligned_unary(0b0000_0000).await?;
+            Ok(base + unary + self.bitqueue.pop_0())
         } else {
             Ok(self.bitqueue.pop_0())
         }
@@ -731,7 +1939,7 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     /// ```
     #[inline]
     fn byte_aligned(&self) -> bool {
-        self.bitqueue.is_empty()
+        self.bitqueue.is_empty() && self.skip % 8 == 0
     }
 
     /// # Example
@@ -748,15 +1956,23 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> BitRead for BitReader<R,
     /// ```
     #[inline]
     fn byte_align(&mut self) {
-        self.bitqueue.clear()
+        self.bitqueue.clear();
+        self.skip = self.skip.next_multiple_of(8);
     }
 }
 
-impl<R, E> BitReader<R, E>
+impl<R, E, B> BitReader<R, E, B>
 where
     E: Endianness,
     R: AsyncRead + AsyncSeek + Unpin + Send + Sync,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
 {
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput`
+    /// if the new position would be before the start of the stream.
+    ///
     /// # Example
     /// ```
     /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
@@ -782,115 +1998,739 @@ where
             match from {
                 io::SeekFrom::Start(from_start_pos) => {
                     let (bytes, bits) = (from_start_pos / 8, (from_start_pos % 8) as u32);
-                    self.byte_align();
+                    self.bitqueue.clear();
+                    self.skip = 0;
+                    self.buffer.borrow_mut().clear();
                     self.reader.seek(io::SeekFrom::Start(bytes)).await?;
+                    self.buffer.borrow_mut().consumed = bytes;
                     self.skip(bits).await?;
                     return Ok(from_start_pos)
                 }
                 io::SeekFrom::End(from_end_pos) => {
+                    let reader_pos = self.reader.stream_position().await?;
                     let reader_end = self.reader.seek(io::SeekFrom::End(0)).await?;
-                    let new_pos = (reader_end * 8) as i64 - from_end_pos;
-                    assert!(new_pos >= 0, "The final position should be greater than 0");
-                    //self.seek_bits(io::SeekFrom::Start(new_pos as u64)).await
-                    from = io::SeekFrom::Start(new_pos as u64);
+                    match seek_target(reader_end * 8, from_end_pos.wrapping_neg()) {
+                        Ok(position) => from = io::SeekFrom::Start(position),
+                        Err(err) => {
+                            // leave the reader where it was
+                            self.reader.seek(io::SeekFrom::Start(reader_pos)).await?;
+                            return Err(err);
+                        }
+                    }
                     continue;
                 }
                 io::SeekFrom::Current(offset) => {
-                    let new_pos = self.position_in_bits().await? as i64 + offset;
-                    assert!(new_pos >= 0, "The final position should be greater than 0");
-                    //self.seek_bits(io::SeekFrom::Start(new_pos as u64)).await
-                    from = io::SeekFrom::Start(new_pos as u64);
+                    let current = self.position_in_bits().await?;
+                    from = io::SeekFrom::Start(seek_target(current, offset)?);
                     continue;
                 }
             }
         }
     }
 
-    /// # Example
-    /// ```
-    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
-    /// use std::fs::read;
-    /// use std::io::{Cursor, SeekFrom};
-    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
-    /// let data = [0x00, 0xFF];
-    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.position_in_bits().await.unwrap(), 0);
-    ///
-    /// let _: i32 = reader.read_signed(5).await.unwrap();
-    /// assert_eq!(reader.position_in_bits().await.unwrap(), 5);
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::fs::read;
+    /// use std::io::{Cursor, SeekFrom};
+    /// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x00, 0xFF];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.position_in_bits().await.unwrap(), 0);
+    ///
+    /// let _: i32 = reader.read_signed(5).await.unwrap();
+    /// assert_eq!(reader.position_in_bits().await.unwrap(), 5);
+    ///
+    /// reader.read_bit().await.unwrap();
+    /// assert_eq!(reader.position_in_bits().await.unwrap(), 6);
+    /// # });
+    /// ```
+    #[inline]
+    pub async fn position_in_bits(&mut self) -> io::Result<u64> {
+        let bytes = self.reader.stream_position().await? - self.buffer.borrow().len() as u64;
+        Ok(bytes * 8 - (self.bitqueue.len() as u64) + self.skip)
+    }
+}
+
+impl<R, E, B> HuffmanRead<E> for BitReader<R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, HuffmanRead};
+    /// use tokio_bitstream_io::huffman::compile_read_tree;
+    /// let tree = compile_read_tree(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1, 0]),
+    ///          ('d', vec![1, 1, 1])]).unwrap();
+    /// let data = [0b10110111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'b');
+    /// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'c');
+    /// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'd');
+    /// # });
+    /// ```
+    async fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> io::Result<T>
+    where
+        T: Clone + Send + Sync,
+    {
+        let mut limit = u64::MAX;
+        self.read_huffman_within(tree, None, &mut limit).await
+    }
+
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitReader, HuffmanRead};
+    /// use tokio_bitstream_io::huffman::{compile_read_tree, widen_read_tree};
+    /// let tree = compile_read_tree(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1, 0]),
+    ///          ('d', vec![1, 1, 1])]).unwrap();
+    /// let table = widen_read_tree(tree, 4).unwrap();
+    /// let data = [0b10110111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 'b');
+    /// assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 'c');
+    /// assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 'd');
+    /// # });
+    /// ```
+    async fn read_huffman_wide<T>(&mut self, table: &WideHuffmanTable<E, T>) -> io::Result<T>
+    where
+        T: Clone + Send + Sync,
+    {
+        let mut limit = u64::MAX;
+        self.read_huffman_within(table.tree(), Some(table), &mut limit)
+            .await
+    }
+}
+
+impl<R, E, B> BitReader<R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    /// Reads a Huffman code only if it is no longer than `limit` bits,
+    /// subtracting the number of bits consumed from `limit`,
+    /// trying the wide table first if there is one
+    async fn read_huffman_within<T>(
+        &mut self,
+        tree: &[ReadHuffmanTree<E, T>],
+        table: Option<&WideHuffmanTable<E, T>>,
+        limit: &mut u64,
+    ) -> io::Result<T>
+    where
+        T: Clone + Send + Sync,
+    {
+        self.settle().await?;
+        if let Some(table) = table {
+            if let Some(value) = self.lookup_huffman_wide(table, limit).await? {
+                return Ok(value);
+            }
+        }
+        let queued = u64::from(self.bitqueue.len());
+        let mut bytes = 0;
+        let mut result = tree
+            .get(self.bitqueue.to_state())
+            .unwrap_or(&ReadHuffmanTree::InvalidState);
+        loop {
+            match result {
+                ReadHuffmanTree::Done(ref value, ref queue_val, ref queue_bits, _) => {
+                    let bits = queued + bytes as u64 * 8 - u64::from(*queue_bits);
+                    if bits > *limit {
+                        return Err(limit_reached());
+                    }
+                    self.buffer.borrow_mut().consume(bytes);
+                    self.bitqueue.set(*queue_val, *queue_bits);
+                    *limit -= bits;
+                    return Ok(value.clone());
+                }
+                ReadHuffmanTree::Continue(ref tree) => {
+                    if queued + bytes as u64 * 8 >= *limit {
+                        return Err(limit_reached());
+                    }
+                    // look ahead without consuming anything
+                    // until the length of the code is known
+                    self.buffer.borrow_mut().fill(&mut self.reader, bytes + 1).await?;
+                    result = &tree[self.buffer.borrow().buffered()[bytes] as usize];
+                    bytes += 1;
+                }
+                ReadHuffmanTree::Unassigned(ref prefix, ref queue_val, ref queue_bits, _) => {
+                    let bits = queued + bytes as u64 * 8 - u64::from(*queue_bits);
+                    if bits > *limit {
+                        return Err(limit_reached());
+                    }
+                    self.buffer.borrow_mut().consume(bytes);
+                    self.bitqueue.set(*queue_val, *queue_bits);
+                    *limit -= bits;
+                    return Err(unassigned_code(prefix));
+                }
+                ReadHuffmanTree::InvalidState => {
+                    return Err(io_error(
+                        BitstreamErrorKind::InvalidCode,
+                        "invalid Huffman reader state",
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl<R, E, B> BitReader<R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    /// Reads a Huffman code with a single lookup in a wide table,
+    /// returning `None` without consuming anything if the code
+    /// is longer than the table or too few bits remain to fill it
+    async fn lookup_huffman_wide<T>(
+        &mut self,
+        table: &WideHuffmanTable<E, T>,
+        limit: &mut u64,
+    ) -> io::Result<Option<T>>
+    where
+        T: Clone + Send + Sync,
+    {
+        let queued = self.bitqueue.len();
+        let bytes = table.width().saturating_sub(queued).div_ceil(8) as usize;
+        if self.buffer.borrow().len() < bytes {
+            match self.buffer.borrow_mut().fill(&mut self.reader, bytes).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(err) => return Err(err),
+            }
+        }
+
+        let window = table.window(
+            self.bitqueue.clone().value(),
+            queued,
+            &self.buffer.borrow().buffered()[0..bytes],
+        );
+        let (len, result) = match table.entry(&window) {
+            WideEntry::Done(ref value, len) => (*len, Ok(value.clone())),
+            WideEntry::Unassigned(ref prefix, len) => (*len, Err(unassigned_code(prefix))),
+            WideEntry::Long => return Ok(None),
+        };
+        if u64::from(len) > *limit {
+            return Err(limit_reached());
+        }
+
+        // whole bytes left over stay buffered
+        // and the rest of a partial byte goes back into the queue
+        let (whole, queue_val, queue_bits) = table.consume(&window, len);
+        self.buffer.borrow_mut().consume(bytes - whole);
+        self.bitqueue.set(queue_val, queue_bits);
+        *limit -= u64::from(len);
+        result.map(Some)
+    }
+}
+
+/// A reader limited to a fixed number of bits of a `BitReader`,
+/// returned by [`BitReader::take`] and [`BitReader::take_bytes`].
+///
+/// This is meant for length-prefixed payloads such as
+/// MP4 boxes or FLAC metadata blocks, which must not read
+/// into whatever follows them.
+/// Any read which would go past the limit fails with `UnexpectedEof`.
+/// Reads of a known size, including Huffman codes, check the limit
+/// beforehand and consume nothing if it fails, while unary values
+/// are read a bit at a time until they reach it.
+///
+/// Any bits left unread are skipped by [`BitTake::finish`].
+/// They are also skipped if the `BitTake` is simply dropped,
+/// but since that cannot wait on the underlying reader,
+/// bits which have not already been read ahead are skipped
+/// at the start of the next read from the parent instead,
+/// and any error doing so is reported by that read.
+///
+/// # Example
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// use std::io::Cursor;
+/// use tokio_bitstream_io::{BigEndian, BitReader, BitRead};
+/// // a pair of boxes, each with a 1 byte size (including itself)
+/// // and a payload of 16-bit values
+/// let data = [6, 0x00, 0x01, 0x00, 0x02, 0xFF, 3, 0x00, 0x03];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// let mut values = Vec::new();
+/// while values.len() < 3 {
+///     let size = reader.read::<u64>(8).await.unwrap();
+///     let mut payload = reader.take_bytes(size - 1);
+///     while payload.remaining() >= 16 {
+///         values.push(payload.read::<u16>(16).await.unwrap());
+///     }
+///     // the odd byte at the end of the first box is skipped
+///     payload.finish().await.unwrap();
+/// }
+/// assert_eq!(values, [1, 2, 3]);
+/// # });
+/// ```
+pub struct BitTake<
+    'a,
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync = ReadBuffer,
+> {
+    reader: &'a mut BitReader<R, E, B>,
+    remaining: u64,
+}
+
+impl<'a, R, E, B> BitTake<'a, R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    /// Returns the number of bits left before the limit
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Skips any bits left before the limit,
+    /// leaving the parent reader just past it.
+    pub async fn finish(mut self) -> io::Result<()> {
+        let remaining = self.remaining;
+        self.remaining = 0;
+        self.reader.defer_skip(remaining);
+        self.reader.settle().await
+    }
+
+    #[inline]
+    fn check(&self, bits: u64) -> io::Result<()> {
+        if bits <= self.remaining {
+            Ok(())
+        } else {
+            Err(limit_reached())
+        }
+    }
+}
+
+impl<'a, R, E, B> Drop for BitTake<'a, R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    fn drop(&mut self) {
+        self.reader.defer_skip(self.remaining);
+    }
+}
+
+impl<'a, R, E, B> BitRead for BitTake<'a, R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    async fn read_bit(&mut self) -> io::Result<bool> {
+        self.check(1)?;
+        let bit = self.reader.read_bit().await?;
+        self.remaining -= 1;
+        Ok(bit)
+    }
+
+    async fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        self.check(u64::from(bits))?;
+        let value = self.reader.read(bits).await?;
+        self.remaining -= u64::from(bits);
+        Ok(value)
+    }
+
+    async fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        self.check(u64::from(bits))?;
+        let value = self.reader.read_signed(bits).await?;
+        self.remaining -= u64::from(bits);
+        Ok(value)
+    }
+
+    async fn peek_bit(&mut self) -> io::Result<bool> {
+        self.check(1)?;
+        self.reader.peek_bit().await
+    }
+
+    async fn peek<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        self.check(u64::from(bits))?;
+        self.reader.peek(bits).await
+    }
+
+    async fn skip(&mut self, bits: u32) -> io::Result<()> {
+        self.check(u64::from(bits))?;
+        self.reader.skip(bits).await?;
+        self.remaining -= u64::from(bits);
+        Ok(())
+    }
+
+    async fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let bits = buf.len() as u64 * 8;
+        self.check(bits)?;
+        self.reader.read_bytes(buf).await?;
+        self.remaining -= bits;
+        Ok(())
+    }
+
+    async fn read_unary0(&mut self) -> io::Result<u32> {
+        // a bit at a time, so as not to read past the limit
+        let mut unary = 0;
+        while self.read_bit().await? {
+            unary += 1;
+        }
+        Ok(unary)
+    }
+
+    async fn read_unary1(&mut self) -> io::Result<u32> {
+        let mut unary = 0;
+        while !self.read_bit().await? {
+            unary += 1;
+        }
+        Ok(unary)
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.reader.byte_aligned()
+    }
+
+    /// Aligns to the next whole byte of the parent reader,
+    /// or to the limit if that comes first.
+    fn byte_align(&mut self) {
+        let padding = (8 - self.reader.bits_read() % 8) % 8;
+        let padding = min(padding, self.remaining);
+        self.reader.defer_skip(padding);
+        self.remaining -= padding;
+    }
+}
+
+impl<'a, R, E, B> HuffmanRead<E> for BitTake<'a, R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+    async fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> io::Result<T>
+    where
+        T: Clone + Send + Sync,
+    {
+        self.reader
+            .read_huffman_within(tree, None, &mut self.remaining)
+            .await
+    }
+
+    async fn read_huffman_wide<T>(&mut self, table: &WideHuffmanTable<E, T>) -> io::Result<T>
+    where
+        T: Clone + Send + Sync,
+    {
+        self.reader
+            .read_huffman_within(table.tree(), Some(table), &mut self.remaining)
+            .await
+    }
+}
+
+#[inline]
+/// Returns the bit position `offset` bits from `base`
+pub(crate) fn seek_target(base: u64, offset: i64) -> io::Result<u64> {
+    match (base as i64).checked_add(offset) {
+        Some(position) if position >= 0 => Ok(position as u64),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot seek before the start of the stream",
+        )),
+    }
+}
+
+fn limit_reached() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "read past limit")
+}
+
+pub(crate) fn unassigned_code(prefix: &[u8]) -> io::Error {
+    let prefix: String = prefix
+        .iter()
+        .map(|bit| if *bit == 0 { '0' } else { '1' })
+        .collect();
+    BitstreamError::new(
+        BitstreamErrorKind::InvalidCode,
+        format!("no Huffman code for bits {}", prefix),
+    )
+    .into()
+}
+
+/// Accumulates the 7-bit groups of a little-endian base 128 value
+#[derive(Default)]
+struct Leb128 {
+    value: u128,
+    shift: u32,
+}
+
+impl Leb128 {
+    /// Adds the next byte to the value,
+    /// returning true if it is the final one
+    fn push(&mut self, byte: u8, signed: bool) -> io::Result<bool> {
+        let group = u128::from(byte & 0x7F);
+        let negative = |value: u128| signed && (value >> 127) == 1;
+        if self.shift < 128 {
+            self.value |= group << self.shift;
+            if self.shift > 121 {
+                // some of this group's bits lie beyond 128
+                let extra = group >> (128 - self.shift);
+                let expected = if negative(self.value) {
+                    0x7F >> (128 - self.shift)
+                } else {
+                    0
+                };
+                if extra != expected {
+                    return Err(varint_overflow());
+                }
+            }
+        } else if group != if negative(self.value) { 0x7F } else { 0 } {
+            return Err(varint_overflow());
+        }
+        self.shift = min(self.shift + 7, 128);
+
+        if byte & 0x80 != 0 {
+            Ok(false)
+        } else {
+            if signed && (byte & 0x40) != 0 && self.shift < 128 {
+                self.value |= !0 << self.shift;
+            }
+            Ok(true)
+        }
+    }
+}
+
+/// Adds the next byte of a big-endian base 128 value,
+/// returning true if it is the final one
+fn push_vlq(value: &mut u128, byte: u8) -> io::Result<bool> {
+    if (*value >> 121) != 0 {
+        Err(varint_overflow())
+    } else {
+        *value = (*value << 7) | u128::from(byte & 0x7F);
+        Ok(byte & 0x80 == 0)
+    }
+}
+
+/// Given the first byte of a QUIC variable-length integer,
+/// returns its total size in bytes
+#[inline]
+fn quic_varint_len(byte: u8) -> usize {
+    1 << (byte >> 6)
+}
+
+fn unsigned_varint<N: Numeric>(value: u128) -> io::Result<N> {
+    numeric_from_u128(value).ok_or_else(varint_overflow)
+}
+
+fn signed_varint<S: SignedNumeric>(value: u128) -> io::Result<S> {
+    numeric_from_i128(value as i128).ok_or_else(varint_overflow)
+}
+
+#[inline]
+fn varint_overflow() -> io::Error {
+    // the stream's value is at fault rather than the caller's
+    BitstreamError::new(
+        BitstreamErrorKind::ExcessiveValue,
+        "excessive variable-length value for type read",
+    )
+    .with_io_kind(io::ErrorKind::InvalidData)
+    .into()
+}
+
+/// Returns the number of bits in the longer
+/// truncated binary remainders of a Golomb code with divisor `m`
+#[inline]
+pub(crate) fn golomb_remainder_bits(m: u64) -> u32 {
+    64 - (m - 1).leading_zeros()
+}
+
+/// The most whole bytes of a unary value's run which
+/// [`BitRead::read_unary0`] and [`BitRead::read_unary1`]
+/// buffer before they start consuming them
+const UNARY_LOOKAHEAD: usize = 4096;
+
+/// Whole bytes read ahead from a `BitReader`'s underlying reader
+/// but not yet consumed, along with a count of those which have been.
+///
+/// Each `BitReader` and `ByteReader` owns one of these.
+/// The temporary readers returned by [`BitReader::bytereader`]
+/// and [`ByteReader::bitreader`] borrow their parent's instead,
+/// so that whatever they read ahead or consume is kept track of there.
+#[derive(Clone)]
+pub struct ReadBuffer {
+    buf: Vec<u8>,
+    pos: usize,
+    end: usize,
+    capacity: usize,
+    /// Whole bytes consumed from the buffer or read past it
+    consumed: u64,
+}
+
+impl ReadBuffer {
+    fn with_capacity(capacity: usize) -> ReadBuffer {
+        ReadBuffer {
+            buf: vec![0; capacity],
+            pos: 0,
+            end: 0,
+            capacity,
+            consumed: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.end - self.pos
+    }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.pos == self.end
+    }
+
+    #[inline(always)]
+    fn buffered(&self) -> &[u8] {
+        &self.buf[self.pos..self.end]
+    }
+
+    #[inline]
+    fn into_buffered(mut self) -> Vec<u8> {
+        self.buf.truncate(self.end);
+        self.buf.drain(0..self.pos);
+        self.buf
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.pos = 0;
+        self.end = 0;
+    }
+
+    /// Removes the given number of buffered bytes and returns them.
+    /// Panics if fewer bytes than that are buffered.
+    #[inline]
+    fn consume(&mut self, bytes: usize) -> &[u8] {
+        assert!(bytes <= self.len());
+        let start = self.pos;
+        self.pos += bytes;
+        self.consumed += bytes as u64;
+        &self.buf[start..self.pos]
+    }
+
+    /// Reads from the underlying reader until at least `bytes`
+    /// are buffered, reading no more than the larger of `bytes`
+    /// and the buffer's capacity.
     ///
-    /// reader.read_bit().await.unwrap();
-    /// assert_eq!(reader.position_in_bits().await.unwrap(), 6);
-    /// # });
-    /// ```
+    /// Bytes are kept as they arrive, so this may be
+    /// cancelled and retried without losing any.
     #[inline]
-    pub async fn position_in_bits(&mut self) -> io::Result<u64> {
-        let bytes = self.reader.stream_position().await?;
-        Ok(bytes * 8 - (self.bitqueue.len() as u64))
+    async fn fill<R>(&mut self, reader: &mut R, bytes: usize) -> io::Result<()>
+    where
+        R: AsyncRead + Unpin + Send + Sync,
+    {
+        if self.len() >= bytes {
+            return Ok(());
+        }
+        poll_fn(|cx| self.poll_fill(cx, reader, bytes)).await
     }
-}
 
-#[async_trait::async_trait]
-impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> HuffmanRead<E> for BitReader<R, E> {
-    /// # Example
-    /// ```
-    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
-    /// use std::io::Cursor;
-    /// use tokio_bitstream_io::{BigEndian, BitReader, HuffmanRead};
-    /// use tokio_bitstream_io::huffman::compile_read_tree;
-    /// let tree = compile_read_tree(
-    ///     vec![('a', vec![0]),
-    ///          ('b', vec![1, 0]),
-    ///          ('c', vec![1, 1, 0]),
-    ///          ('d', vec![1, 1, 1])]).unwrap();
-    /// let data = [0b10110111];
-    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'b');
-    /// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'c');
-    /// assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'd');
-    /// # });
-    /// ```
-    async fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> io::Result<T>
+    fn poll_fill<R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        reader: &mut R,
+        bytes: usize,
+    ) -> Poll<io::Result<()>>
     where
-        T: Clone + Send + Sync,
+        R: AsyncRead + Unpin + Send + Sync,
     {
-        let mut result: &ReadHuffmanTree<E, T> = &tree[self.bitqueue.to_state()];
-        loop {
-            match result {
-                ReadHuffmanTree::Done(ref value, ref queue_val, ref queue_bits, _) => {
-                    self.bitqueue.set(*queue_val, *queue_bits);
-                    return Ok(value.clone());
-                }
-                ReadHuffmanTree::Continue(ref tree) => {
-                    result = &tree[read_byte(&mut self.reader).await? as usize];
-                }
-                ReadHuffmanTree::InvalidState => {
-                    panic!("invalid state");
+        if self.len() >= bytes {
+            return Poll::Ready(Ok(()));
+        }
+
+        let limit = max(self.capacity, bytes);
+        if self.pos > 0 {
+            self.buf.copy_within(self.pos..self.end, 0);
+            self.end -= self.pos;
+            self.pos = 0;
+        }
+        if self.buf.len() < limit {
+            self.buf.resize(limit, 0);
+        }
+        while self.end < bytes {
+            let mut buf = ReadBuf::new(&mut self.buf[self.end..limit]);
+            match Pin::new(&mut *reader).poll_read(cx, &mut buf) {
+                Poll::Ready(Ok(())) if buf.filled().is_empty() => {
+                    return Poll::Ready(Err(io_error(
+                        BitstreamErrorKind::UnexpectedEof,
+                        "early eof",
+                    )))
                 }
+                Poll::Ready(Ok(())) => self.end += buf.filled().len(),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
             }
         }
+        Poll::Ready(Ok(()))
     }
-}
 
-#[inline]
-async fn read_byte<R>(mut reader: R) -> io::Result<u8>
-where
-    R: AsyncRead + Unpin + Send + Sync,
-{
-    let mut buf = [0; 1];
-    reader.read_exact(&mut buf).await.map(|_| buf[0])
+    #[inline]
+    async fn read_byte<R>(&mut self, reader: &mut R) -> io::Result<u8>
+    where
+        R: AsyncRead + Unpin + Send + Sync,
+    {
+        self.fill(reader, 1).await?;
+        Ok(self.consume(1)[0])
+    }
+
+    async fn read_exact<R>(&mut self, reader: &mut R, buf: &mut [u8]) -> io::Result<()>
+    where
+        R: AsyncRead + Unpin + Send + Sync,
+    {
+        let buffered = min(self.len(), buf.len());
+        let (head, rest) = buf.split_at_mut(buffered);
+        head.copy_from_slice(self.consume(buffered));
+        if rest.len() >= self.capacity {
+            // too large to be worth buffering, so read it directly
+            reader.read_exact(rest).await?;
+            self.consumed += rest.len() as u64;
+        } else if !rest.is_empty() {
+            self.fill(reader, rest.len()).await?;
+            rest.copy_from_slice(self.consume(rest.len()));
+        }
+        Ok(())
+    }
 }
 
-async fn read_aligned<R, E, N>(mut reader: R, bytes: u32, acc: &mut BitQueue<E, N>) -> io::Result<()>
+async fn read_aligned<R, E, N>(
+    reader: &mut R,
+    buffer: &mut ReadBuffer,
+    bytes: u32,
+    acc: &mut BitQueue<E, N>,
+) -> io::Result<()>
 where
     R: AsyncRead + Unpin + Send + Sync,
     E: Endianness,
     N: Numeric,
 {
     if bytes > 0 {
-        let mut buf = N::buffer();
-        reader.read_exact(&mut buf.as_mut()[0..bytes as usize]).await?;
-        for b in &buf.as_ref()[0..bytes as usize] {
+        buffer.fill(reader, bytes as usize).await?;
+        for b in buffer.consume(bytes as usize) {
             acc.push(8, N::from_u8(*b));
         }
     }
@@ -901,8 +2741,6 @@ async fn skip_aligned<R>(mut reader: R, mut bytes: u32) -> io::Result<()>
 where
     R: AsyncRead + Unpin + Send + Sync,
 {
-    use std::cmp::min;
-
     /*skip up to 8 bytes at a time
     (unlike with read_aligned, "bytes" may be larger than any native type)*/
     let mut buf = [0; 8];
@@ -916,7 +2754,8 @@ where
 
 #[inline]
 async fn read_unaligned<R, E, N>(
-    reader: R,
+    reader: &mut R,
+    buffer: &mut ReadBuffer,
     bits: u32,
     acc: &mut BitQueue<E, N>,
     rem: &mut BitQueue<E, u8>,
@@ -929,50 +2768,14 @@ where
     debug_assert!(bits <= 8);
 
     if bits > 0 {
-        rem.set(read_byte(reader).await?, 8);
+        rem.set(buffer.read_byte(reader).await?, 8);
         acc.push(bits, N::from_u8(rem.pop(bits)));
     }
     Ok(())
 }
 
-#[inline]
-async fn skip_unaligned<R, E>(reader: R, bits: u32, rem: &mut BitQueue<E, u8>) -> io::Result<()>
-where
-    R: AsyncRead + Unpin + Send + Sync,
-    E: Endianness,
-{
-    debug_assert!(bits <= 8);
-
-    if bits > 0 {
-        rem.set(read_byte(reader).await?, 8);
-        rem.pop(bits);
-    }
-    Ok(())
-}
-
-#[inline]
-async fn read_aligned_unary<R, E>(
-    mut reader: R,
-    continue_val: u8,
-    rem: &mut BitQueue<E, u8>,
-) -> io::Result<u32>
-where
-    R: AsyncRead + Unpin + Send + Sync,
-    E: Endianness,
-{
-    let mut acc = 0;
-    let mut byte = read_byte(&mut reader).await?;
-    while byte == continue_val {
-        acc += 8;
-        byte = read_byte(&mut reader).await?;
-    }
-    rem.set(byte, 8);
-    Ok(acc)
-}
-
 /// A trait for anything that can read aligned values from an input stream
-#[async_trait::async_trait]
-pub trait ByteRead {
+pub trait ByteRead: Send {
     /// Reads whole numeric value from stream
     ///
     /// # Errors
@@ -999,18 +2802,20 @@ pub trait ByteRead {
     /// assert_eq!(reader.read::<u16>().await.unwrap(), 0b1111111100000000);
     /// # });
     /// ```
-    async fn read<N: Numeric>(&mut self) -> Result<N, io::Error>;
+    fn read<N: Numeric>(&mut self) -> impl Future<Output = Result<N, io::Error>> + Send;
 
     /// Completely fills the given buffer with whole bytes.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
-        for b in buf.iter_mut() {
-            *b = self.read().await?;
+    fn read_bytes(&mut self, buf: &mut [u8]) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            for b in buf.iter_mut() {
+                *b = self.read().await?;
+            }
+            Ok(())
         }
-        Ok(())
     }
 
     /// Completely fills a whole buffer with bytes and returns it.
@@ -1018,10 +2823,12 @@ pub trait ByteRead {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn read_to_bytes<const SIZE: usize>(&mut self) -> io::Result<[u8; SIZE]> {
-        let mut buf = [0; SIZE];
-        self.read_bytes(&mut buf).await?;
-        Ok(buf)
+    fn read_to_bytes<const SIZE: usize>(&mut self) -> impl Future<Output = io::Result<[u8; SIZE]>> + Send {
+        async move {
+            let mut buf = [0; SIZE];
+            self.read_bytes(&mut buf).await?;
+            Ok(buf)
+        }
     }
 
     /// Completely fills a vector of bytes and returns it.
@@ -1029,10 +2836,12 @@ pub trait ByteRead {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn read_to_vec(&mut self, bytes: usize) -> io::Result<Vec<u8>> {
-        let mut buf = vec![0; bytes];
-        self.read_bytes(&mut buf).await?;
-        Ok(buf)
+    fn read_to_vec(&mut self, bytes: usize) -> impl Future<Output = io::Result<Vec<u8>>> + Send {
+        async move {
+            let mut buf = vec![0; bytes];
+            self.read_bytes(&mut buf).await?;
+            Ok(buf)
+        }
     }
 
     /// Skips the given number of bytes in the stream.
@@ -1040,15 +2849,151 @@ pub trait ByteRead {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn skip(&mut self, bytes: u32) -> io::Result<()>;
+    fn skip(&mut self, bytes: u32) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Reads an unsigned LEB128 value, as used by WebAssembly,
+    /// DWARF and Protocol Buffers.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    ///
+    /// # Examples
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, ByteReader, ByteRead};
+    /// let data = [0xE5, 0x8E, 0x26, 0x7F];
+    /// let mut reader = ByteReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_leb128::<u32>().await.unwrap(), 624485);
+    /// assert_eq!(reader.read_leb128::<u8>().await.unwrap(), 127);
+    /// # });
+    /// ```
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::{Cursor, ErrorKind};
+    /// use tokio_bitstream_io::{BigEndian, ByteReader, ByteRead};
+    /// let data = [0xE5, 0x8E, 0x26];
+    /// let mut reader = ByteReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_leb128::<u16>().await.unwrap_err().kind(), ErrorKind::InvalidData);
+    /// # });
+    /// ```
+    fn read_leb128<N: Numeric>(&mut self) -> impl Future<Output = io::Result<N>> + Send {
+        async move {
+            let mut leb128 = Leb128::default();
+            while !leb128.push(self.read::<u8>().await?, false)? {}
+            unsigned_varint(leb128.value)
+        }
+    }
+
+    /// Reads a signed LEB128 value, as used by WebAssembly and DWARF.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, ByteReader, ByteRead};
+    /// let data = [0xC0, 0xBB, 0x78, 0x3F, 0x40];
+    /// let mut reader = ByteReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_signed_leb128::<i32>().await.unwrap(), -123456);
+    /// assert_eq!(reader.read_signed_leb128::<i8>().await.unwrap(), 63);
+    /// assert_eq!(reader.read_signed_leb128::<i8>().await.unwrap(), -64);
+    /// # });
+    /// ```
+    fn read_signed_leb128<S: SignedNumeric>(&mut self) -> impl Future<Output = io::Result<S>> + Send {
+        async move {
+            let mut leb128 = Leb128::default();
+            while !leb128.push(self.read::<u8>().await?, true)? {}
+            signed_varint(leb128.value)
+        }
+    }
+
+    /// Reads a big-endian variable-length quantity
+    /// of 7-bit groups, as used by MIDI.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, ByteReader, ByteRead};
+    /// let data = [0xC0, 0x00, 0xFF, 0xFF, 0xFF, 0x7F];
+    /// let mut reader = ByteReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_vlq::<u32>().await.unwrap(), 0x2000);
+    /// assert_eq!(reader.read_vlq::<u32>().await.unwrap(), 0x0FFF_FFFF);
+    /// # });
+    /// ```
+    fn read_vlq<N: Numeric>(&mut self) -> impl Future<Output = io::Result<N>> + Send {
+        async move {
+            let mut value = 0;
+            while !push_vlq(&mut value, self.read::<u8>().await?)? {}
+            unsigned_varint(value)
+        }
+    }
+
+    /// Reads a QUIC variable-length integer,
+    /// whose first 2 bits give its size as 1, 2, 4 or 8 bytes.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an `InvalidData` error if the value does not fit
+    /// in the output type.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, ByteReader, ByteRead};
+    /// let data = [0x25, 0x7B, 0xBD, 0x9D, 0x7F, 0x3E, 0x7D];
+    /// let mut reader = ByteReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_quic_varint::<u8>().await.unwrap(), 37);
+    /// assert_eq!(reader.read_quic_varint::<u16>().await.unwrap(), 15293);
+    /// assert_eq!(reader.read_quic_varint::<u32>().await.unwrap(), 494878333);
+    /// # });
+    /// ```
+    fn read_quic_varint<N: Numeric>(&mut self) -> impl Future<Output = io::Result<N>> + Send {
+        async move {
+            let first = self.read::<u8>().await?;
+            let mut rest = [0; 7];
+            let rest = &mut rest[0..quic_varint_len(first) - 1];
+            self.read_bytes(rest).await?;
+            unsigned_varint(
+                rest.iter()
+                    .fold(u128::from(first & 0x3F), |acc, b| (acc << 8) | u128::from(*b)),
+            )
+        }
+    }
 }
 
 /// For reading aligned bytes from a stream of bytes in a given endianness.
 ///
-/// This only reads aligned values and maintains no internal state.
-pub struct ByteReader<R: AsyncRead + Unpin + Send + Sync, E: Endianness> {
+/// This only reads aligned values and maintains no internal state
+/// beyond a count of the bytes read.
+/// As with [`BitReader`], the third type parameter holds that count,
+/// and is borrowed from the parent by [`BitReader::bytereader`].
+pub struct ByteReader<
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync = ReadBuffer,
+> {
     phantom: PhantomData<E>,
     reader: R,
+    buffer: B,
 }
 
 impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> ByteReader<R, E> {
@@ -1057,6 +3002,7 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> ByteReader<R, E> {
         ByteReader {
             phantom: PhantomData,
             reader,
+            buffer: ReadBuffer::with_capacity(0),
         }
     }
 
@@ -1066,25 +3012,94 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> ByteReader<R, E> {
         ByteReader {
             phantom: PhantomData,
             reader,
+            buffer: ReadBuffer::with_capacity(0),
         }
     }
 
     /// Unwraps internal reader and disposes of `ByteReader`.
+    ///
+    /// # Warning
+    ///
+    /// Any whole bytes carried over from a `BitReader`
+    /// which have not been read yet are discarded.
+    /// Use [`ByteReader::into_parts`] to keep those bytes.
     #[inline]
     pub fn into_reader(self) -> R {
         self.reader
     }
 
-    /// Provides mutable reference to internal reader
+    /// Unwraps internal reader and disposes of `ByteReader`,
+    /// returning it along with any whole bytes carried over
+    /// from a `BitReader` which have not been read yet.
+    ///
+    /// Those bytes come before whatever is left in the reader,
+    /// so nothing is lost.
+    #[inline]
+    pub fn into_parts(self) -> (R, Vec<u8>) {
+        (self.reader, self.buffer.into_buffered())
+    }
+}
+
+impl<R, E, B> ByteReader<R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
+
+    /// Returns the number of bytes read since the reader was created,
+    /// which works with any underlying reader, seekable or not.
+    ///
+    /// Bytes read directly from the underlying reader
+    /// via [`ByteReader::reader`] are not counted.
+    /// Bytes consumed by a temporary [`ByteReader::bitreader`] are counted,
+    /// including any partial byte it was reading from,
+    /// and the count is carried over by [`ByteReader::into_bitreader`].
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitRead, ByteReader, ByteRead};
+    /// let data = [0x00, 0x01, 0x02, 0x03, 0x04];
+    /// let mut reader = ByteReader::endian(Cursor::new(&data), BigEndian);
+    /// reader.read::<u16>().await.unwrap();
+    /// assert_eq!(reader.bytes_read(), 2);
+    /// reader.bitreader().read::<u8>(4).await.unwrap();
+    /// assert_eq!(reader.bytes_read(), 3);
+    /// let mut reader = reader.into_bitreader();
+    /// reader.read::<u8>(4).await.unwrap();
+    /// assert_eq!(reader.bits_read(), 28);
+    /// # });
+    /// ```
+    #[inline]
+    pub fn bytes_read(&self) -> u64 {
+        self.buffer.borrow().consumed
+    }
+
+    /// If no bytes carried over from a `BitReader` by
+    /// [`BitReader::into_bytereader`] are waiting to be read,
+    /// provides mutable reference to internal reader.
+    /// Otherwise returns `None`, since reading from it directly
+    /// would skip those bytes.
     #[inline]
-    pub fn reader(&mut self) -> &mut R {
-        &mut self.reader
+    pub fn reader(&mut self) -> Option<&mut R> {
+        if self.buffer.borrow().is_empty() {
+            Some(&mut self.reader)
+        } else {
+            None
+        }
     }
 
     /// Converts `ByteReader` to `BitReader` in the same endianness.
     #[inline]
-    pub fn into_bitreader(self) -> BitReader<R, E> {
-        BitReader::new(self.into_reader())
+    pub fn into_bitreader(self) -> BitReader<R, E, B> {
+        BitReader {
+            reader: self.reader,
+            buffer: self.buffer,
+            bitqueue: BitQueue::new(),
+            skip: 0,
+        }
     }
 
     /// Provides temporary `BitReader` in the same endianness.
@@ -1093,26 +3108,53 @@ impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> ByteReader<R, E> {
     ///
     /// Any unread bits left over when `BitReader` is dropped are lost.
     #[inline]
-    pub fn bitreader(&mut self) -> BitReader<&mut R, E> {
-        BitReader::new(self.reader())
+    pub fn bitreader(&mut self) -> BitReader<&mut R, E, &mut ReadBuffer> {
+        BitReader {
+            reader: &mut self.reader,
+            buffer: self.buffer.borrow_mut(),
+            bitqueue: BitQueue::new(),
+            skip: 0,
+        }
     }
 }
 
-#[async_trait::async_trait]
-impl<R: AsyncRead + Unpin + Send + Sync, E: Endianness> ByteRead for ByteReader<R, E> {
+impl<R, E, B> ByteRead for ByteReader<R, E, B>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    E: Endianness,
+    B: BorrowMut<ReadBuffer> + Send + Sync,
+{
     #[inline]
     async fn read<N: Numeric>(&mut self) -> Result<N, io::Error> {
-        E::read_numeric(&mut self.reader).await
+        let buffer = self.buffer.borrow_mut();
+        if buffer.is_empty() {
+            let value = E::read_numeric(&mut self.reader).await?;
+            buffer.consumed += u64::from(N::BITS_SIZE / 8);
+            Ok(value)
+        } else {
+            // bytes carried over from a BitReader come first
+            let mut bytes = N::buffer();
+            buffer.read_exact(&mut self.reader, bytes.as_mut()).await?;
+            E::read_numeric(bytes.as_ref()).await
+        }
     }
 
     #[inline]
     async fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
-        self.reader.read_exact(buf).await?;
-        Ok(())
+        self.buffer
+            .borrow_mut()
+            .read_exact(&mut self.reader, buf)
+            .await
     }
 
     #[inline]
     async fn skip(&mut self, bytes: u32) -> io::Result<()> {
-        skip_aligned(&mut self.reader, bytes).await
+        let buffer = self.buffer.borrow_mut();
+        let buffered = min(buffer.len(), bytes as usize);
+        buffer.consume(buffered);
+        let rest = bytes - buffered as u32;
+        skip_aligned(&mut self.reader, rest).await?;
+        buffer.consumed += u64::from(rest);
+        Ok(())
     }
 }
@@ -171,13 +171,21 @@
 
 #![warn(missing_docs)]
 
-use std::convert::From;
+use std::borrow::{Borrow, BorrowMut};
+use std::convert::{From, TryFrom};
+use std::future::Future;
 use std::io;
 use std::ops::{AddAssign, Rem};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
 
-use super::{huffman::WriteHuffmanTree, BitQueue, Endianness, Numeric, PhantomData, SignedNumeric};
+use super::error::{io_error, BitstreamErrorKind};
+use super::read::{golomb_remainder_bits, seek_target};
+use super::{
+    huffman::WriteHuffmanCodes, numeric_to_i128, numeric_to_u128, BitQueue, BoxFuture,
+    Endianness, Numeric, PhantomData, SignedNumeric,
+};
 
 /// For writing bit values to an underlying stream in a given endianness.
 ///
@@ -186,9 +194,20 @@ use super::{huffman::WriteHuffmanTree, BitQueue, Endianness, Numeric, PhantomDat
 /// writer's lifetime ends.
 /// **Partial bytes will be lost** if the writer is disposed of
 /// before they can be written.
-pub struct BitWriter<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> {
+///
+/// The third type parameter holds the count of bytes written.
+/// A writer owns its count, while the temporary one returned
+/// by [`ByteWriter::bitwriter`] borrows its parent's.
+pub struct BitWriter<
+    W: AsyncWrite + Unpin + Send + Sync,
+    E: Endianness,
+    K: BorrowMut<u64> + Send + Sync = u64,
+> {
     writer: W,
     bitqueue: BitQueue<E, u8>,
+    /// Whole bytes written, including any held back by placeholders
+    count: K,
+    held: Option<HeldBytes>,
 }
 
 impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWriter<W, E> {
@@ -197,6 +216,8 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWriter<W, E> {
         BitWriter {
             writer,
             bitqueue: BitQueue::new(),
+            count: 0,
+            held: None,
         }
     }
 
@@ -206,24 +227,63 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWriter<W, E> {
         BitWriter {
             writer,
             bitqueue: BitQueue::new(),
+            count: 0,
+            held: None,
         }
     }
+}
+
+impl<W, E, K> BitWriter<W, E, K>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+    E: Endianness,
+    K: BorrowMut<u64> + Send + Sync,
+{
+
+    /// Returns the number of bits written since the writer was created,
+    /// including any partial byte not yet sent to the underlying writer.
+    /// This works with any underlying writer, seekable or not.
+    ///
+    /// Bytes written directly to the underlying writer
+    /// via [`BitWriter::writer`] are not counted.
+    /// Bytes written by a temporary [`BitWriter::bytewriter`] are counted,
+    /// and the count is carried over by [`BitWriter::into_bytewriter`].
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite, ByteWrite};
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(5, 0b10110).await.unwrap();
+    /// assert_eq!(writer.bits_written(), 5);
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.bits_written(), 8);
+    /// writer.bytewriter().unwrap().write(0x1234u16).await.unwrap();
+    /// assert_eq!(writer.bits_written(), 24);
+    /// # });
+    /// ```
+    #[inline]
+    pub fn bits_written(&self) -> u64 {
+        *self.count.borrow() * 8 + u64::from(self.bitqueue.len())
+    }
 
     /// Unwraps internal writer and disposes of BitWriter.
     ///
     /// # Warning
     ///
-    /// Any unwritten partial bits are discarded.
+    /// Any unwritten partial bits are discarded,
+    /// as are any bytes held back by unpatched placeholders.
     #[inline]
     pub fn into_writer(self) -> W {
         self.writer
     }
 
-    /// If stream is byte-aligned, provides mutable reference
-    /// to internal writer.  Otherwise returns `None`
+    /// If stream is byte-aligned and no placeholders are outstanding,
+    /// provides mutable reference to internal writer.
+    /// Otherwise returns `None`
     #[inline]
     pub fn writer(&mut self) -> Option<&mut W> {
-        if self.byte_aligned() {
+        if self.byte_aligned() && self.held.is_none() {
             Some(&mut self.writer)
         } else {
             None
@@ -234,21 +294,35 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWriter<W, E> {
     ///
     /// # Warning
     ///
-    /// Any written partial bits are discarded.
+    /// Any written partial bits are discarded,
+    /// as are any bytes held back by unpatched placeholders.
     #[inline]
-    pub fn into_bytewriter(self) -> ByteWriter<W, E> {
-        ByteWriter::new(self.into_writer())
+    pub fn into_bytewriter(self) -> ByteWriter<W, E, K> {
+        ByteWriter {
+            phantom: PhantomData,
+            writer: self.writer,
+            count: self.count,
+        }
     }
 
-    /// If stream is byte-aligned, provides temporary `ByteWriter`
-    /// in the same endianness.  Otherwise returns `None`
+    /// If stream is byte-aligned and no placeholders are outstanding,
+    /// provides temporary `ByteWriter` in the same endianness.
+    /// Otherwise returns `None`
     ///
     /// # Warning
     ///
     /// Any unwritten bits left over when `ByteWriter` is dropped are lost.
     #[inline]
-    pub fn bytewriter(&mut self) -> Option<ByteWriter<&mut W, E>> {
-        self.writer().map(ByteWriter::new)
+    pub fn bytewriter(&mut self) -> Option<ByteWriter<&mut W, E, &mut u64>> {
+        if self.byte_aligned() && self.held.is_none() {
+            Some(ByteWriter {
+                phantom: PhantomData,
+                writer: &mut self.writer,
+                count: self.count.borrow_mut(),
+            })
+        } else {
+            None
+        }
     }
 
     /// Consumes writer and returns any un-written partial byte
@@ -292,7 +366,8 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWriter<W, E> {
     }
 
     /// Flushes output stream to disk, if necessary.
-    /// Any partial bytes are not flushed.
+    /// Any partial bytes are not flushed,
+    /// nor are any bytes held back by unpatched placeholders.
     ///
     /// # Errors
     ///
@@ -301,11 +376,321 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWriter<W, E> {
     pub async fn flush(&mut self) -> io::Result<()> {
         self.writer.flush().await
     }
+
+    /// Writes a placeholder of the given number of zero bits,
+    /// to be filled in later by [`BitWriter::patch`]
+    /// once its value is known, such as the size of
+    /// a payload which follows it.
+    ///
+    /// Placeholders may start and end anywhere, aligned or not.
+    /// Since the underlying writer need not be seekable,
+    /// everything from the start of the first outstanding placeholder on
+    /// is held in memory, and only passed along to the underlying writer
+    /// once every placeholder has been patched.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(4, 0b1010).await.unwrap();
+    /// let size = writer.reserve(8).await.unwrap();
+    /// writer.write_bytes(b"abc").await.unwrap();
+    /// writer.write(4, 0b0101).await.unwrap();
+    /// // nothing has been written since the placeholder
+    /// assert!(writer.writer().is_none());
+    /// writer.patch(&size, 3u8).await.unwrap();
+    /// assert_eq!(
+    ///     writer.into_writer(),
+    ///     [0b1010_0000, 0b0011_0110, 0b0001_0110, 0b0010_0110, 0b0011_0101]
+    /// );
+    /// # });
+    /// ```
+    pub async fn reserve(&mut self, bits: u32) -> io::Result<Placeholder> {
+        let position = self.bits_written();
+        let count = *self.count.borrow();
+        let held = self.held.get_or_insert_with(|| HeldBytes {
+            id: NEXT_HELD_ID.fetch_add(1, Ordering::Relaxed),
+            bytes: Vec::new(),
+            start: count,
+            unpatched: Vec::new(),
+        });
+        held.unpatched.push((position, bits));
+        let id = held.id;
+
+        let mut remaining = bits;
+        while remaining > 0 {
+            let chunk = remaining.min(32);
+            self.write(chunk, 0u32).await?;
+            remaining -= chunk;
+        }
+        Ok(Placeholder { id, position, bits })
+    }
+
+    /// Fills in a placeholder returned by [`BitWriter::reserve`]
+    /// with the given value, which must fit in the placeholder
+    /// just as it must for [`BitWrite::write`].
+    ///
+    /// Once every outstanding placeholder has been patched,
+    /// any bytes held back by them are written to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if the value doesn't fit,
+    /// in which case the placeholder remains outstanding,
+    /// or if the placeholder has already been patched
+    /// or doesn't come from this writer.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{LittleEndian, BitWriter, BitWrite};
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    /// writer.write(3, 0b111).await.unwrap();
+    /// let first = writer.reserve(10).await.unwrap();
+    /// let second = writer.reserve(3).await.unwrap();
+    /// assert!(writer.patch(&second, 8u8).await.is_err());
+    /// writer.patch(&second, 0b101u8).await.unwrap();
+    /// assert!(writer.patch(&second, 0b101u8).await.is_err());
+    /// writer.patch(&first, 0b11_0000_0001u16).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b0000_1111, 0b1011_1000]);
+    /// # });
+    /// ```
+    pub async fn patch<U>(&mut self, placeholder: &Placeholder, value: U) -> io::Result<()>
+    where
+        U: Numeric,
+    {
+        let key = (placeholder.position, placeholder.bits);
+        let index = self
+            .held
+            .as_ref()
+            .filter(|held| held.id == placeholder.id)
+            .and_then(|held| held.unpatched.iter().position(|p| *p == key))
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "placeholder not outstanding")
+            })?;
+
+        // render the value and a mask of its bits at the same
+        // alignment as the placeholder, then merge them in
+        let offset = (placeholder.position % 8) as u32;
+        let mut patch = BitWriter::<_, E>::new(Vec::new());
+        patch.write(offset, 0u8).await?;
+        patch.write(placeholder.bits, value).await?;
+        patch.byte_align().await?;
+        let mut mask = BitWriter::<_, E>::new(Vec::new());
+        mask.write(offset, 0u8).await?;
+        for _ in 0..placeholder.bits {
+            mask.write_bit(true).await?;
+        }
+        mask.byte_align().await?;
+
+        let held = self.held.as_mut().unwrap();
+        let first = (placeholder.position / 8 - held.start) as usize;
+        for (i, (value, mask)) in patch
+            .into_writer()
+            .into_iter()
+            .zip(mask.into_writer())
+            .enumerate()
+        {
+            match held.bytes.get_mut(first + i) {
+                Some(byte) => *byte = (*byte & !mask) | value,
+                None if mask != 0 => {
+                    // the rest of the placeholder is still queued
+                    let queued = self.bitqueue.len();
+                    let mut byte = self.bitqueue.clone();
+                    byte.push(8 - queued, 0);
+                    let byte = (byte.value() & !mask) | value;
+                    let mut byte = BitQueue::<E, u8>::from_value(byte, 8);
+                    self.bitqueue.set(byte.pop(queued), queued);
+                }
+                None => {}
+            }
+        }
+
+        held.unpatched.swap_remove(index);
+        if held.unpatched.is_empty() {
+            if let Some(held) = self.held.take() {
+                self.writer.write_all(&held.bytes).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W, E, K> BitWriter<W, E, K>
+where
+    E: Endianness,
+    W: AsyncWrite + AsyncSeek + Unpin + Send + Sync,
+    K: BorrowMut<u64> + Send + Sync,
+{
+    /// Returns the current position in the stream in bits,
+    /// including any partial byte not yet written
+    /// and any bytes held back by unpatched placeholders.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Cursor;
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Cursor::new(Vec::new()), BigEndian);
+    /// assert_eq!(writer.position_in_bits().await.unwrap(), 0);
+    /// writer.write(5, 0b10110).await.unwrap();
+    /// assert_eq!(writer.position_in_bits().await.unwrap(), 5);
+    /// writer.write(16, 0xFFFF).await.unwrap();
+    /// assert_eq!(writer.position_in_bits().await.unwrap(), 21);
+    /// # });
+    /// ```
+    pub async fn position_in_bits(&mut self) -> io::Result<u64> {
+        let held = self.held.as_ref().map(|held| held.bytes.len()).unwrap_or(0);
+        let bytes = self.writer.stream_position().await? + held as u64;
+        Ok(bytes * 8 + u64::from(self.bitqueue.len()))
+    }
+}
+
+impl<W, E, K> BitWriter<W, E, K>
+where
+    E: Endianness,
+    W: AsyncRead + AsyncWrite + AsyncSeek + Unpin + Send + Sync,
+    K: BorrowMut<u64> + Send + Sync,
+{
+    /// Moves to the given bit position in the stream,
+    /// returning the new position in bits.
+    ///
+    /// Any partial byte is written out first, over the start of whatever
+    /// byte is already at that position so that the rest of it is kept.
+    /// Likewise, when seeking into the middle of an existing byte,
+    /// the bits before the new position are read back
+    /// so that they are kept once that byte is written.
+    /// This is why the stream must be readable as well.
+    ///
+    /// As with [`BitReader::seek_bits`](crate::BitReader::seek_bits),
+    /// `SeekFrom::End` counts backwards from the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if any placeholders are outstanding
+    /// or if the new position would be before the start of the stream.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::{Cursor, SeekFrom};
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Cursor::new(vec![0xFF, 0xFF, 0xFF]), BigEndian);
+    /// assert_eq!(writer.seek_bits(SeekFrom::Start(4)).await.unwrap(), 4);
+    /// writer.write(8, 0).await.unwrap();
+    /// assert_eq!(writer.seek_bits(SeekFrom::End(3)).await.unwrap(), 21);
+    /// writer.write(2, 0b00).await.unwrap();
+    /// assert_eq!(writer.seek_bits(SeekFrom::Current(-11)).await.unwrap(), 12);
+    /// writer.write_bit(false).await.unwrap();
+    /// writer.seek_bits(SeekFrom::Start(0)).await.unwrap();
+    /// assert_eq!(
+    ///     writer.into_writer().into_inner(),
+    ///     [0b1111_0000, 0b0000_0111, 0b1111_1001]
+    /// );
+    /// # });
+    /// ```
+    pub async fn seek_bits(&mut self, from: io::SeekFrom) -> io::Result<u64> {
+        if self.held.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot seek with placeholders outstanding",
+            ));
+        }
+
+        let current = self.position_in_bits().await?;
+
+        let queued = self.bitqueue.len();
+        if queued > 0 {
+            let mut rest = BitQueue::<E, u8>::from_value(read_back(&mut self.writer).await?, 8);
+            rest.drop(queued);
+            let mut byte = self.bitqueue.clone();
+            byte.push(8 - queued, rest.value());
+            write_byte(&mut self.writer, byte.value()).await?;
+            self.bitqueue.clear();
+        }
+
+        let position = match from {
+            io::SeekFrom::Start(position) => position,
+            io::SeekFrom::End(from_end) => {
+                let end = self.writer.seek(io::SeekFrom::End(0)).await?;
+                seek_target(end * 8, from_end.wrapping_neg())?
+            }
+            io::SeekFrom::Current(offset) => seek_target(current, offset)?,
+        };
+
+        let (bytes, bits) = (position / 8, (position % 8) as u32);
+        self.writer.seek(io::SeekFrom::Start(bytes)).await?;
+        *self.count.borrow_mut() = bytes;
+        if bits > 0 {
+            let mut head = BitQueue::<E, u8>::from_value(read_back(&mut self.writer).await?, 8);
+            self.bitqueue.set(head.pop(bits), bits);
+        }
+        Ok(position)
+    }
+}
+
+/// Returns the byte at the stream's current position without moving it,
+/// or 0 if at the end of the stream
+async fn read_back<W>(writer: &mut W) -> io::Result<u8>
+where
+    W: AsyncRead + AsyncSeek + Unpin + Send + Sync,
+{
+    let mut byte = [0];
+    if writer.read(&mut byte).await? > 0 {
+        writer.seek(io::SeekFrom::Current(-1)).await?;
+    }
+    Ok(byte[0])
+}
+
+/// A span of bits reserved in a `BitWriter`'s output by
+/// [`BitWriter::reserve`], to be filled in by [`BitWriter::patch`].
+///
+/// Everything written after the first outstanding placeholder
+/// is held in memory until each one has been patched,
+/// so dropping a placeholder without patching it holds the rest
+/// of the output back for the writer's lifetime.
+#[must_use]
+#[derive(Debug)]
+pub struct Placeholder {
+    id: u64,
+    position: u64,
+    bits: u32,
+}
+
+impl Placeholder {
+    /// Returns the size of the placeholder in bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Returns the placeholder's position, as bits written before it
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+/// Source of [`HeldBytes`] ids, unique across every writer
+static NEXT_HELD_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes held back while placeholders are outstanding
+struct HeldBytes {
+    /// Identifies the placeholders reserved against these bytes
+    id: u64,
+    bytes: Vec<u8>,
+    /// The position of the first held byte
+    start: u64,
+    /// The positions and sizes of placeholders not yet patched
+    unpatched: Vec<(u64, u32)>,
 }
 
 /// A trait for anything that can write a variable number of
 /// potentially un-aligned values to an output stream
-#[async_trait::async_trait]
 pub trait BitWrite: Send {
     /// Writes a single bit to the stream.
     /// `true` indicates 1, `false` indicates 0
@@ -313,7 +698,7 @@ pub trait BitWrite: Send {
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn write_bit(&mut self, bit: bool) -> io::Result<()>;
+    fn write_bit(&mut self, bit: bool) -> impl Future<Output = io::Result<()>> + Send;
 
     /// Writes an unsigned value to the stream using the given
     /// number of bits.
@@ -325,7 +710,7 @@ pub trait BitWrite: Send {
     /// to hold the given number of bits.
     /// Returns an error if the value is too large
     /// to fit the given number of bits.
-    async fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    fn write<U>(&mut self, bits: u32, value: U) -> impl Future<Output = io::Result<()>> + Send
     where
         U: Numeric;
 
@@ -339,7 +724,7 @@ pub trait BitWrite: Send {
     /// to hold the given number of bits.
     /// Returns an error if the value is too large
     /// to fit the given number of bits.
-    async fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
+    fn write_signed<S>(&mut self, bits: u32, value: S) -> impl Future<Output = io::Result<()>> + Send
     where
         S: SignedNumeric;
 
@@ -364,13 +749,15 @@ pub trait BitWrite: Send {
     /// # });
     /// ```
     #[inline]
-    async fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
-        //buf.iter().try_for_each(|b| self.write(8, *b))
-        for b in buf {
-            self.write(8, *b).await?
-        }
+    fn write_bytes(&mut self, buf: &[u8]) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            //buf.iter().try_for_each(|b| self.write(8, *b))
+            for b in buf {
+                self.write(8, *b).await?
+            }
 
-        Ok(())
+            Ok(())
+        }
     }
 
     /// Writes `value` number of 1 bits to the stream
@@ -402,41 +789,39 @@ pub trait BitWrite: Send {
     /// assert_eq!(writer.into_writer(), [0b11101110, 0b01111111]);
     /// # });
     /// ```
-    async fn write_unary0(&mut self, value: u32) -> io::Result<()> {
-        match value {
-            
-            0 => self.write_bit(false).await,
-            
-            bits @ 1..=31 => {
-                // self.write(value, (1u32 << bits) - 1).and_then(|()| self.write_bit(false)),
-                self.write(value, (1u32 << bits) - 1).await?;
-                self.write_bit(false).await
+    fn write_unary0(&mut self, value: u32) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            let mut value = value;
+            while value > 64 {
+                self.write(64, 0xFFFF_FFFF_FFFF_FFFFu64).await?;
+                value -= 64;
             }
+            match value {
+                0 => self.write_bit(false).await,
 
-            32 => {
-                // self.write(value, ).and_then(|()| self.write_bit(false)),
-                self.write(value, 0xFFFF_FFFFu32).await?;
-                self.write_bit(false).await
-            }
-            
-            bits @ 33..=63 => {
-                // self.write(value, (1u64 << bits) - 1).and_then(|()| self.write_bit(false)),
-                self.write(value, (1u64 << bits) - 1).await?;
-                self.write_bit(false).await
-            }
+                bits @ 1..=31 => {
+                    // self.write(value, (1u32 << bits) - 1).and_then(|()| self.write_bit(false)),
+                    self.write(value, (1u32 << bits) - 1).await?;
+                    self.write_bit(false).await
+                }
 
-            64 => {
-                // self.write().and_then(|()| self.write_bit(false)),
-                self.write(value, 0xFFFF_FFFF_FFFF_FFFFu64).await?;
-                self.write_bit(false).await
-            }
-            
-            mut bits => {
-                while bits > 64 {
-                    self.write(64, 0xFFFF_FFFF_FFFF_FFFFu64).await?;
-                    bits -= 64;
+                32 => {
+                    // self.write(value, ).and_then(|()| self.write_bit(false)),
+                    self.write(value, 0xFFFF_FFFFu32).await?;
+                    self.write_bit(false).await
+                }
+
+                bits @ 33..=63 => {
+                    // self.write(value, (1u64 << bits) - 1).and_then(|()| self.write_bit(false)),
+                    self.write(value, (1u64 << bits) - 1).await?;
+                    self.write_bit(false).await
+                }
+
+                _ => {
+                    // self.write().and_then(|()| self.write_bit(false)),
+                    self.write(value, 0xFFFF_FFFF_FFFF_FFFFu64).await?;
+                    self.write_bit(false).await
                 }
-                self.write_unary0(bits).await
             }
         }
     }
@@ -470,78 +855,700 @@ pub trait BitWrite: Send {
     /// assert_eq!(writer.into_writer(), [0b00010001, 0b10000000]);
     /// # });
     /// ```
-    async fn write_unary1(&mut self, value: u32) -> io::Result<()> {
-        match value {
-            0 => self.write_bit(true).await,
-            
-            1..=32 => {
-                // self.write(value, 0u32).and_then(|()| self.write_bit(true)),
-                self.write(value, 0u32).await?;
-                self.write_bit(true).await
+    fn write_unary1(&mut self, value: u32) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            let mut value = value;
+            while value > 64 {
+                self.write(64, 0u64).await?;
+                value -= 64;
+            }
+            match value {
+                0 => self.write_bit(true).await,
+
+                1..=32 => {
+                    // self.write(value, 0u32).and_then(|()| self.write_bit(true)),
+                    self.write(value, 0u32).await?;
+                    self.write_bit(true).await
+                }
+
+                _ => {
+                    // self.write(value, 0u64).and_then(|()| self.write_bit(true)),
+                    self.write(value, 0u64).await?;
+                    self.write_bit(true).await
+                }
+            }
+        }
+    }
+
+    /// Writes an unsigned Exp-Golomb code of order `k`,
+    /// such as the `ue(v)` fields of H.264 and HEVC (where `k` is 0).
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 31
+    /// or if the value's code would need more than 32 leading zeros.
+    ///
+    /// # Examples
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_exp_golomb(0, 0).await.unwrap();
+    /// writer.write_exp_golomb(0, 1).await.unwrap();
+    /// writer.write_exp_golomb(0, 2).await.unwrap();
+    /// writer.write_exp_golomb(0, 3).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1_010_011_0, 0b0100_0000]);
+    /// # });
+    /// ```
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_exp_golomb(1, 0).await.unwrap();
+    /// writer.write_exp_golomb(1, 1).await.unwrap();
+    /// writer.write_exp_golomb(1, 2).await.unwrap();
+    /// writer.write_exp_golomb(1, 5).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b10_11_0100, 0b0111_0000]);
+    /// # });
+    /// ```
+    fn write_exp_golomb(&mut self, k: u32, value: u64) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            let (zeros, suffix) = exp_golomb_code(k, value.into())?;
+            self.write_unary1(zeros).await?;
+            if zeros + k > 0 {
+                self.write(zeros + k, suffix).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Writes a signed Exp-Golomb code of order `k`,
+    /// such as the `se(v)` fields of H.264 and HEVC (where `k` is 0).
+    ///
+    /// Values 0, 1, -1, 2, -2... map to unsigned codes 0, 1, 2, 3, 4...
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 31
+    /// or if the value's code would need more than 32 leading zeros.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_signed_exp_golomb(0, 0).await.unwrap();
+    /// writer.write_signed_exp_golomb(0, 1).await.unwrap();
+    /// writer.write_signed_exp_golomb(0, -1).await.unwrap();
+    /// writer.write_signed_exp_golomb(0, 2).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1_010_011_0, 0b0100_0000]);
+    /// # });
+    /// ```
+    fn write_signed_exp_golomb(&mut self, k: u32, value: i64) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            let (zeros, suffix) = exp_golomb_code(k, signed_exp_golomb_value(value))?;
+            self.write_unary1(zeros).await?;
+            if zeros + k > 0 {
+                self.write(zeros + k, suffix).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Writes a Rice code with parameter `k`, which is
+    /// a unary quotient (as written by `write_unary0`)
+    /// followed by a `k`-bit remainder.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 63
+    /// or if the value's quotient does not fit in a `u32`.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_rice(2, 0).await.unwrap();
+    /// writer.write_rice(2, 3).await.unwrap();
+    /// writer.write_rice(2, 5).await.unwrap();
+    /// writer.write_rice(2, 10).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b0_00_0_11_10, 0b01_110_10_0]);
+    /// # });
+    /// ```
+    fn write_rice(&mut self, k: u32, value: u64) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            let quotient = rice_quotient(k, value)?;
+            self.write_unary0(quotient).await?;
+            if k > 0 {
+                self.write(k, value & (u64::MAX >> (64 - k))).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Writes a signed Rice code with parameter `k`
+    /// whose unsigned value is zigzag-encoded,
+    /// such that 0, -1, 1, -2, 2... map to 0, 1, 2, 3, 4...
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 63
+    /// or if the value's quotient does not fit in a `u32`.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_signed_rice(1, 0).await.unwrap();
+    /// writer.write_signed_rice(1, -1).await.unwrap();
+    /// writer.write_signed_rice(1, 1).await.unwrap();
+    /// writer.write_signed_rice(1, -2).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b00_01_100_1, 0b01_000000]);
+    /// # });
+    /// ```
+    fn write_signed_rice(&mut self, k: u32, value: i64) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_rice(k, zigzag(value)).await
+        }
+    }
+
+    /// Writes a Golomb code with divisor `m`, which is
+    /// a unary quotient (as written by `write_unary0`)
+    /// followed by a truncated binary remainder.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `m` is 0
+    /// or if the value's quotient does not fit in a `u32`.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_golomb(5, 0).await.unwrap();
+    /// writer.write_golomb(5, 4).await.unwrap();
+    /// writer.write_golomb(5, 6).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b000_0111_1, 0b001_00000]);
+    /// # });
+    /// ```
+    fn write_golomb(&mut self, m: u64, value: u64) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            let (quotient, bits, cutoff) = golomb_code(m, value)?;
+            self.write_unary0(quotient).await?;
+            if bits > 0 {
+                let remainder = u128::from(value % m);
+                if remainder < cutoff {
+                    self.write(bits - 1, remainder as u64).await?;
+                } else {
+                    let long = remainder + cutoff;
+                    self.write(bits - 1, (long >> 1) as u64).await?;
+                    self.write_bit(long & 1 == 1).await?;
+                }
             }
-            
-            33..=64 => {
-                // self.write(value, 0u64).and_then(|()| self.write_bit(true)),
-                self.write(value, 0u64).await?;
-                self.write_bit(true).await
+            Ok(())
+        }
+    }
+
+    /// Writes an unsigned LEB128 value, which need not be byte-aligned.
+    /// See [`ByteWrite::write_leb128`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(4, 0b1111u8).await.unwrap();
+    /// writer.write_leb128(624485u32).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1111_1110, 0b0101_1000, 0b1110_0010, 0b0110_0000]);
+    /// # });
+    /// ```
+    fn write_leb128<N: Numeric>(&mut self, value: N) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&leb128_bytes(numeric_to_u128(value)))
+                .await
+        }
+    }
+
+    /// Writes a signed LEB128 value, which need not be byte-aligned.
+    /// See [`ByteWrite::write_signed_leb128`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    fn write_signed_leb128<S: SignedNumeric>(&mut self, value: S) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&signed_leb128_bytes(numeric_to_i128(value)))
+                .await
+        }
+    }
+
+    /// Writes a big-endian variable-length quantity,
+    /// which need not be byte-aligned.
+    /// See [`ByteWrite::write_vlq`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    fn write_vlq<N: Numeric>(&mut self, value: N) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&vlq_bytes(numeric_to_u128(value))).await
+        }
+    }
+
+    /// Writes a QUIC variable-length integer,
+    /// which need not be byte-aligned.
+    /// See [`ByteWrite::write_quic_varint`].
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if the value is 2 ** 62 or larger.
+    fn write_quic_varint<N: Numeric>(&mut self, value: N) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&quic_varint_bytes(numeric_to_u128(value))?)
+                .await
+        }
+    }
+
+    /// Writes a complete value to the stream
+    /// using its [`ToBitStream`] implementation.
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.build(&(true, 0x12u8, [0x34u8, 0x56])).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1000_1001, 0b0001_1010, 0b0010_1011, 0b0000_0000]);
+    /// # });
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Passes along any error from the value's implementation.
+    fn build<T: ToBitStream + ?Sized>(&mut self, value: &T) -> impl Future<Output = Result<(), T::Error>> + Send {
+        async move {
+            value.to_writer(self).await
+        }
+    }
+
+    /// Writes a complete value to the stream
+    /// using its [`ToBitStreamWith`] implementation
+    /// and the given context.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any error from the value's implementation.
+    fn build_with<T, C>(&mut self, value: &T, context: &C) -> impl Future<Output = Result<(), T::Error>> + Send
+    where
+        T: ToBitStreamWith<C> + ?Sized,
+        C: Sync + ?Sized,
+    {
+        async move {
+            value.to_writer(self, context).await
+        }
+    }
+
+    /// Returns true if the stream is aligned at a whole byte.
+    fn byte_aligned(&self) -> bool;
+
+    /// Pads the stream with 0 bits until it is aligned at a whole byte.
+    /// Does nothing if the stream is already aligned.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underyling stream.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use std::io::Write;
+    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(1, 0).await.unwrap();
+    /// writer.byte_align().await.unwrap();
+    /// writer.write(8, 0xFF).await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0x00, 0xFF]);
+    /// # });
+    /// ```
+    fn byte_align(&mut self) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            while !self.byte_aligned() {
+                self.write_bit(false).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// A dyn-compatible version of [`BitWrite`] whose methods
+/// return boxed futures, for when the concrete writer
+/// isn't known until runtime.
+///
+/// It is implemented for every [`BitWrite`] and
+/// `dyn DynBitWrite` implements [`BitWrite`] in turn,
+/// so a trait object may be passed anywhere a writer is expected.
+/// Values travel through the boxed methods as 128-bit integers.
+///
+/// Writers used directly through [`BitWrite`] don't box
+/// their futures, so prefer it when the writer's type is known.
+///
+/// # Example
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// use tokio_bitstream_io::{BigEndian, BitCounter, BitWriter, BitWrite, DynBitWrite};
+/// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+/// let mut counter = BitCounter::<u32, BigEndian>::new();
+/// for w in [&mut writer as &mut dyn DynBitWrite, &mut counter] {
+///     w.write(4, 0b1011u8).await.unwrap();
+///     w.write_signed(4, -1i8).await.unwrap();
+/// }
+/// assert_eq!(writer.into_writer(), [0b1011_1111]);
+/// assert_eq!(counter.written(), 8);
+/// # });
+/// ```
+pub trait DynBitWrite: Send {
+    /// Writes a single bit to the stream
+    fn dyn_write_bit(&mut self, bit: bool) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Writes an unsigned value of up to 128 bits to the stream
+    fn dyn_write(&mut self, bits: u32, value: u128) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Writes a twos-complement signed value of up to 128 bits to the stream
+    fn dyn_write_signed(&mut self, bits: u32, value: i128) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Writes the entirety of a byte buffer to the stream
+    fn dyn_write_bytes<'a>(&'a mut self, buf: &'a [u8]) -> BoxFuture<'a, io::Result<()>>;
+
+    /// Writes `value` number of 1 bits followed by a 0 bit
+    fn dyn_write_unary0(&mut self, value: u32) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Writes `value` number of 0 bits followed by a 1 bit
+    fn dyn_write_unary1(&mut self, value: u32) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Returns true if the stream is aligned at a whole byte
+    fn dyn_byte_aligned(&self) -> bool;
+
+    /// Pads the stream with 0 bits until it is aligned at a whole byte
+    fn dyn_byte_align(&mut self) -> BoxFuture<'_, io::Result<()>>;
+}
+
+impl<W: BitWrite> DynBitWrite for W {
+    fn dyn_write_bit(&mut self, bit: bool) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(self.write_bit(bit))
+    }
+
+    fn dyn_write(&mut self, bits: u32, value: u128) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(self.write(bits, value))
+    }
+
+    fn dyn_write_signed(&mut self, bits: u32, value: i128) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(self.write_signed(bits, value))
+    }
+
+    fn dyn_write_bytes<'a>(&'a mut self, buf: &'a [u8]) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(self.write_bytes(buf))
+    }
+
+    fn dyn_write_unary0(&mut self, value: u32) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(self.write_unary0(value))
+    }
+
+    fn dyn_write_unary1(&mut self, value: u32) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(self.write_unary1(value))
+    }
+
+    #[inline]
+    fn dyn_byte_aligned(&self) -> bool {
+        self.byte_aligned()
+    }
+
+    fn dyn_byte_align(&mut self) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(self.byte_align())
+    }
+}
+
+impl BitWrite for dyn DynBitWrite + '_ {
+    #[inline]
+    async fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.dyn_write_bit(bit).await
+    }
+
+    async fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    where
+        U: Numeric,
+    {
+        if bits <= U::BITS_SIZE {
+            self.dyn_write(bits, numeric_to_u128(value)).await
+        } else {
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type written",
+            ))
+        }
+    }
+
+    async fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
+    where
+        S: SignedNumeric,
+    {
+        if bits <= S::BITS_SIZE {
+            self.dyn_write_signed(bits, numeric_to_i128(value)).await
+        } else {
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
+                "excessive bits for type written",
+            ))
+        }
+    }
+
+    #[inline]
+    async fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.dyn_write_bytes(buf).await
+    }
+
+    #[inline]
+    async fn write_unary0(&mut self, value: u32) -> io::Result<()> {
+        self.dyn_write_unary0(value).await
+    }
+
+    #[inline]
+    async fn write_unary1(&mut self, value: u32) -> io::Result<()> {
+        self.dyn_write_unary1(value).await
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.dyn_byte_aligned()
+    }
+
+    #[inline]
+    async fn byte_align(&mut self) -> io::Result<()> {
+        self.dyn_byte_align().await
+    }
+}
+
+/// A trait for types which can be written to a bitstream
+/// on their own, without any outside information.
+///
+/// This is implemented for `bool` (a single bit),
+/// for all built-in integer types (at their full width),
+/// and for tuples, arrays and `Vec`s of other implementations,
+/// so nested structures may be written with a single
+/// [`BitWrite::build`] call.
+///
+/// # Example
+/// ```
+/// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite, ToBitStream};
+///
+/// struct BlockHeader {
+///     last_block: bool,
+///     block_type: u8,
+///     block_size: u32,
+/// }
+///
+/// impl ToBitStream for BlockHeader {
+///     type Error = std::io::Error;
+///
+///     async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> std::io::Result<()> {
+///         w.write_bit(self.last_block).await?;
+///         w.write(7, self.block_type).await?;
+///         w.write(24, self.block_size).await
+///     }
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+/// writer.build(&vec![
+///     BlockHeader { last_block: false, block_type: 4, block_size: 34 },
+///     BlockHeader { last_block: true, block_type: 4, block_size: 122 },
+/// ]).await.unwrap();
+/// assert_eq!(writer.into_writer(), [0x04, 0x00, 0x00, 0x22, 0x84, 0x00, 0x00, 0x7A]);
+/// # });
+/// ```
+pub trait ToBitStream: Sync {
+    /// The error type returned when writing fails
+    type Error;
+
+    /// Writes this value to the given writer
+    fn to_writer<W: BitWrite + ?Sized>(
+        &self,
+        w: &mut W,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// A trait for types which need some outside context
+/// in order to be written to a bitstream.
+pub trait ToBitStreamWith<C: Sync + ?Sized>: Sync {
+    /// The error type returned when writing fails
+    type Error;
+
+    /// Writes this value to the given writer using the given context
+    fn to_writer<W: BitWrite + ?Sized>(
+        &self,
+        w: &mut W,
+        context: &C,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+impl ToBitStream for bool {
+    type Error = io::Error;
+
+    #[inline]
+    async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        w.write_bit(*self).await
+    }
+}
+
+macro_rules! define_to_bitstream_unsigned {
+    ($t:ty) => {
+        impl ToBitStream for $t {
+            type Error = io::Error;
+
+            #[inline]
+            async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+                w.write(<$t as Numeric>::BITS_SIZE, *self).await
             }
-            
-            mut bits => {
-                while bits > 64 {
-                    self.write(64, 0u64).await?;
-                    bits -= 64;
-                }
-                self.write_unary1(bits).await
+        }
+    };
+}
+
+macro_rules! define_to_bitstream_signed {
+    ($t:ty) => {
+        impl ToBitStream for $t {
+            type Error = io::Error;
+
+            #[inline]
+            async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+                w.write_signed(<$t as Numeric>::BITS_SIZE, *self).await
             }
         }
-    }
+    };
+}
 
-    /// Returns true if the stream is aligned at a whole byte.
-    fn byte_aligned(&self) -> bool;
+define_to_bitstream_unsigned!(u8);
+define_to_bitstream_unsigned!(u16);
+define_to_bitstream_unsigned!(u32);
+define_to_bitstream_unsigned!(u64);
+define_to_bitstream_unsigned!(u128);
+define_to_bitstream_signed!(i8);
+define_to_bitstream_signed!(i16);
+define_to_bitstream_signed!(i32);
+define_to_bitstream_signed!(i64);
+define_to_bitstream_signed!(i128);
+
+macro_rules! define_to_bitstream_tuple {
+    ($first:ident $first_value:ident $(, $rest:ident $rest_value:ident)*) => {
+        impl<$first: ToBitStream $(, $rest: ToBitStream)*> ToBitStream for ($first, $($rest,)*)
+        where
+            $($rest::Error: Into<$first::Error>,)*
+        {
+            type Error = $first::Error;
+
+            async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> Result<(), Self::Error> {
+                let ($first_value, $($rest_value,)*) = self;
+                $first_value.to_writer(w).await?;
+                $($rest_value.to_writer(w).await.map_err(Into::into)?;)*
+                Ok(())
+            }
+        }
+    };
+}
 
-    /// Pads the stream with 0 bits until it is aligned at a whole byte.
-    /// Does nothing if the stream is already aligned.
-    ///
-    /// # Errors
-    ///
-    /// Passes along any I/O error from the underyling stream.
-    ///
-    /// # Example
-    /// ```
-    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
-    /// use std::io::Write;
-    /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
-    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
-    /// writer.write(1, 0).await.unwrap();
-    /// writer.byte_align().await.unwrap();
-    /// writer.write(8, 0xFF).await.unwrap();
-    /// assert_eq!(writer.into_writer(), [0x00, 0xFF]);
-    /// # });
-    /// ```
-    async fn byte_align(&mut self) -> io::Result<()> {
-        while !self.byte_aligned() {
-            self.write_bit(false).await?;
+define_to_bitstream_tuple!(A a);
+define_to_bitstream_tuple!(A a, B b);
+define_to_bitstream_tuple!(A a, B b, C c);
+define_to_bitstream_tuple!(A a, B b, C c, D d);
+define_to_bitstream_tuple!(A a, B b, C c, D d, E e);
+define_to_bitstream_tuple!(A a, B b, C c, D d, E e, F f);
+define_to_bitstream_tuple!(A a, B b, C c, D d, E e, F f, G g);
+define_to_bitstream_tuple!(A a, B b, C c, D d, E e, F f, G g, H h);
+
+impl<T: ToBitStream> ToBitStream for [T] {
+    type Error = T::Error;
+
+    async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> Result<(), Self::Error> {
+        for value in self.iter() {
+            value.to_writer(w).await?;
         }
         Ok(())
     }
 }
 
+impl<T: ToBitStream, const SIZE: usize> ToBitStream for [T; SIZE] {
+    type Error = T::Error;
+
+    #[inline]
+    async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> Result<(), Self::Error> {
+        self[..].to_writer(w).await
+    }
+}
+
+impl<T: ToBitStream> ToBitStream for Vec<T> {
+    type Error = T::Error;
+
+    #[inline]
+    async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> Result<(), Self::Error> {
+        self[..].to_writer(w).await
+    }
+}
+
 /// A trait for anything that can write Huffman codes
 /// of a given endianness to an output stream
-#[async_trait::async_trait]
 pub trait HuffmanWrite<E: Endianness> {
     /// Writes Huffman code for the given symbol to the stream.
     ///
+    /// The symbol may be given by value or by reference.
+    ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn write_huffman<T>(&mut self, tree: &WriteHuffmanTree<E, T>, symbol: T) -> io::Result<()>
+    /// Returns an error of kind `InvalidInput` if the symbol
+    /// has no code in the tree.
+    fn write_huffman<C, S>(&mut self, tree: &C, symbol: S) -> impl Future<Output = io::Result<()>> + Send
     where
-        T: Ord + Copy + Send + Sync;
+        C: WriteHuffmanCodes<E> + Sync + ?Sized,
+        S: Borrow<C::Symbol> + Send;
 }
 
-#[async_trait::async_trait]
-impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWrite for BitWriter<W, E> {
+/// Looks up the code to write for a symbol
+#[inline]
+pub(crate) fn huffman_code<'t, C, E>(tree: &'t C, symbol: &C::Symbol) -> io::Result<&'t [(u32, u32)]>
+where
+    C: WriteHuffmanCodes<E> + ?Sized,
+    E: Endianness,
+{
+    tree.get_code(symbol)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "symbol not in Huffman tree"))
+}
+
+impl<W, E, K> BitWrite for BitWriter<W, E, K>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+    E: Endianness,
+    K: BorrowMut<u64> + Send + Sync,
+{
     /// # Examples
     /// ```
     /// use tokio_bitstream_io::{BigEndian, BitWriter, BitWrite};
@@ -577,10 +1584,14 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWrite for BitWriter<
     async fn write_bit(&mut self, bit: bool) -> io::Result<()> {
         self.bitqueue.push(1, u8::from(bit));
         if self.bitqueue.is_full() {
-            write_byte(&mut self.writer, self.bitqueue.pop(8)).await
-        } else {
-            Ok(())
+            let byte = self.bitqueue.pop(8);
+            match self.held.as_mut() {
+                Some(held) => held.bytes.push(byte),
+                None => write_byte(&mut self.writer, byte).await?,
+            }
+            *self.count.borrow_mut() += 1;
         }
+        Ok(())
     }
 
     /// # Examples
@@ -626,22 +1637,32 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWrite for BitWriter<
         U: Numeric,
     {
         if bits > U::BITS_SIZE {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
                 "excessive bits for type written",
             ))
         } else if (bits < U::BITS_SIZE) && (value >= (U::ONE << bits)) {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveValue,
                 "excessive value for bits written",
             ))
         } else if bits < self.bitqueue.remaining_len() {
             self.bitqueue.push(bits, value.to_u8());
             Ok(())
         } else {
+            let queued = self.bitqueue.len();
             let mut acc = BitQueue::from_value(value, bits);
-            write_unaligned(&mut self.writer, &mut acc, &mut self.bitqueue).await?;
-            write_aligned(&mut self.writer, &mut acc).await?;
+            match self.held.as_mut() {
+                Some(held) => {
+                    write_unaligned(&mut held.bytes, &mut acc, &mut self.bitqueue).await?;
+                    write_aligned(&mut held.bytes, &mut acc).await?;
+                }
+                None => {
+                    write_unaligned(&mut self.writer, &mut acc, &mut self.bitqueue).await?;
+                    write_aligned(&mut self.writer, &mut acc).await?;
+                }
+            }
+            *self.count.borrow_mut() += u64::from((queued + bits - acc.len()) / 8);
             self.bitqueue.push(acc.len(), acc.value().to_u8());
             Ok(())
         }
@@ -678,7 +1699,12 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWrite for BitWriter<
     #[inline]
     async fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
         if self.byte_aligned() {
-            self.writer.write_all(buf).await
+            match self.held.as_mut() {
+                Some(held) => held.bytes.extend_from_slice(buf),
+                None => self.writer.write_all(buf).await?,
+            }
+            *self.count.borrow_mut() += buf.len() as u64;
+            Ok(())
         } else {
             // buf.iter().try_for_each(|b| self.write(8, *b))
             for b in buf {
@@ -707,8 +1733,12 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> BitWrite for BitWriter<
     }
 }
 
-#[async_trait::async_trait]
-impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> HuffmanWrite<E> for BitWriter<W, E> {
+impl<W, E, K> HuffmanWrite<E> for BitWriter<W, E, K>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+    E: Endianness,
+    K: BorrowMut<u64> + Send + Sync,
+{
     /// # Example
     /// ```
     /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
@@ -727,12 +1757,12 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> HuffmanWrite<E> for Bit
     /// # });
     /// ```
     #[inline]
-    async fn write_huffman<T>(&mut self, tree: &WriteHuffmanTree<E, T>, symbol: T) -> io::Result<()>
+    async fn write_huffman<C, S>(&mut self, tree: &C, symbol: S) -> io::Result<()>
     where
-        T: Ord + Copy + Send + Sync,
+        C: WriteHuffmanCodes<E> + Sync + ?Sized,
+        S: Borrow<C::Symbol> + Send,
     {
-        // tree.get(&symbol).try_for_each(|(bits, value)| self.write(*bits, *value))
-        for (bits, value) in tree.get(&symbol) {
+        for (bits, value) in huffman_code(tree, symbol.borrow())? {
             self.write(*bits, *value).await?
         }
 
@@ -776,7 +1806,6 @@ impl<N: Default + Copy, E: Endianness> BitCounter<N, E> {
     }
 }
 
-#[async_trait::async_trait]
 impl<N, E> BitWrite for BitCounter<N, E>
 where
     E: Endianness,
@@ -794,13 +1823,13 @@ where
         U: Numeric,
     {
         if bits > U::BITS_SIZE {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveBits,
                 "excessive bits for type written",
             ))
         } else if (bits < U::BITS_SIZE) && (value >= (U::ONE << bits)) {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
+            Err(io_error(
+                BitstreamErrorKind::ExcessiveValue,
                 "excessive value for bits written",
             ))
         } else {
@@ -835,23 +1864,65 @@ where
         Ok(())
     }
 
+    #[inline]
+    async fn write_rice(&mut self, k: u32, value: u64) -> io::Result<()> {
+        let quotient = rice_quotient(k, value)?;
+        self.bits += quotient.into();
+        self.bits += (k + 1).into();
+        Ok(())
+    }
+
+    #[inline]
+    async fn write_signed_rice(&mut self, k: u32, value: i64) -> io::Result<()> {
+        self.write_rice(k, zigzag(value)).await
+    }
+
+    #[inline]
+    async fn write_golomb(&mut self, m: u64, value: u64) -> io::Result<()> {
+        let (quotient, bits, cutoff) = golomb_code(m, value)?;
+        let remainder_bits = if bits == 0 {
+            0
+        } else if u128::from(value % m) < cutoff {
+            bits - 1
+        } else {
+            bits
+        };
+        self.bits += quotient.into();
+        self.bits += (remainder_bits + 1).into();
+        Ok(())
+    }
+
+    #[inline]
+    async fn write_exp_golomb(&mut self, k: u32, value: u64) -> io::Result<()> {
+        let (zeros, _) = exp_golomb_code(k, value.into())?;
+        self.bits += (zeros * 2 + 1 + k).into();
+        Ok(())
+    }
+
+    #[inline]
+    async fn write_signed_exp_golomb(&mut self, k: u32, value: i64) -> io::Result<()> {
+        let (zeros, _) = exp_golomb_code(k, signed_exp_golomb_value(value))?;
+        self.bits += (zeros * 2 + 1 + k).into();
+        Ok(())
+    }
+
     #[inline]
     fn byte_aligned(&self) -> bool {
         self.bits % 8.into() == 0.into()
     }
 }
 
-#[async_trait::async_trait]
 impl<N, E> HuffmanWrite<E> for BitCounter<N, E>
 where
     E: Endianness,
     N: AddAssign + From<u32> + Send + Sync,
 {
-    async fn write_huffman<T>(&mut self, tree: &WriteHuffmanTree<E, T>, symbol: T) -> io::Result<()>
+    async fn write_huffman<C, S>(&mut self, tree: &C, symbol: S) -> io::Result<()>
     where
-        T: Ord + Copy + Send + Sync,
+        C: WriteHuffmanCodes<E> + Sync + ?Sized,
+        S: Borrow<C::Symbol> + Send,
     {
-        for &(bits, _) in tree.get(&symbol) {
+        for &(bits, _) in huffman_code(tree, symbol.borrow())? {
             let bits: N = bits.into();
             self.bits += bits;
         }
@@ -930,6 +2001,8 @@ enum WriteRecord {
     Unary0(u32),
     Unary1(u32),
     Bytes(Box<[u8]>),
+    ExpGolomb { k: u32, value: u64 },
+    SignedExpGolomb { k: u32, value: i64 },
 }
 
 impl WriteRecord {
@@ -964,6 +2037,10 @@ impl WriteRecord {
             WriteRecord::Unary0(v) => writer.write_unary0(*v).await,
             WriteRecord::Unary1(v) => writer.write_unary1(*v).await,
             WriteRecord::Bytes(bytes) => writer.write_bytes(bytes).await,
+            WriteRecord::ExpGolomb { k, value } => writer.write_exp_golomb(*k, *value).await,
+            WriteRecord::SignedExpGolomb { k, value } => {
+                writer.write_signed_exp_golomb(*k, *value).await
+            }
         }
     }
 }
@@ -1036,7 +2113,6 @@ impl<N: Default + Copy + Send + Sync, E: Endianness> BitRecorder<N, E> {
     }
 }
 
-#[async_trait::async_trait]
 impl<N, E> BitWrite for BitRecorder<N, E>
 where
     E: Endianness,
@@ -1092,32 +2168,212 @@ where
         self.counter.write_bytes(buf).await
     }
 
+    #[inline]
+    async fn write_exp_golomb(&mut self, k: u32, value: u64) -> io::Result<()> {
+        self.counter.write_exp_golomb(k, value).await?;
+        self.records.push(WriteRecord::ExpGolomb { k, value });
+        Ok(())
+    }
+
+    #[inline]
+    async fn write_signed_exp_golomb(&mut self, k: u32, value: i64) -> io::Result<()> {
+        self.counter.write_signed_exp_golomb(k, value).await?;
+        self.records.push(WriteRecord::SignedExpGolomb { k, value });
+        Ok(())
+    }
+
     #[inline]
     fn byte_aligned(&self) -> bool {
         self.counter.byte_aligned()
     }
 }
 
-#[async_trait::async_trait]
 impl<N, E> HuffmanWrite<E> for BitRecorder<N, E>
 where
     E: Endianness,
     N: Copy + From<u32> + AddAssign + Rem<Output = N> + Eq + Send + Sync,
 {
     #[inline]
-    async fn write_huffman<T>(&mut self, tree: &WriteHuffmanTree<E, T>, symbol: T) -> io::Result<()>
+    async fn write_huffman<C, S>(&mut self, tree: &C, symbol: S) -> io::Result<()>
     where
-        T: Ord + Copy + Send + Sync,
+        C: WriteHuffmanCodes<E> + Sync + ?Sized,
+        S: Borrow<C::Symbol> + Send,
     {
-        // tree.get(&symbol)
-        //     .try_for_each(|(bits, value)| self.write(*bits, *value))
-        for (bits, value) in tree.get(&symbol) {
+        for (bits, value) in huffman_code(tree, symbol.borrow())? {
             self.write(*bits, *value).await?
         }
         Ok(())
     }
 }
 
+/// Given an Exp-Golomb order and unsigned value,
+/// returns the code's number of leading zeros
+/// and the value of the bits following its 1 bit.
+fn exp_golomb_code(k: u32, value: u128) -> io::Result<(u32, u64)> {
+    if k > 31 {
+        return Err(io_error(
+            BitstreamErrorKind::ExcessiveBits,
+            "excessive order for Exp-Golomb code",
+        ));
+    }
+    let shifted = value + (1 << k);
+    let zeros = 127 - shifted.leading_zeros() - k;
+    if zeros > 32 {
+        Err(io_error(
+            BitstreamErrorKind::ExcessiveValue,
+            "excessive value for Exp-Golomb code",
+        ))
+    } else {
+        Ok((zeros, (shifted - (1 << (zeros + k))) as u64))
+    }
+}
+
+/// Given a Rice parameter and unsigned value,
+/// returns the code's unary quotient
+fn rice_quotient(k: u32, value: u64) -> io::Result<u32> {
+    if k > 63 {
+        Err(io_error(
+            BitstreamErrorKind::ExcessiveBits,
+            "excessive parameter for Rice code",
+        ))
+    } else {
+        u32::try_from(value >> k).map_err(|_| {
+            io_error(BitstreamErrorKind::ExcessiveValue, "excessive value for Rice code")
+        })
+    }
+}
+
+/// Given a Golomb divisor and unsigned value,
+/// returns the code's unary quotient, the size of its
+/// longer remainders and the first remainder which is long
+fn golomb_code(m: u64, value: u64) -> io::Result<(u32, u32, u128)> {
+    if m == 0 {
+        return Err(io_error(
+            BitstreamErrorKind::ExcessiveValue,
+            "Golomb code divisor must be nonzero",
+        ));
+    }
+    let quotient = u32::try_from(value / m).map_err(|_| {
+        io_error(BitstreamErrorKind::ExcessiveValue, "excessive value for Golomb code")
+    })?;
+    let bits = golomb_remainder_bits(m);
+    Ok((quotient, bits, (1u128 << bits) - u128::from(m)))
+}
+
+/// Maps 0, -1, 1, -2, 2... to 0, 1, 2, 3, 4...
+/// for signed Rice coding
+#[inline]
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Maps 0, 1, -1, 2, -2... to 0, 1, 2, 3, 4...
+/// for signed Exp-Golomb coding
+#[inline]
+fn signed_exp_golomb_value(value: i64) -> u128 {
+    if value > 0 {
+        u128::from(value as u64) * 2 - 1
+    } else {
+        u128::from(value.unsigned_abs()) * 2
+    }
+}
+
+/// The bytes of a variable-length integer, built on the stack.
+///
+/// 19 bytes is enough for any 128-bit value in 7-bit groups.
+struct VarBytes {
+    bytes: [u8; 19],
+    len: usize,
+}
+
+impl VarBytes {
+    #[inline]
+    fn new() -> Self {
+        VarBytes {
+            bytes: [0; 19],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, byte: u8) {
+        self.bytes[self.len] = byte;
+        self.len += 1;
+    }
+}
+
+impl std::ops::Deref for VarBytes {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// Encodes an unsigned value as little-endian base 128 groups
+fn leb128_bytes(mut value: u128) -> VarBytes {
+    let mut bytes = VarBytes::new();
+    loop {
+        let group = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(group);
+            return bytes;
+        }
+        bytes.push(group | 0x80);
+    }
+}
+
+/// Encodes a signed value as little-endian base 128 groups
+fn signed_leb128_bytes(mut value: i128) -> VarBytes {
+    let mut bytes = VarBytes::new();
+    loop {
+        let group = (value & 0x7F) as u8;
+        value >>= 7;
+        if (value == 0 && (group & 0x40) == 0) || (value == -1 && (group & 0x40) != 0) {
+            bytes.push(group);
+            return bytes;
+        }
+        bytes.push(group | 0x80);
+    }
+}
+
+/// Encodes an unsigned value as big-endian base 128 groups
+fn vlq_bytes(mut value: u128) -> VarBytes {
+    let mut bytes = VarBytes::new();
+    bytes.push((value & 0x7F) as u8);
+    value >>= 7;
+    while value != 0 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.bytes[..bytes.len].reverse();
+    bytes
+}
+
+/// Encodes an unsigned value as a QUIC variable-length integer
+fn quic_varint_bytes(value: u128) -> io::Result<VarBytes> {
+    let (len, prefix) = match value {
+        0..=0x3F => (1, 0b00),
+        0x40..=0x3FFF => (2, 0b01),
+        0x4000..=0x3FFF_FFFF => (4, 0b10),
+        0x4000_0000..=0x3FFF_FFFF_FFFF_FFFF => (8, 0b11),
+        _ => {
+            return Err(io_error(
+                BitstreamErrorKind::ExcessiveValue,
+                "excessive value for QUIC variable-length integer",
+            ))
+        }
+    };
+    let mut bytes = VarBytes::new();
+    for byte in &value.to_be_bytes()[16 - len..] {
+        bytes.push(*byte);
+    }
+    bytes.bytes[0] |= prefix << 6;
+    Ok(bytes)
+}
+
 #[inline]
 async fn write_byte<W>(mut writer: W, byte: u8) -> io::Result<()>
 where
@@ -1172,10 +2428,18 @@ where
 
 /// For writing aligned bytes to a stream of bytes in a given endianness.
 ///
-/// This only writes aligned values and maintains no internal state.
-pub struct ByteWriter<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> {
+/// This only writes aligned values and maintains no internal state
+/// beyond a count of the bytes written.
+/// As with [`BitWriter`], the third type parameter holds that count,
+/// and is borrowed from the parent by [`BitWriter::bytewriter`].
+pub struct ByteWriter<
+    W: AsyncWrite + Unpin + Send + Sync,
+    E: Endianness,
+    K: BorrowMut<u64> + Send + Sync = u64,
+> {
     phantom: PhantomData<E>,
     writer: W,
+    count: K,
 }
 
 impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> ByteWriter<W, E> {
@@ -1184,6 +2448,7 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> ByteWriter<W, E> {
         ByteWriter {
             phantom: PhantomData,
             writer,
+            count: 0,
         }
     }
 
@@ -1193,8 +2458,44 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> ByteWriter<W, E> {
         ByteWriter {
             phantom: PhantomData,
             writer,
+            count: 0,
         }
     }
+}
+
+impl<W, E, K> ByteWriter<W, E, K>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+    E: Endianness,
+    K: BorrowMut<u64> + Send + Sync,
+{
+
+    /// Returns the number of bytes written since the writer was created,
+    /// which works with any underlying writer, seekable or not.
+    ///
+    /// Bytes written directly to the underlying writer
+    /// via [`ByteWriter::writer`] are not counted.
+    /// Whole bytes written by a temporary [`ByteWriter::bitwriter`]
+    /// are counted, and the count is carried over by
+    /// [`ByteWriter::into_bitwriter`].
+    ///
+    /// # Example
+    /// ```
+    /// use tokio_bitstream_io::{BigEndian, BitWrite, ByteWriter, ByteWrite};
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// let mut writer = ByteWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(0x1234u16).await.unwrap();
+    /// writer.bitwriter().write(8, 0x56).await.unwrap();
+    /// assert_eq!(writer.bytes_written(), 3);
+    /// let mut writer = writer.into_bitwriter();
+    /// writer.write(4, 0x7).await.unwrap();
+    /// assert_eq!(writer.bits_written(), 28);
+    /// # });
+    /// ```
+    #[inline]
+    pub fn bytes_written(&self) -> u64 {
+        *self.count.borrow()
+    }
 
     /// Unwraps internal writer and disposes of `ByteWriter`.
     /// Any unwritten partial bits are discarded.
@@ -1211,8 +2512,13 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> ByteWriter<W, E> {
 
     /// Converts `ByteWriter` to `BitWriter` in the same endianness.
     #[inline]
-    pub fn into_bitwriter(self) -> BitWriter<W, E> {
-        BitWriter::new(self.into_writer())
+    pub fn into_bitwriter(self) -> BitWriter<W, E, K> {
+        BitWriter {
+            writer: self.writer,
+            bitqueue: BitQueue::new(),
+            count: self.count,
+            held: None,
+        }
     }
 
     /// Provides temporary `BitWriter` in the same endianness.
@@ -1221,14 +2527,18 @@ impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> ByteWriter<W, E> {
     ///
     /// Any unwritten bits left over when `BitWriter` is dropped are lost.
     #[inline]
-    pub fn bitwriter(&mut self) -> BitWriter<&mut W, E> {
-        BitWriter::new(self.writer())
+    pub fn bitwriter(&mut self) -> BitWriter<&mut W, E, &mut u64> {
+        BitWriter {
+            writer: &mut self.writer,
+            bitqueue: BitQueue::new(),
+            count: self.count.borrow_mut(),
+            held: None,
+        }
     }
 }
 
 /// A trait for anything that can write aligned values to an output stream
-#[async_trait::async_trait]
-pub trait ByteWrite {
+pub trait ByteWrite: Send {
     /// Writes whole numeric value to stream
     ///
     /// # Errors
@@ -1252,25 +2562,131 @@ pub trait ByteWrite {
     /// assert_eq!(writer.into_writer(), [0b11111111, 0b00000000]);
     /// # });
     /// ```
-    async fn write<N: Numeric>(&mut self, value: N) -> io::Result<()>;
+    fn write<N: Numeric>(&mut self, value: N) -> impl Future<Output = io::Result<()>> + Send;
 
     /// Writes the entirety of a byte buffer to the stream.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
-    async fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()>;
+    fn write_bytes(&mut self, buf: &[u8]) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Writes an unsigned LEB128 value, as used by WebAssembly,
+    /// DWARF and Protocol Buffers.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, ByteWriter, ByteWrite};
+    /// let mut writer = ByteWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_leb128(624485u32).await.unwrap();
+    /// writer.write_leb128(127u8).await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0xE5, 0x8E, 0x26, 0x7F]);
+    /// # });
+    /// ```
+    fn write_leb128<N: Numeric>(&mut self, value: N) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&leb128_bytes(numeric_to_u128(value)))
+                .await
+        }
+    }
+
+    /// Writes a signed LEB128 value, as used by WebAssembly and DWARF.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, ByteWriter, ByteWrite};
+    /// let mut writer = ByteWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_signed_leb128(-123456i32).await.unwrap();
+    /// writer.write_signed_leb128(63i8).await.unwrap();
+    /// writer.write_signed_leb128(-64i8).await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0xC0, 0xBB, 0x78, 0x3F, 0x40]);
+    /// # });
+    /// ```
+    fn write_signed_leb128<S: SignedNumeric>(&mut self, value: S) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&signed_leb128_bytes(numeric_to_i128(value)))
+                .await
+        }
+    }
+
+    /// Writes a big-endian variable-length quantity
+    /// of 7-bit groups, as used by MIDI.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, ByteWriter, ByteWrite};
+    /// let mut writer = ByteWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_vlq(0x2000u32).await.unwrap();
+    /// writer.write_vlq(0x0FFF_FFFFu32).await.unwrap();
+    /// assert_eq!(writer.into_writer(), [0xC0, 0x00, 0xFF, 0xFF, 0xFF, 0x7F]);
+    /// # });
+    /// ```
+    fn write_vlq<N: Numeric>(&mut self, value: N) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&vlq_bytes(numeric_to_u128(value))).await
+        }
+    }
+
+    /// Writes a QUIC variable-length integer
+    /// in the fewest of 1, 2, 4 or 8 bytes.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if the value is 2 ** 62 or larger.
+    ///
+    /// # Example
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+    /// use tokio_bitstream_io::{BigEndian, ByteWriter, ByteWrite};
+    /// let mut writer = ByteWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_quic_varint(37u8).await.unwrap();
+    /// writer.write_quic_varint(15293u16).await.unwrap();
+    /// writer.write_quic_varint(494878333u32).await.unwrap();
+    /// assert!(writer.write_quic_varint(u64::MAX).await.is_err());
+    /// assert_eq!(writer.into_writer(), [0x25, 0x7B, 0xBD, 0x9D, 0x7F, 0x3E, 0x7D]);
+    /// # });
+    /// ```
+    fn write_quic_varint<N: Numeric>(&mut self, value: N) -> impl Future<Output = io::Result<()>> + Send {
+        async move {
+            self.write_bytes(&quic_varint_bytes(numeric_to_u128(value))?)
+                .await
+        }
+    }
 }
 
-#[async_trait::async_trait]
-impl<W: AsyncWrite + Unpin + Send + Sync, E: Endianness> ByteWrite for ByteWriter<W, E> {
+impl<W, E, K> ByteWrite for ByteWriter<W, E, K>
+where
+    W: AsyncWrite + Unpin + Send + Sync,
+    E: Endianness,
+    K: BorrowMut<u64> + Send + Sync,
+{
     #[inline]
     async fn write<N: Numeric>(&mut self, value: N) -> io::Result<()> {
-        E::write_numeric(&mut self.writer, value).await
+        E::write_numeric(&mut self.writer, value).await?;
+        *self.count.borrow_mut() += u64::from(N::BITS_SIZE / 8);
+        Ok(())
     }
 
     #[inline]
     async fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
-        self.writer.write_all(buf).await
+        self.writer.write_all(buf).await?;
+        *self.count.borrow_mut() += buf.len() as u64;
+        Ok(())
     }
 }
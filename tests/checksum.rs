@@ -0,0 +1,131 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate tokio_bitstream_io;
+use std::io::Cursor;
+use tokio_bitstream_io::checksum::{
+    Checksum, Checksummed, Crc, CrcAlgorithm, CRC_16, CRC_16_CCITT_FALSE, CRC_16_KERMIT,
+    CRC_16_XMODEM, CRC_32, CRC_32_MPEG2, CRC_8,
+};
+use tokio_bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter, LittleEndian};
+
+#[test]
+fn test_crc_check_values() {
+    // the standard check value is the CRC of the ASCII string "123456789"
+    let algorithms = [
+        (CRC_8, 0xF4),
+        (CRC_16, 0xFEE8),
+        (CRC_16_CCITT_FALSE, 0x29B1),
+        (CRC_16_XMODEM, 0x31C3),
+        (CRC_16_KERMIT, 0x2189),
+        (CRC_32, 0xCBF4_3926),
+        (CRC_32_MPEG2, 0x0376_E6E7),
+        (
+            // CRC-3/GSM
+            CrcAlgorithm {
+                width: 3,
+                poly: 0x3,
+                init: 0x0,
+                reflect: false,
+                xorout: 0x7,
+            },
+            0x4,
+        ),
+        (
+            // CRC-24/OPENPGP
+            CrcAlgorithm {
+                width: 24,
+                poly: 0x86_4CFB,
+                init: 0xB7_04CE,
+                reflect: false,
+                xorout: 0x00_0000,
+            },
+            0x21_CF02,
+        ),
+    ];
+
+    for (algorithm, check) in algorithms.iter() {
+        let mut crc = Crc::new(*algorithm);
+        crc.update(b"123456789");
+        assert_eq!(crc.value(), *check, "{:?}", algorithm);
+
+        // splitting the input makes no difference
+        crc.reset();
+        for byte in b"123456789".iter() {
+            crc.update(&[*byte]);
+        }
+        assert_eq!(crc.value(), *check, "{:?}", algorithm);
+    }
+}
+
+#[tokio::test]
+async fn test_checksummed_reader() {
+    let data = b"\x12\x34123456789\x56";
+    let mut reader = BitReader::endian(
+        Checksummed::new(Cursor::new(&data), Crc::new(CRC_32)),
+        LittleEndian,
+    );
+    assert_eq!(reader.read::<u16>(12).await.unwrap(), 0x412);
+    // the wrapper is only reachable at byte boundaries
+    assert!(reader.reader().is_none());
+    reader.skip(4).await.unwrap();
+
+    let checksummed = reader.reader().unwrap();
+    assert_eq!(checksummed.checksum(), {
+        let mut crc = Crc::new(CRC_32);
+        crc.update(&[0x12, 0x34]);
+        crc.value()
+    });
+    checksummed.reset();
+
+    reader.skip(9 * 8).await.unwrap();
+    let checksummed = reader.reader().unwrap();
+    checksummed.verify(0xCBF4_3926).unwrap();
+    assert_eq!(
+        checksummed.verify(0).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+
+    // buffered readers hold bytes back until they're consumed
+    let mut reader = BitReader::<_, BigEndian>::with_capacity(
+        64,
+        Checksummed::new(Cursor::new(&data), Crc::new(CRC_8)),
+    );
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x12);
+    assert!(reader.reader().is_none());
+}
+
+#[tokio::test]
+async fn test_checksummed_writer() {
+    let mut writer = BitWriter::endian(Checksummed::new(Vec::new(), Crc::new(CRC_16)), BigEndian);
+    for value in 0..100u32 {
+        writer.write(value % 7 + 1, value % 2).await.unwrap();
+    }
+    writer.byte_align().await.unwrap();
+    let crc = writer.writer().unwrap().checksum();
+    writer.write(16, crc).await.unwrap();
+    writer.writer().unwrap().verify(0).unwrap();
+
+    let (data, checksum) = writer.into_writer().into_parts();
+    let mut crc = Crc::new(CRC_16);
+    crc.update(&data[0..data.len() - 2]);
+    assert_eq!(checksum.value(), 0);
+    assert_eq!(&data[data.len() - 2..], &(crc.value() as u16).to_be_bytes());
+
+    // reading the same frame back verifies the same way
+    let mut reader = BitReader::endian(
+        Checksummed::new(Cursor::new(&data), Crc::new(CRC_16)),
+        BigEndian,
+    );
+    for value in 0..100u32 {
+        assert_eq!(reader.read::<u32>(value % 7 + 1).await.unwrap(), value % 2);
+    }
+    reader.byte_align();
+    let crc = reader.reader().unwrap().checksum();
+    assert_eq!(reader.read::<u32>(16).await.unwrap(), crc);
+}
@@ -0,0 +1,245 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "codec")]
+
+extern crate tokio_bitstream_io;
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use tokio_bitstream_io::codec::BitCodec;
+use tokio_bitstream_io::{
+    BigEndian, BitRead, BitWrite, BitstreamError, FromBitStream, LittleEndian, ToBitStream,
+};
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+/// A frame with a 3-bit version, a 13-bit payload length,
+/// the payload, and a final 5-bit checksum of the payload
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Packet {
+    version: u8,
+    payload: Vec<u8>,
+}
+
+impl Packet {
+    fn checksum(&self) -> u8 {
+        self.payload.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) & 0x1F
+    }
+}
+
+#[derive(Debug)]
+enum PacketError {
+    Io(io::Error),
+    Checksum,
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PacketError::Io(err) => err.fmt(f),
+            PacketError::Checksum => f.write_str("checksum mismatch"),
+        }
+    }
+}
+
+impl Error for PacketError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PacketError::Io(err) => Some(err),
+            PacketError::Checksum => None,
+        }
+    }
+}
+
+impl From<io::Error> for PacketError {
+    fn from(err: io::Error) -> Self {
+        PacketError::Io(err)
+    }
+}
+
+impl FromBitStream for Packet {
+    type Error = PacketError;
+
+    async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> Result<Self, PacketError> {
+        let version = r.read(3).await?;
+        let len = r.read::<u16>(13).await?;
+        let packet = Packet {
+            version,
+            payload: r.read_to_vec(usize::from(len)).await?,
+        };
+        if r.read::<u8>(5).await? == packet.checksum() {
+            Ok(packet)
+        } else {
+            Err(PacketError::Checksum)
+        }
+    }
+}
+
+impl ToBitStream for Packet {
+    type Error = PacketError;
+
+    async fn to_writer<W: BitWrite + ?Sized>(&self, w: &mut W) -> Result<(), PacketError> {
+        w.write(3, self.version).await?;
+        w.write(13, self.payload.len() as u16).await?;
+        w.write_bytes(&self.payload).await?;
+        w.write(5, self.checksum()).await?;
+        Ok(())
+    }
+}
+
+fn packets() -> Vec<Packet> {
+    (0..20)
+        .map(|i| Packet {
+            version: (i % 8) as u8,
+            payload: (0..i * 37).map(|b| (b * 3 + i) as u8).collect(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_codec_partial_frames() {
+    let mut codec = BitCodec::<Packet, BigEndian>::new();
+    let mut encoded = BytesMut::new();
+    for packet in packets() {
+        codec.encode(packet, &mut encoded).unwrap();
+    }
+    // 2 header bytes, the payload, and a padded checksum byte
+    let expected: usize = packets().iter().map(|p| p.payload.len() + 3).sum();
+    assert_eq!(encoded.len(), expected);
+
+    // every way of cutting off the data only asks for more
+    let first = packets()[5].payload.len() + 3;
+    let mut skipped = encoded.clone();
+    for packet in packets().iter().take(5) {
+        assert_eq!(codec.decode(&mut skipped).unwrap().as_ref(), Some(packet));
+    }
+    for len in 0..first {
+        let mut partial = BytesMut::from(&skipped[0..len]);
+        assert!(codec.decode(&mut partial).unwrap().is_none());
+        assert_eq!(partial.len(), len);
+    }
+
+    // complete frames are consumed one at a time
+    let mut buf = encoded.clone();
+    for packet in packets() {
+        let remaining = buf.len();
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(packet.clone()));
+        assert_eq!(buf.len(), remaining - packet.payload.len() - 3);
+    }
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+    assert!(codec.decode_eof(&mut buf).unwrap().is_none());
+
+    // other failures are errors, which consume nothing
+    let mut corrupt = encoded.clone();
+    corrupt[2] ^= 0xFF;
+    corrupt.truncate(3);
+    assert!(matches!(
+        codec.decode(&mut corrupt),
+        Err(PacketError::Checksum)
+    ));
+    assert_eq!(corrupt.len(), 3);
+}
+
+/// A frame which turns running out of data into some other error
+#[derive(Debug, PartialEq)]
+struct Strict(u8);
+
+impl FromBitStream for Strict {
+    type Error = PacketError;
+
+    async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> Result<Self, PacketError> {
+        r.read(8)
+            .await
+            .map(Strict)
+            .map_err(|_| PacketError::Checksum)
+    }
+}
+
+/// A frame whose errors are `BitstreamError`s
+#[derive(Debug, PartialEq)]
+struct Pair(u8, u8);
+
+impl FromBitStream for Pair {
+    type Error = BitstreamError;
+
+    async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> Result<Self, BitstreamError> {
+        let first = r.read(8).await?;
+        if first == 0 {
+            return Err(BitstreamError::constraint("zero pair"));
+        }
+        Ok(Pair(first, r.read(8).await?))
+    }
+}
+
+#[test]
+fn test_codec_eof_errors() {
+    // only an unexpected end of stream asks for more data
+    let mut strict = BitCodec::<Strict, BigEndian>::new();
+    let mut buf = BytesMut::from(&[7][..]);
+    assert_eq!(strict.decode(&mut buf).unwrap(), Some(Strict(7)));
+    assert!(matches!(
+        strict.decode(&mut buf),
+        Err(PacketError::Checksum)
+    ));
+
+    let mut codec = BitCodec::<Pair, BigEndian>::new();
+    let mut buf = BytesMut::from(&[1][..]);
+    assert_eq!(codec.decode(&mut buf).unwrap(), None);
+    buf.extend_from_slice(&[2]);
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(Pair(1, 2)));
+    let mut zero = BytesMut::from(&[0][..]);
+    assert!(codec.decode(&mut zero).is_err());
+    assert_eq!(zero.len(), 1);
+}
+
+#[test]
+fn test_codec_endianness() {
+    let packet = Packet {
+        version: 5,
+        payload: vec![0x10, 0x20],
+    };
+
+    let mut be = BytesMut::new();
+    BitCodec::<Packet, BigEndian>::new()
+        .encode(packet.clone(), &mut be)
+        .unwrap();
+    assert_eq!(&be[..], [0b1010_0000, 0b0000_0010, 0x10, 0x20, 0b1000_0000]);
+
+    let mut le = BytesMut::new();
+    BitCodec::<Packet, LittleEndian>::new()
+        .encode(packet.clone(), &mut le)
+        .unwrap();
+    assert_eq!(&le[..], [0b0001_0101, 0b0000_0000, 0x10, 0x20, 0b0001_0000]);
+    assert_eq!(
+        BitCodec::<Packet, LittleEndian>::new()
+            .decode(&mut le)
+            .unwrap(),
+        Some(packet)
+    );
+}
+
+#[tokio::test]
+async fn test_codec_framed() {
+    // a small pipe delivers frames in pieces
+    let (client, server) = tokio::io::duplex(7);
+    let mut client = Framed::new(client, BitCodec::<Packet, BigEndian>::new());
+    let mut server = Framed::new(server, BitCodec::<Packet, BigEndian>::new());
+
+    let sender = tokio::spawn(async move {
+        for packet in packets() {
+            client.send(packet).await.unwrap();
+        }
+    });
+    for packet in packets() {
+        assert_eq!(server.next().await.unwrap().unwrap(), packet);
+    }
+    sender.await.unwrap();
+    assert!(server.next().await.is_none());
+}
@@ -0,0 +1,119 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "futures-io")]
+
+extern crate tokio_bitstream_io;
+use futures::executor::block_on;
+use futures::io::{AsyncRead, Cursor};
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio_bitstream_io::compat::{FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt};
+use tokio_bitstream_io::{
+    BigEndian, BitRead, BitReader, BitWrite, BitWriter, ByteRead, ByteReader, ByteWrite,
+    ByteWriter, LittleEndian,
+};
+
+/// Passes along at most one byte per call,
+/// and only every other time it is polled
+struct Trickle<T> {
+    inner: T,
+    ready: bool,
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Trickle<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.ready = !self.ready;
+        if self.ready {
+            let len = buf.len().min(1);
+            Pin::new(&mut self.inner).poll_read(cx, &mut buf[0..len])
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn test_compat_roundtrip() {
+    // no tokio runtime is needed for futures-io streams
+    block_on(async {
+        let mut writer = BitWriter::endian(Cursor::new(Vec::new()).compat_write(), LittleEndian);
+        for value in 0..256u32 {
+            writer.write(value % 11 + 9, value).await.unwrap();
+            writer
+                .write_signed(7, (value % 64) as i8 - 32)
+                .await
+                .unwrap();
+        }
+        writer.byte_align().await.unwrap();
+        let mut writer = writer.into_bytewriter();
+        writer.write::<u32>(0xDEAD_BEEF).await.unwrap();
+        let data = writer.into_writer().into_inner().into_inner();
+
+        let reader = Trickle {
+            inner: Cursor::new(&data),
+            ready: false,
+        };
+        let mut reader = BitReader::endian(reader.compat(), LittleEndian);
+        for value in 0..256u32 {
+            assert_eq!(reader.read::<u32>(value % 11 + 9).await.unwrap(), value);
+            assert_eq!(
+                reader.read_signed::<i8>(7).await.unwrap(),
+                (value % 64) as i8 - 32
+            );
+        }
+        reader.byte_align();
+        let mut reader = reader.into_bytereader();
+        assert_eq!(reader.read::<u32>().await.unwrap(), 0xDEAD_BEEF);
+        assert_eq!(
+            reader.read::<u8>().await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    });
+}
+
+#[tokio::test]
+async fn test_compat_bytes() {
+    let mut writer = ByteWriter::endian(Cursor::new(Vec::new()).compat_write(), BigEndian);
+    writer.write::<u16>(0x1234).await.unwrap();
+    writer.write::<i32>(-2).await.unwrap();
+    let data = writer.into_writer().into_inner().into_inner();
+    assert_eq!(data, [0x12, 0x34, 0xFF, 0xFF, 0xFF, 0xFE]);
+
+    let mut reader = ByteReader::endian(Cursor::new(&data).compat(), BigEndian);
+    assert_eq!(reader.read::<u16>().await.unwrap(), 0x1234);
+    assert_eq!(reader.read::<i32>().await.unwrap(), -2);
+}
+
+#[tokio::test]
+async fn test_compat_seek() {
+    let data = [0xB1, 0xED, 0x3B, 0xC1];
+    let mut reader = BitReader::endian(Cursor::new(&data).compat(), BigEndian);
+    assert_eq!(reader.seek_bits(SeekFrom::Start(4)).await.unwrap(), 4);
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x1E);
+    assert_eq!(reader.position_in_bits().await.unwrap(), 12);
+    assert_eq!(reader.seek_bits(SeekFrom::Current(-9)).await.unwrap(), 3);
+    assert_eq!(reader.read::<u8>(5).await.unwrap(), 0b1_0001);
+    assert_eq!(reader.seek_bits(SeekFrom::End(4)).await.unwrap(), 28);
+    assert_eq!(reader.read::<u8>(4).await.unwrap(), 0x1);
+    assert_eq!(reader.reader().unwrap().get_ref().position(), 4);
+
+    let mut writer = BitWriter::endian(Cursor::new(data.to_vec()).compat_write(), BigEndian);
+    assert_eq!(writer.seek_bits(SeekFrom::Start(12)).await.unwrap(), 12);
+    writer.write(8, 0x00).await.unwrap();
+    assert_eq!(writer.position_in_bits().await.unwrap(), 20);
+    writer.write(4, 0xF).await.unwrap();
+    let data = writer.into_writer().into_inner().into_inner();
+    assert_eq!(data, [0xB1, 0xE0, 0x0F, 0xC1]);
+}
@@ -0,0 +1,254 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate tokio_bitstream_io;
+use std::io::{Cursor, ErrorKind};
+use tokio_bitstream_io::huffman::{
+    code_lengths, compile_canonical_read_tree, compile_canonical_write_tree,
+    compile_dense_write_tree, compile_incomplete_read_tree, widen_read_tree,
+};
+use tokio_bitstream_io::{
+    BitRead, BitReader, BitSlice, BitVecWriter, BitWrite, BitWriter, Endianness, HuffmanWrite, BE,
+    LE,
+};
+
+/// Writes the same mix of values with both writers,
+/// which should produce identical bytes
+async fn write_matches<E: Endianness>() -> Vec<u8> {
+    let mut sync = BitVecWriter::<E>::new();
+    let mut r#async = BitWriter::<_, E>::new(Vec::new());
+    for i in 0..500u32 {
+        let bits = i % 32 + 1;
+        let value = i.wrapping_mul(2_654_435_761) >> (32 - bits);
+        sync.write(bits, value).unwrap();
+        r#async.write(bits, value).await.unwrap();
+
+        let signed = (i % 14 + 3, (i as i16 % 7) - 3);
+        sync.write_signed(signed.0, signed.1).unwrap();
+        r#async.write_signed(signed.0, signed.1).await.unwrap();
+        sync.write_signed(16, -(i as i16)).unwrap();
+        r#async.write_signed(16, -(i as i16)).await.unwrap();
+
+        sync.write_unary0(i % 70).unwrap();
+        r#async.write_unary0(i % 70).await.unwrap();
+        sync.write_unary1(i % 40).unwrap();
+        r#async.write_unary1(i % 40).await.unwrap();
+        sync.write_bit(i % 3 == 0).unwrap();
+        r#async.write_bit(i % 3 == 0).await.unwrap();
+        sync.write_bytes(&[i as u8, 0xA5]).unwrap();
+        r#async.write_bytes(&[i as u8, 0xA5]).await.unwrap();
+    }
+    assert_eq!(sync.bits_written(), r#async.bits_written());
+    sync.byte_align();
+    r#async.byte_align().await.unwrap();
+    let sync = sync.into_vec();
+    assert_eq!(sync, r#async.into_writer());
+    sync
+}
+
+fn read_matches<E: Endianness>(data: &[u8]) {
+    let mut reader = BitSlice::<E>::new(data);
+    for i in 0..500u32 {
+        let bits = i % 32 + 1;
+        let value = i.wrapping_mul(2_654_435_761) >> (32 - bits);
+        assert_eq!(reader.read::<u32>(bits).unwrap(), value);
+        assert_eq!(
+            reader.read_signed::<i16>(i % 14 + 3).unwrap(),
+            (i as i16 % 7) - 3
+        );
+        assert_eq!(reader.read_signed::<i16>(16).unwrap(), -(i as i16));
+        assert_eq!(reader.read_unary0().unwrap(), i % 70);
+        assert_eq!(reader.read_unary1().unwrap(), i % 40);
+        assert_eq!(reader.read_bit().unwrap(), i % 3 == 0);
+        let mut bytes = [0; 2];
+        reader.read_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, [i as u8, 0xA5]);
+    }
+    reader.byte_align();
+    assert_eq!(reader.remaining(), 0);
+    assert_eq!(reader.bits_read(), data.len() as u64 * 8);
+}
+
+#[tokio::test]
+async fn test_cursor_matches_async() {
+    let data = write_matches::<BE>().await;
+    read_matches::<BE>(&data);
+    let data = write_matches::<LE>().await;
+    read_matches::<LE>(&data);
+}
+
+#[test]
+fn test_cursor_errors() {
+    // nothing is consumed by reads past the end
+    let data = [0b1011_0111, 0b0000_0000];
+    let mut reader = BitSlice::<BE>::new(&data);
+    assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    assert_eq!(
+        reader.read::<u16>(14).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        reader.skip(14).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    let mut buf = [0; 2];
+    assert_eq!(
+        reader.read_bytes(&mut buf).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(reader.bits_read(), 3);
+    assert_eq!(reader.read_unary0().unwrap(), 1);
+    assert_eq!(reader.read_unary0().unwrap(), 3);
+    assert_eq!(
+        reader.read_unary1().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        reader.read_unary1().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(reader.remaining(), 7);
+    assert_eq!(
+        reader.read::<u8>(9).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        reader.read_signed::<i8>(0).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(reader.read::<u8>(7).unwrap(), 0);
+    assert_eq!(
+        reader.read_bit().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let mut writer = BitVecWriter::<LE>::new();
+    assert_eq!(
+        writer.write(9, 0u8).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write(3, 8u8).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write_signed(4, 8i8).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write_signed(4, -9i8).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    writer.write_signed(4, -8i8).unwrap();
+    writer.write_signed(4, 7i8).unwrap();
+    assert_eq!(writer.bits_written(), 8);
+    assert_eq!(writer.into_vec(), [0b0111_1000]);
+}
+
+#[tokio::test]
+async fn test_cursor_huffman() {
+    let frequencies: Vec<(u8, u64)> = (0..40).map(|i| (i, 1 << (40 - i).min(20))).collect();
+    let lengths = code_lengths(frequencies, Some(15)).unwrap();
+    let symbols: Vec<u8> = (0..1000).map(|i| ((i * 7919) % 40) as u8).collect();
+
+    let write_tree = compile_canonical_write_tree::<BE, u8>(lengths.clone()).unwrap();
+    let dense_tree = compile_dense_write_tree::<BE, u8>(write_tree.table().into_codes()).unwrap();
+    let mut writer = BitVecWriter::<BE>::new();
+    let mut r#async = BitWriter::<_, BE>::new(Vec::new());
+    for (i, symbol) in symbols.iter().enumerate() {
+        if i % 2 == 0 {
+            writer.write_huffman(&write_tree, *symbol).unwrap();
+        } else {
+            writer.write_huffman(&dense_tree, *symbol).unwrap();
+        }
+        r#async.write_huffman(&write_tree, *symbol).await.unwrap();
+        writer.write(i as u32 % 5, 0u8).unwrap();
+        r#async.write(i as u32 % 5, 0u8).await.unwrap();
+    }
+    assert_eq!(
+        writer.write_huffman(&write_tree, 40).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    writer.byte_align();
+    r#async.byte_align().await.unwrap();
+    let data = writer.into_vec();
+    assert_eq!(data, r#async.into_writer());
+
+    for width in [8, 12].iter() {
+        let tree = compile_canonical_read_tree::<BE, u8>(lengths.clone()).unwrap();
+        let table = widen_read_tree(tree, *width).unwrap();
+        for wide in [false, true].iter() {
+            let read = |reader: &mut BitSlice<BE>| {
+                if *wide {
+                    reader.read_huffman_wide(&table)
+                } else {
+                    reader.read_huffman(table.tree())
+                }
+            };
+            let mut reader = BitSlice::<BE>::new(&data);
+            for (i, symbol) in symbols.iter().enumerate() {
+                assert_eq!(read(&mut reader).unwrap(), *symbol);
+                reader.skip(i as u32 % 5).unwrap();
+            }
+
+            // a truncated code consumes nothing
+            let mut reader = BitSlice::<BE>::new(&[0xFF]);
+            assert_eq!(
+                read(&mut reader).unwrap_err().kind(),
+                ErrorKind::UnexpectedEof
+            );
+            assert_eq!(reader.remaining(), 8);
+        }
+    }
+
+    // bits with no code are consumed and reported
+    let tree = compile_incomplete_read_tree::<LE, u8>(vec![(0, vec![0]), (1, vec![1, 0])]).unwrap();
+    let mut reader = BitSlice::<LE>::new(&[0b0000_1011]);
+    assert_eq!(
+        reader.read_huffman(&tree).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(reader.bits_read(), 2);
+    assert_eq!(reader.read_huffman(&tree).unwrap(), 0);
+    assert_eq!(reader.read_huffman(&tree).unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_cursor_conversions() {
+    let mut writer = BitVecWriter::<LE>::with_capacity(4);
+    writer.write(12, 0xABCu16).unwrap();
+    writer.byte_align();
+    let mut writer = writer.into_bitwriter();
+    writer.write(8, 0x12).await.unwrap();
+    writer.write(4, 0x3).await.unwrap();
+    writer.byte_align().await.unwrap();
+    let mut writer = BitVecWriter::<LE>::from_vec(writer.into_writer());
+    writer.write(8, 0x45).unwrap();
+    assert_eq!(writer.bits_written(), 8);
+    assert_eq!(writer.as_slice(), [0xBC, 0x0A, 0x12, 0x03, 0x45]);
+    let data = writer.into_vec();
+
+    let mut reader = BitSlice::<LE>::new(&data);
+    assert_eq!(reader.read::<u16>(12).unwrap(), 0xABC);
+    reader.byte_align();
+    assert_eq!(reader.unread_bytes(), [0x12, 0x03, 0x45]);
+    let mut reader = reader.into_bitreader();
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x12);
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x03);
+    // a peek reads ahead, and those bytes come back first
+    assert_eq!(reader.peek::<u8>(8).await.unwrap(), 0x45);
+    let (rest, buffered) = reader.into_parts();
+    assert_eq!(buffered, [0x45]);
+    assert!(rest.is_empty());
+    let mut reader = BitSlice::<LE>::new(&buffered);
+    assert_eq!(reader.read::<u8>(8).unwrap(), 0x45);
+
+    // the same bytes read asynchronously
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    assert_eq!(reader.read::<u16>(12).await.unwrap(), 0xABC);
+}
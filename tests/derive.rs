@@ -0,0 +1,281 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "derive")]
+
+extern crate tokio_bitstream_io;
+use std::io::Cursor;
+use tokio_bitstream_io::{
+    BigEndian, BitCounter, BitDecode, BitEncode, BitReader, BitRecorder, BitWrite, BitWriter,
+    LittleEndian,
+};
+
+const MD5_SIZE: usize = 16;
+
+#[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+struct BlockHeader {
+    last_block: bool,
+    #[bits(7)]
+    block_type: u8,
+    #[bits(24)]
+    block_size: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+struct Streaminfo {
+    #[bits(16)]
+    minimum_block_size: u16,
+    #[bits(16)]
+    maximum_block_size: u16,
+    #[bits(24)]
+    minimum_frame_size: u32,
+    #[bits(24)]
+    maximum_frame_size: u32,
+    #[bits(20)]
+    sample_rate: u32,
+    #[bits(3)]
+    channels: u8,
+    #[bits(5)]
+    bits_per_sample: u8,
+    #[bits(36)]
+    total_samples: u64,
+    #[bytes(MD5_SIZE)]
+    md5: [u8; MD5_SIZE],
+}
+
+#[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+struct Metadata {
+    header: BlockHeader,
+    streaminfo: Streaminfo,
+}
+
+#[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+struct Codes(
+    #[signed(5)] i8,
+    #[unary0] u32,
+    #[unary1] u32,
+    #[align]
+    #[bytes(3)]
+    Vec<u8>,
+    #[signed(40)] i64,
+);
+
+#[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+struct Empty;
+
+#[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+struct Composite {
+    headers: [BlockHeader; 2],
+    pair: (u8, i16),
+    #[bits(4)]
+    nibble: u8,
+    empty: Empty,
+}
+
+fn streaminfo_bytes() -> Vec<u8> {
+    vec![
+        0x00, 0x00, 0x00, 0x22, 0x10, 0x00, 0x10, 0x00, 0x00, 0x06, 0x06, 0x00, 0x21, 0x62, 0x0a,
+        0xc4, 0x42, 0xf0, 0x00, 0x04, 0xa6, 0xcc, 0xfa, 0xf2, 0x69, 0x2f, 0xfd, 0xec, 0x2d, 0x5b,
+        0x30, 0x01, 0x76, 0xb4, 0x62, 0x88, 0x7d, 0x92,
+    ]
+}
+
+fn streaminfo() -> Metadata {
+    Metadata {
+        header: BlockHeader {
+            last_block: false,
+            block_type: 0,
+            block_size: 34,
+        },
+        streaminfo: Streaminfo {
+            minimum_block_size: 4096,
+            maximum_block_size: 4096,
+            minimum_frame_size: 1542,
+            maximum_frame_size: 8546,
+            sample_rate: 44100,
+            channels: 1,
+            bits_per_sample: 15,
+            total_samples: 304844,
+            md5: *b"\xFA\xF2\x69\x2F\xFD\xEC\x2D\x5B\x30\x01\x76\xB4\x62\x88\x7D\x92",
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_derive_read() {
+    let data = streaminfo_bytes();
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(Metadata::read(&mut reader).await.unwrap(), streaminfo());
+
+    // running out of data is reported like any other read
+    let mut reader = BitReader::endian(Cursor::new(&data[0..10]), BigEndian);
+    assert_eq!(
+        Metadata::read(&mut reader).await.unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+}
+
+#[tokio::test]
+async fn test_derive_write() {
+    let mut data = Vec::new();
+    let mut writer = BitWriter::endian(&mut data, BigEndian);
+    streaminfo().write(&mut writer).await.unwrap();
+    assert_eq!(data, streaminfo_bytes());
+
+    let mut counter = BitCounter::<u32, BigEndian>::new();
+    streaminfo().write(&mut counter).await.unwrap();
+    assert_eq!(counter.written(), 38 * 8);
+
+    let mut recorder = BitRecorder::<u32, BigEndian>::new();
+    streaminfo().write(&mut recorder).await.unwrap();
+    let mut data = Vec::new();
+    recorder
+        .playback(&mut BitWriter::endian(&mut data, BigEndian))
+        .await
+        .unwrap();
+    assert_eq!(data, streaminfo_bytes());
+}
+
+#[tokio::test]
+async fn test_derive_roundtrip() {
+    async fn roundtrip<E: tokio_bitstream_io::Endianness + Copy>(endianness: E) {
+        let codes = Codes(-16, 3, 2, vec![1, 2, 3], -(1 << 32));
+
+        let mut data = Vec::new();
+        {
+            let mut writer = BitWriter::endian(&mut data, endianness);
+            codes.write(&mut writer).await.unwrap();
+            Empty.write(&mut writer).await.unwrap();
+            assert!(writer.byte_aligned());
+        }
+
+        // 5 + 4 + 3 bits padded to 2 bytes, 3 bytes, then 40 bits
+        assert_eq!(data.len(), 2 + 3 + 5);
+
+        let mut reader = BitReader::endian(Cursor::new(&data), endianness);
+        assert_eq!(Codes::read(&mut reader).await.unwrap(), codes);
+        assert_eq!(Empty::read(&mut reader).await.unwrap(), Empty);
+    }
+
+    roundtrip(BigEndian).await;
+    roundtrip(LittleEndian).await;
+}
+
+#[tokio::test]
+async fn test_derive_composite() {
+    use tokio_bitstream_io::{BitRead, FromBitStream, ToBitStream};
+
+    let composite = Composite {
+        headers: [
+            BlockHeader {
+                last_block: false,
+                block_type: 4,
+                block_size: 34,
+            },
+            BlockHeader {
+                last_block: true,
+                block_type: 127,
+                block_size: 0xFFFFFF,
+            },
+        ],
+        pair: (0xAB, -2),
+        nibble: 0xC,
+        empty: Empty,
+    };
+
+    let mut data = Vec::new();
+    {
+        let mut writer = BitWriter::endian(&mut data, BigEndian);
+        composite.to_writer(&mut writer).await.unwrap();
+        writer.byte_align().await.unwrap();
+    }
+    assert_eq!(
+        data,
+        [0x04, 0x00, 0x00, 0x22, 0xFF, 0xFF, 0xFF, 0xFF, 0xAB, 0xFF, 0xFE, 0xC0]
+    );
+
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        Composite::from_reader(&mut reader).await.unwrap(),
+        composite
+    );
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        reader.parse::<(Composite, Empty)>().await.unwrap(),
+        (composite, Empty)
+    );
+}
+
+#[derive(Debug, PartialEq, Eq, BitDecode, BitEncode)]
+struct Tagged<T, N> {
+    #[bits(4)]
+    tag: N,
+    value: T,
+}
+
+#[tokio::test]
+async fn test_derive_generic() {
+    use tokio_bitstream_io::{BitRead, FromBitStream, ToBitStream};
+
+    let tagged = Tagged {
+        tag: 0xAu8,
+        value: BlockHeader {
+            last_block: true,
+            block_type: 1,
+            block_size: 2,
+        },
+    };
+    let mut data = Vec::new();
+    {
+        let mut writer = BitWriter::endian(&mut data, BigEndian);
+        tagged.to_writer(&mut writer).await.unwrap();
+        writer.byte_align().await.unwrap();
+    }
+    assert_eq!(data, [0xA8, 0x10, 0x00, 0x00, 0x20]);
+
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        Tagged::<BlockHeader, u8>::from_reader(&mut reader)
+            .await
+            .unwrap(),
+        tagged
+    );
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        reader.parse::<Tagged<BlockHeader, u8>>().await.unwrap(),
+        tagged
+    );
+}
+
+#[tokio::test]
+async fn test_derive_bytes_length() {
+    #[derive(Debug, BitEncode)]
+    struct Payload {
+        #[bytes(3)]
+        data: Vec<u8>,
+    }
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    for data in [vec![1, 2], vec![1, 2, 3, 4]] {
+        assert_eq!(
+            Payload { data }
+                .write(&mut writer)
+                .await
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+    }
+    Payload {
+        data: vec![1, 2, 3],
+    }
+    .write(&mut writer)
+    .await
+    .unwrap();
+    assert_eq!(writer.into_writer(), [1, 2, 3]);
+}
@@ -0,0 +1,114 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate tokio_bitstream_io;
+use std::io::{Cursor, ErrorKind};
+use tokio_bitstream_io::{
+    BigEndian, BitRead, BitReader, BitRecorder, BitWrite, BitWriter, DynBitRead, DynBitWrite,
+    LittleEndian,
+};
+
+async fn write_values(w: &mut dyn DynBitWrite) {
+    w.write_bit(true).await.unwrap();
+    w.write(7, 0b101_0011u8).await.unwrap();
+    w.write_signed(5, -11i8).await.unwrap();
+    w.write_signed(64, i64::MIN).await.unwrap();
+    w.write(128, u128::MAX - 1).await.unwrap();
+    w.write_unary0(70).await.unwrap();
+    w.write_unary1(3).await.unwrap();
+    w.write_exp_golomb(0, 7).await.unwrap();
+    w.byte_align().await.unwrap();
+    w.write_bytes(b"dyn").await.unwrap();
+}
+
+async fn read_values(r: &mut dyn DynBitRead) {
+    assert!(r.read_bit().await.unwrap());
+    assert_eq!(r.peek::<u8>(7).await.unwrap(), 0b101_0011);
+    assert_eq!(r.read::<u8>(7).await.unwrap(), 0b101_0011);
+    assert_eq!(r.read_signed::<i8>(5).await.unwrap(), -11);
+    assert_eq!(r.read_signed::<i64>(64).await.unwrap(), i64::MIN);
+    assert_eq!(r.read::<u128>(128).await.unwrap(), u128::MAX - 1);
+    assert_eq!(r.read_unary0().await.unwrap(), 70);
+    assert_eq!(r.read_unary1().await.unwrap(), 3);
+    assert_eq!(r.read_exp_golomb(0).await.unwrap(), 7);
+    assert!(!r.byte_aligned());
+    r.byte_align();
+    assert_eq!(r.read_to_bytes::<3>().await.unwrap(), *b"dyn");
+}
+
+#[tokio::test]
+async fn test_dyn_roundtrip() {
+    let mut big = BitWriter::endian(Vec::new(), BigEndian);
+    write_values(&mut big).await;
+    let mut little = BitWriter::endian(Vec::new(), LittleEndian);
+    write_values(&mut little).await;
+    let big = big.into_writer();
+    let little = little.into_writer();
+
+    // boxed writers produce the same bytes as direct ones
+    let mut recorder = BitRecorder::<u32, BigEndian>::new();
+    write_values(&mut recorder).await;
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    recorder.playback(&mut writer).await.unwrap();
+    assert_eq!(writer.into_writer(), big);
+
+    let mut readers: Vec<Box<dyn DynBitRead>> = vec![
+        Box::new(BitReader::endian(Cursor::new(big), BigEndian)),
+        Box::new(BitReader::endian(Cursor::new(little), LittleEndian)),
+    ];
+    for reader in readers.iter_mut() {
+        read_values(reader.as_mut()).await;
+    }
+}
+
+#[tokio::test]
+async fn test_dyn_errors() {
+    let data = [0xFF, 0x00];
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    let r: &mut dyn DynBitRead = &mut reader;
+    assert_eq!(
+        r.read::<u8>(9).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        r.peek::<u8>(9).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        r.read_signed::<i8>(9).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    // failed reads consume nothing
+    assert_eq!(r.read::<u16>(12).await.unwrap(), 0xFF0);
+    assert_eq!(
+        r.read::<u8>(8).await.unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    let w: &mut dyn DynBitWrite = &mut writer;
+    assert_eq!(
+        w.write(9, 0u8).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        w.write(3, 8u8).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        w.write_signed(9, 0i8).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        w.write_signed(4, 8i8).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    w.write_signed(4, -8i8).await.unwrap();
+    w.write_signed(4, 7i8).await.unwrap();
+    assert_eq!(writer.into_writer(), [0b0111_1000]);
+}
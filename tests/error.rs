@@ -0,0 +1,196 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate tokio_bitstream_io;
+use std::error::Error;
+use std::io::{self, Cursor};
+use tokio_bitstream_io::huffman::{compile_incomplete_read_tree, compile_read_tree};
+use tokio_bitstream_io::{
+    BigEndian, BitRead, BitReader, BitSlice, BitWrite, BitWriter, BitstreamError,
+    BitstreamErrorKind, ErrorContext, FromBitStream, HuffmanRead, LittleEndian,
+};
+
+fn kind<T>(result: io::Result<T>) -> BitstreamErrorKind {
+    BitstreamError::from(result.err().unwrap()).kind()
+}
+
+#[tokio::test]
+async fn test_error_kinds() {
+    let data = [0b1011_0001, 0xFF, 0xFF];
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        kind(reader.read::<u8>(9).await),
+        BitstreamErrorKind::ExcessiveBits
+    );
+    assert_eq!(
+        kind(reader.read_signed::<i8>(9).await),
+        BitstreamErrorKind::ExcessiveBits
+    );
+    assert_eq!(
+        kind(reader.read::<u32>(32).await),
+        BitstreamErrorKind::UnexpectedEof
+    );
+    reader.read::<u8>(8).await.unwrap();
+    // the LEB128 value never ends before the stream does
+    assert_eq!(
+        kind(reader.read_leb128::<u8>().await),
+        BitstreamErrorKind::UnexpectedEof
+    );
+
+    let mut reader = BitReader::endian(Cursor::new([0xFF, 0x7F]), LittleEndian);
+    let err = reader.read_leb128::<u8>().await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        BitstreamError::from(err).kind(),
+        BitstreamErrorKind::ExcessiveValue
+    );
+
+    let tree = compile_incomplete_read_tree::<BigEndian, u8>(vec![(0, vec![0])]).unwrap();
+    let mut reader = BitReader::endian(Cursor::new([0b1000_0000]), BigEndian);
+    let err = reader.read_huffman(&tree).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "no Huffman code for bits 1");
+    assert_eq!(
+        BitstreamError::from(err).kind(),
+        BitstreamErrorKind::InvalidCode
+    );
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    assert_eq!(
+        kind(writer.write(9, 0u8).await),
+        BitstreamErrorKind::ExcessiveBits
+    );
+    assert_eq!(
+        kind(writer.write(3, 8u8).await),
+        BitstreamErrorKind::ExcessiveValue
+    );
+    assert_eq!(
+        kind(writer.write_exp_golomb(0, u64::MAX).await),
+        BitstreamErrorKind::ExcessiveValue
+    );
+
+    let mut slice = BitSlice::<BigEndian>::new(&data);
+    assert_eq!(kind(slice.read::<u8>(9)), BitstreamErrorKind::ExcessiveBits);
+    assert_eq!(
+        kind(slice.read::<u32>(32)),
+        BitstreamErrorKind::UnexpectedEof
+    );
+
+    let err = compile_read_tree::<BigEndian, u8>(vec![(0, vec![0])]).err();
+    let err = BitstreamError::from(err.unwrap());
+    assert_eq!(err.kind(), BitstreamErrorKind::InvalidCode);
+}
+
+#[test]
+fn test_error_context() {
+    let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::UnexpectedEof, "early eof"));
+    let err = result
+        .at_bit(12)
+        .field("size")
+        .at_bit(8)
+        .field("header")
+        .field(format!("blocks[{}]", 3))
+        .unwrap_err();
+    assert_eq!(err.kind(), BitstreamErrorKind::UnexpectedEof);
+    assert_eq!(err.message(), "early eof");
+    // the innermost offset is kept
+    assert_eq!(err.offset(), Some(12));
+    assert_eq!(err.fields(), ["blocks[3]", "header", "size"]);
+    assert_eq!(
+        err.to_string(),
+        "early eof at bit 12 in blocks[3].header.size"
+    );
+
+    let err = BitstreamError::constraint("reserved bits set");
+    assert_eq!(err.to_string(), "reserved bits set");
+    assert_eq!(err.offset(), None);
+    assert!(err.fields().is_empty());
+    assert_eq!(
+        err.in_field("flags").to_string(),
+        "reserved bits set in flags"
+    );
+}
+
+#[test]
+fn test_error_conversions() {
+    // errors with context travel through io::Error unchanged
+    let err = BitstreamError::constraint("bad sync code")
+        .at(40)
+        .in_field("frame");
+    let io_err = io::Error::from(err);
+    assert_eq!(io_err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(io_err.to_string(), "bad sync code at bit 40 in frame");
+    let err = BitstreamError::from(io_err);
+    assert_eq!(err.kind(), BitstreamErrorKind::Constraint);
+    assert_eq!(err.offset(), Some(40));
+    assert_eq!(err.fields(), ["frame"]);
+
+    // other I/O errors are kept as the source
+    let err = BitstreamError::from(io::Error::new(io::ErrorKind::BrokenPipe, "gone"));
+    assert_eq!(err.kind(), BitstreamErrorKind::Io);
+    assert_eq!(err.to_string(), "gone");
+    assert!(err.source().is_some());
+    let io_err = io::Error::from(err);
+    assert_eq!(io_err.kind(), io::ErrorKind::BrokenPipe);
+    assert!(io_err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<BitstreamError>()
+        .is_none());
+
+    let err = BitstreamError::from(io::Error::from(io::ErrorKind::BrokenPipe)).at(3);
+    let io_err = io::Error::from(err);
+    assert_eq!(io_err.kind(), io::ErrorKind::BrokenPipe);
+    assert_eq!(
+        BitstreamError::from(io_err).to_string(),
+        "broken pipe at bit 3"
+    );
+}
+
+#[derive(Debug)]
+struct Header {
+    version: u8,
+    length: u16,
+}
+
+impl FromBitStream for Header {
+    type Error = BitstreamError;
+
+    async fn from_reader<R: BitRead + ?Sized>(r: &mut R) -> Result<Self, BitstreamError> {
+        let version = r.read(4).await.field("version")?;
+        if version != 1 {
+            return Err(BitstreamError::constraint("unsupported version").in_field("version"));
+        }
+        Ok(Header {
+            version,
+            length: r.read(16).await.field("length")?,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_error_parse() {
+    let mut reader = BitReader::endian(Cursor::new([0x10, 0x00, 0x20]), BigEndian);
+    let header = reader.parse::<Header>().await.unwrap();
+    assert_eq!((header.version, header.length), (1, 2));
+
+    let mut reader = BitReader::endian(Cursor::new([0x10, 0x00]), BigEndian);
+    let err = reader
+        .parse::<Header>()
+        .await
+        .at_bit(reader.bits_read())
+        .field("header")
+        .unwrap_err();
+    assert_eq!(err.kind(), BitstreamErrorKind::UnexpectedEof);
+    assert_eq!(err.to_string(), "early eof at bit 4 in header.length");
+
+    let mut reader = BitReader::endian(Cursor::new([0x30, 0x00, 0x20]), BigEndian);
+    let err = reader.parse::<Header>().await.unwrap_err();
+    assert_eq!(err.kind(), BitstreamErrorKind::Constraint);
+    assert_eq!(err.fields(), ["version"]);
+}
@@ -157,3 +157,716 @@ async fn test_lengthy_huffman_values() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_canonical_huffman() {
+    use std::io::Cursor;
+    use tokio_bitstream_io::huffman::{
+        canonical_codes, compile_canonical_read_tree, compile_canonical_write_tree,
+    };
+    use tokio_bitstream_io::{BitReader, BitWrite, BitWriter, HuffmanRead, HuffmanWrite, BE, LE};
+
+    assert_eq!(
+        canonical_codes(vec![(0, 1), (1, 1), (2, 1)]).unwrap_err(),
+        HuffmanTreeError::OverSubscribed
+    );
+    assert_eq!(
+        canonical_codes(vec![(0, 1), (1, 2), (2, 3), (3, 3), (4, 3)]).unwrap_err(),
+        HuffmanTreeError::OverSubscribed
+    );
+    assert_eq!(
+        canonical_codes(vec![(0, 1), (1, 2)]).unwrap_err(),
+        HuffmanTreeError::Incomplete
+    );
+    assert_eq!(
+        canonical_codes::<u8>(vec![(0, 0), (1, 0)]).unwrap_err(),
+        HuffmanTreeError::Incomplete
+    );
+    assert_eq!(
+        canonical_codes(vec![(0, 2)]).unwrap_err(),
+        HuffmanTreeError::Incomplete
+    );
+
+    // the example from RFC 1951 section 3.2.2
+    assert_eq!(
+        canonical_codes(vec![
+            ('A', 3),
+            ('B', 3),
+            ('C', 3),
+            ('D', 3),
+            ('E', 3),
+            ('F', 2),
+            ('G', 4),
+            ('H', 4)
+        ])
+        .unwrap(),
+        vec![
+            ('F', vec![0, 0]),
+            ('A', vec![0, 1, 0]),
+            ('B', vec![0, 1, 1]),
+            ('C', vec![1, 0, 0]),
+            ('D', vec![1, 0, 1]),
+            ('E', vec![1, 1, 0]),
+            ('G', vec![1, 1, 1, 0]),
+            ('H', vec![1, 1, 1, 1]),
+        ]
+    );
+
+    // a single code of length 1 is allowed,
+    // but the unassigned code is an error
+    let data = [0b0100_0000];
+    let tree = compile_canonical_read_tree::<BE, u8>(vec![(0, 0), (7, 1)]).unwrap();
+    let mut reader = BitReader::<_, BE>::new(Cursor::new(&data));
+    assert_eq!(reader.read_huffman(&tree).await.unwrap(), 7);
+    let err = reader.read_huffman(&tree).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(reader.read_huffman(&tree).await.unwrap(), 7);
+    let tree = compile_canonical_write_tree::<BE, u8>(vec![(7, 1)]).unwrap();
+    assert!(tree.has_symbol(&7));
+    assert_eq!(tree.get(&7).collect::<Vec<_>>(), [&(1, 0)]);
+
+    // lengths as long as the symbol count allows
+    let lengths: Vec<(u32, u32)> = (0..40).map(|i| (i, (i + 1).min(39))).collect();
+    let symbols: Vec<u32> = (0..500).map(|i| (i * 7919) % 40).collect();
+
+    let mut data = Vec::new();
+    {
+        let tree = compile_canonical_write_tree::<LE, u32>(lengths.clone()).unwrap();
+        let mut writer = BitWriter::<_, LE>::new(&mut data);
+        for symbol in symbols.iter() {
+            writer.write_huffman(&tree, *symbol).await.unwrap();
+        }
+        writer.byte_align().await.unwrap();
+    }
+    let tree = compile_canonical_read_tree::<LE, u32>(lengths).unwrap();
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    for symbol in symbols.iter() {
+        assert_eq!(reader.read_huffman(&tree).await.unwrap(), *symbol);
+    }
+}
+
+#[tokio::test]
+async fn test_optimal_huffman() {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+    use tokio_bitstream_io::huffman::{
+        code_lengths, compile_optimal_read_tree, compile_optimal_write_tree, optimal_codes,
+    };
+    use tokio_bitstream_io::{BitReader, BitWrite, BitWriter, HuffmanRead, HuffmanWrite, LE};
+
+    fn cost(frequencies: &[(u32, u64)], lengths: &[(u32, u32)]) -> u64 {
+        frequencies
+            .iter()
+            .zip(lengths)
+            .map(|((_, f), (_, l))| f * u64::from(*l))
+            .sum()
+    }
+
+    // every code is used, so the Kraft sum is exactly 1
+    fn complete(lengths: &[(u32, u32)]) -> bool {
+        let max = lengths.iter().map(|(_, l)| *l).max().unwrap();
+        lengths
+            .iter()
+            .filter(|(_, l)| *l > 0)
+            .map(|(_, l)| 1u128 << (max - l))
+            .sum::<u128>()
+            == 1 << max
+    }
+
+    assert_eq!(code_lengths(Vec::<(u8, u64)>::new(), None).unwrap(), vec![]);
+    assert_eq!(code_lengths(vec![(5u8, 3)], Some(1)).unwrap(), vec![(5, 1)]);
+    assert_eq!(
+        code_lengths(vec![(5u8, 3)], Some(0)).unwrap_err(),
+        HuffmanTreeError::OverSubscribed
+    );
+    assert_eq!(
+        code_lengths((0..5u8).map(|s| (s, 1)), Some(2)).unwrap_err(),
+        HuffmanTreeError::OverSubscribed
+    );
+    assert_eq!(
+        code_lengths(vec![(1u8, 2), (0, 3), (1, 2)], None).unwrap(),
+        vec![(0, 1), (1, 1)]
+    );
+    assert!(optimal_codes(vec![(0u8, 0)], None).is_err());
+
+    // Fibonacci frequencies make for the deepest possible tree
+    let mut fibonacci: Vec<(u32, u64)> = vec![(0, 1), (1, 1)];
+    for i in 2..30 {
+        let f = fibonacci[i - 1].1 + fibonacci[i - 2].1;
+        fibonacci.push((i as u32, f));
+    }
+    let unlimited = code_lengths(fibonacci.clone(), None).unwrap();
+    assert_eq!(unlimited.iter().map(|(_, l)| *l).max(), Some(29));
+    assert!(complete(&unlimited));
+    assert_eq!(code_lengths(fibonacci.clone(), Some(29)).unwrap(), unlimited);
+
+    let mut previous = cost(&fibonacci, &unlimited);
+    for max_length in (5..29).rev() {
+        let limited = code_lengths(fibonacci.clone(), Some(max_length)).unwrap();
+        assert!(limited.iter().all(|(_, l)| (1..=max_length).contains(l)));
+        assert!(complete(&limited));
+        // tighter limits can only cost more
+        let limited_cost = cost(&fibonacci, &limited);
+        assert!(limited_cost >= previous);
+        previous = limited_cost;
+    }
+    // small limited codes match the best found by brute force
+    for weights in [[1u64, 1, 2, 3, 5, 8], [45, 13, 12, 16, 9, 5], [1, 100, 1, 100, 7, 2]].iter() {
+        let frequencies: Vec<(u32, u64)> = (0..6).zip(weights.iter().copied()).collect();
+        for max_length in 3..6u32 {
+            let mut best = u64::MAX;
+            for n in 0..max_length.pow(6) {
+                let lengths: Vec<(u32, u32)> =
+                    (0..6).map(|i| (i, n / max_length.pow(i) % max_length + 1)).collect();
+                let kraft: u32 = lengths.iter().map(|(_, l)| 1 << (max_length - l)).sum();
+                if kraft <= 1 << max_length {
+                    best = best.min(cost(&frequencies, &lengths));
+                }
+            }
+            let limited = code_lengths(frequencies.clone(), Some(max_length)).unwrap();
+            assert_eq!(cost(&frequencies, &limited), best);
+        }
+    }
+
+    // with exactly as many codes as symbols, they all have the same length
+    let mut pairs = fibonacci.clone();
+    pairs.truncate(16);
+    assert!(code_lengths(pairs, Some(4))
+        .unwrap()
+        .iter()
+        .all(|(_, l)| *l == 4));
+
+    // a DEFLATE-sized alphabet round trips with a 15 bit limit
+    let mut frequencies: HashMap<u32, u64> = HashMap::new();
+    let symbols: Vec<u32> = (0..5000u32)
+        .map(|i| (i.wrapping_mul(2_654_435_761) >> 20) % 288)
+        .map(|s| if s % 3 == 0 { s / 37 } else { s })
+        .collect();
+    for symbol in symbols.iter() {
+        *frequencies.entry(*symbol).or_insert(0) += 1;
+    }
+    frequencies.insert(1000, 0);
+    let lengths = code_lengths(frequencies.clone(), Some(15)).unwrap();
+    assert!(lengths.iter().all(|(_, l)| *l <= 15));
+    assert_eq!(lengths.last(), Some(&(1000, 0)));
+
+    let write_tree = compile_optimal_write_tree::<LE, u32, _>(frequencies.clone(), Some(15)).unwrap();
+    assert!(!write_tree.has_symbol(&1000));
+    let mut data = Vec::new();
+    {
+        let mut writer = BitWriter::<_, LE>::new(&mut data);
+        for symbol in symbols.iter() {
+            writer.write_huffman(&write_tree, *symbol).await.unwrap();
+        }
+        writer.byte_align().await.unwrap();
+    }
+    let read_tree = compile_optimal_read_tree::<LE, u32, _>(frequencies, Some(15)).unwrap();
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    for symbol in symbols.iter() {
+        assert_eq!(reader.read_huffman(&read_tree).await.unwrap(), *symbol);
+    }
+}
+
+#[tokio::test]
+async fn test_incomplete_huffman() {
+    use std::io::{Cursor, ErrorKind};
+    use tokio_bitstream_io::huffman::compile_incomplete_read_tree;
+    use tokio_bitstream_io::{BitRead, BitReader, HuffmanRead, BE, LE};
+
+    // complete trees are still required unless asked otherwise
+    let codes = vec![(0u32, vec![0]), (1u32, vec![1, 0, 1])];
+    assert!(if let Err(err) = compile_read_tree::<BE, u32>(codes.clone()) {
+        err == HuffmanTreeError::MissingLeaf
+    } else {
+        false
+    });
+    assert!(compile_incomplete_read_tree::<BE, u32>(Vec::new()).is_ok());
+
+    // unassigned codes are consumed and reported
+    let big = compile_incomplete_read_tree::<BE, u32>(codes.clone()).unwrap();
+    let data = [0b0101_1110, 0b0110_0010];
+    let mut reader = BitReader::<_, BE>::new(Cursor::new(&data));
+    assert_eq!(reader.read_huffman(&big).await.unwrap(), 0);
+    assert_eq!(reader.read_huffman(&big).await.unwrap(), 1);
+    let err = reader.read_huffman(&big).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "no Huffman code for bits 11");
+    assert_eq!(reader.bits_read(), 6);
+    let err = reader.read_huffman(&big).await.unwrap_err();
+    assert_eq!(err.to_string(), "no Huffman code for bits 100");
+    assert_eq!(reader.bits_read(), 9);
+    let err = reader.read_huffman(&big).await.unwrap_err();
+    assert_eq!(err.to_string(), "no Huffman code for bits 11");
+    assert_eq!(reader.read_huffman(&big).await.unwrap(), 0);
+    assert_eq!(reader.read_huffman(&big).await.unwrap(), 0);
+    assert_eq!(reader.read_huffman(&big).await.unwrap(), 0);
+
+    // running out of data partway through a code consumes nothing
+    assert_eq!(
+        reader.read_huffman(&big).await.unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(reader.bits_read(), 14);
+    assert_eq!(reader.read::<u8>(2).await.unwrap(), 0b10);
+
+    // which also holds for codes spanning bytes
+    let long = compile_incomplete_read_tree::<LE, u32>(vec![
+        (0u32, vec![0]),
+        (1u32, vec![1; 12]),
+    ])
+    .unwrap();
+    let data = [0xFF];
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    assert_eq!(
+        reader.read_huffman(&long).await.unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(reader.bits_read(), 0);
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0xFF);
+
+    // as does limiting a read
+    let data = [0b1111_1111, 0b0000_1111];
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    let mut take = reader.take(11);
+    assert_eq!(
+        take.read_huffman(&long).await.unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(take.remaining(), 11);
+    assert_eq!(take.read::<u16>(11).await.unwrap(), 0x7FF);
+
+    let data = [0b0000_0111, 0b0000_0000];
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    let mut take = reader.take(16);
+    let err = take.read_huffman(&long).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "no Huffman code for bits 1110");
+    assert_eq!(take.remaining(), 12);
+    assert_eq!(take.read_huffman(&long).await.unwrap(), 0);
+    assert_eq!(take.remaining(), 11);
+}
+
+#[tokio::test]
+async fn test_wide_huffman() {
+    use std::io::{Cursor, ErrorKind};
+    use tokio_bitstream_io::huffman::{
+        code_lengths, compile_canonical_read_tree, compile_canonical_write_tree,
+        compile_incomplete_read_tree, widen_read_tree, MAX_TABLE_WIDTH,
+    };
+    use tokio_bitstream_io::{
+        BitRead, BitReader, BitWrite, BitWriter, Endianness, HuffmanRead, HuffmanWrite, BE, LE,
+    };
+
+    async fn roundtrip<E: Endianness>() {
+        // skewed frequencies give codes from 1 to 15 bits long
+        let frequencies: Vec<(u32, u64)> = (0..40).map(|i| (i, 1 << (40 - i).min(20))).collect();
+        let lengths = code_lengths(frequencies, Some(15)).unwrap();
+        assert_eq!(lengths.iter().map(|(_, l)| *l).max(), Some(15));
+        let symbols: Vec<u32> = (0..2000).map(|i| (i * 7919) % 40).collect();
+
+        // raw values between the codes leave every possible queue state
+        let mut data = Vec::new();
+        let written = {
+            let tree = compile_canonical_write_tree::<E, u32>(lengths.clone()).unwrap();
+            let mut writer = BitWriter::<_, E>::new(&mut data);
+            for (i, symbol) in symbols.iter().enumerate() {
+                writer.write_huffman(&tree, *symbol).await.unwrap();
+                writer.write(i as u32 % 8 + 2, i as u32 % 3).await.unwrap();
+            }
+            let written = writer.bits_written();
+            writer.byte_align().await.unwrap();
+            written
+        };
+
+        for width in [1, 7, 8, 9, 12, MAX_TABLE_WIDTH].iter() {
+            let tree = compile_canonical_read_tree::<E, u32>(lengths.clone()).unwrap();
+            let table = widen_read_tree(tree, *width).unwrap();
+            for capacity in [0, 4096].iter() {
+                let mut reader = BitReader::<_, E>::with_capacity(*capacity, Cursor::new(&data));
+                for (i, symbol) in symbols.iter().enumerate() {
+                    assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), *symbol);
+                    assert_eq!(
+                        reader.read::<u32>(i as u32 % 8 + 2).await.unwrap(),
+                        i as u32 % 3
+                    );
+                }
+                assert_eq!(reader.bits_read(), written);
+                reader.byte_align();
+                assert_eq!(
+                    reader.read::<u8>(1).await.unwrap_err().kind(),
+                    ErrorKind::UnexpectedEof
+                );
+            }
+        }
+    }
+
+    roundtrip::<BE>().await;
+    roundtrip::<LE>().await;
+
+    for width in [0, MAX_TABLE_WIDTH + 1].iter() {
+        let tree = compile_read_tree::<BE, u8>(vec![(0, vec![0]), (1, vec![1])]).unwrap();
+        assert!(if let Err(err) = widen_read_tree(tree, *width) {
+            err == HuffmanTreeError::InvalidTableWidth
+        } else {
+            false
+        });
+    }
+
+    // unassigned codes and running out of data behave
+    // the same with a table as without one
+    let codes = vec![(0u32, vec![0]), (1u32, vec![1, 0, 1])];
+    let table =
+        widen_read_tree(compile_incomplete_read_tree::<BE, u32>(codes).unwrap(), 9).unwrap();
+    let data = [0b0101_1110, 0b0110_0010];
+    let mut reader = BitReader::<_, BE>::new(Cursor::new(&data));
+    assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 0);
+    assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 1);
+    let err = reader.read_huffman_wide(&table).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "no Huffman code for bits 11");
+    assert_eq!(reader.bits_read(), 6);
+    let err = reader.read_huffman_wide(&table).await.unwrap_err();
+    assert_eq!(err.to_string(), "no Huffman code for bits 100");
+    assert_eq!(reader.bits_read(), 9);
+    let err = reader.read_huffman_wide(&table).await.unwrap_err();
+    assert_eq!(err.to_string(), "no Huffman code for bits 11");
+    for _ in 0..3 {
+        assert_eq!(reader.read_huffman_wide(&table).await.unwrap(), 0);
+    }
+    assert_eq!(
+        reader.read_huffman_wide(&table).await.unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(reader.bits_read(), 14);
+    assert_eq!(reader.read::<u8>(2).await.unwrap(), 0b10);
+
+    // codes found in the table still respect a limited read
+    let table = widen_read_tree(
+        compile_read_tree::<LE, u8>(vec![(0, vec![0]), (1, vec![1, 1]), (2, vec![1, 0])]).unwrap(),
+        12,
+    )
+    .unwrap();
+    let data = [0b1111_1111, 0b1111_1111];
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    let mut take = reader.take(3);
+    assert_eq!(take.read_huffman_wide(&table).await.unwrap(), 1);
+    assert_eq!(
+        take.read_huffman_wide(&table).await.unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(take.remaining(), 1);
+}
+
+#[tokio::test]
+async fn test_huffman_table() {
+    use std::io::{Cursor, ErrorKind};
+    use tokio_bitstream_io::huffman::{
+        code_lengths, compile_canonical_read_tree, compile_canonical_write_tree,
+        compile_incomplete_read_tree, widen_read_tree, HuffmanTable, MAX_STORED_LENGTH,
+    };
+    use tokio_bitstream_io::{BitRead, BitReader, BitWrite, BitWriter, BE, LE};
+
+    // tables recovered from trees match the codes they were built from
+    let codes = vec![
+        ('a', vec![1, 1, 0]),
+        ('b', vec![0]),
+        ('c', vec![1, 0, 1, 0, 1, 1, 0, 0, 1, 1]),
+        ('d', vec![1, 0, 1, 0, 1, 1, 0, 0, 1, 0]),
+        ('e', vec![1, 0, 1, 0, 1, 1, 0, 0, 0]),
+        ('f', vec![1, 0, 1, 0, 1, 1, 0, 1]),
+        ('g', vec![1, 0, 1, 0, 1, 1, 1]),
+        ('h', vec![1, 0, 1, 0, 1, 0]),
+        ('i', vec![1, 0, 1, 0, 0]),
+        ('j', vec![1, 0, 1, 1]),
+        ('k', vec![1, 0, 0]),
+        ('l', vec![1, 1, 1]),
+    ];
+    let table = HuffmanTable::new(codes.clone());
+    assert_eq!(table.codes()[0], ('b', vec![0]));
+    assert_eq!(
+        HuffmanTable::from_read_tree(&compile_read_tree::<BE, char>(codes.clone()).unwrap()),
+        table
+    );
+    assert_eq!(
+        HuffmanTable::from_read_tree(&compile_read_tree::<LE, char>(codes.clone()).unwrap()),
+        table
+    );
+    assert_eq!(
+        HuffmanTable::from_read_tree(
+            widen_read_tree(compile_read_tree::<LE, char>(codes.clone()).unwrap(), 9)
+                .unwrap()
+                .tree()
+        ),
+        table
+    );
+    assert_eq!(
+        compile_write_tree::<BE, char>(codes.clone()).unwrap().table(),
+        table
+    );
+    assert_eq!(
+        compile_write_tree::<LE, char>(codes.clone()).unwrap().table(),
+        table
+    );
+    assert_eq!(
+        HuffmanTable::from_read_tree(&table.read_tree::<BE>().unwrap()),
+        table
+    );
+    assert_eq!(table.write_tree::<LE>().unwrap().table(), table);
+
+    // including trees of a single code or with unassigned codes
+    let single = vec![(7u8, vec![])];
+    assert_eq!(
+        HuffmanTable::from_read_tree(&compile_read_tree::<BE, u8>(single.clone()).unwrap()),
+        HuffmanTable::new(single)
+    );
+    let incomplete = vec![(1u8, vec![0]), (2u8, vec![1, 0, 1])];
+    assert_eq!(
+        HuffmanTable::from_read_tree(
+            &compile_incomplete_read_tree::<BE, u8>(incomplete.clone()).unwrap()
+        ),
+        HuffmanTable::new(incomplete)
+    );
+    assert_eq!(
+        HuffmanTable::from_read_tree(
+            &compile_canonical_read_tree::<LE, u8>(vec![(3, 1)]).unwrap()
+        )
+        .into_codes(),
+        vec![(3, vec![0])]
+    );
+
+    // canonical tables survive being stored
+    async fn roundtrip<T: Ord + Clone + Send + Sync + std::fmt::Debug>(
+        table: &HuffmanTable<T>,
+        alphabet: &[T],
+    ) -> usize {
+        let mut data = Vec::new();
+        let mut writer = BitWriter::<_, LE>::new(&mut data);
+        table.write_lengths(&mut writer, alphabet).await.unwrap();
+        writer.write(5, 0b10101).await.unwrap();
+        writer.byte_align().await.unwrap();
+        let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+        assert_eq!(
+            &HuffmanTable::read_lengths(&mut reader, alphabet)
+                .await
+                .unwrap(),
+            table
+        );
+        assert_eq!(reader.read::<u8>(5).await.unwrap(), 0b10101);
+        data.len()
+    }
+
+    let alphabet: Vec<u16> = (0..300).collect();
+    let frequencies: Vec<(u16, u64)> = alphabet
+        .iter()
+        .map(|s| (*s, if s % 37 < 3 { 0 } else { u64::from(s % 11) * 100 + 1 }))
+        .collect();
+    let lengths = code_lengths(frequencies, Some(MAX_STORED_LENGTH)).unwrap();
+    let table = HuffmanTable::from_lengths(lengths.clone()).unwrap();
+    roundtrip(&table, &alphabet).await;
+    assert_eq!(
+        compile_canonical_write_tree::<BE, u16>(lengths).unwrap().table(),
+        table
+    );
+
+    // long runs of repeated and unused lengths are stored compactly
+    let sparse = HuffmanTable::from_lengths(vec![(0u16, 1), (299, 1)]).unwrap();
+    assert!(roundtrip(&sparse, &alphabet).await < 16);
+    let flat = HuffmanTable::from_lengths(alphabet.iter().take(256).map(|s| (*s, 8)).collect())
+        .unwrap();
+    assert!(roundtrip(&flat, &alphabet).await < 24);
+    let lone = HuffmanTable::from_lengths(vec![(5u16, 1)]).unwrap();
+    roundtrip(&lone, &alphabet).await;
+
+    // tables which cannot be stored
+    let mut data = Vec::new();
+    let mut writer = BitWriter::<_, LE>::new(&mut data);
+    assert_eq!(
+        sparse.write_lengths(&mut writer, &alphabet[0..10]).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    let reversed = HuffmanTable::new(vec![(0u16, vec![1]), (1u16, vec![0])]);
+    assert_eq!(
+        reversed.write_lengths(&mut writer, &alphabet).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    let long = HuffmanTable::from_lengths(
+        (0..17u16).map(|s| (s, (s + 1).min(16) as u32)).collect(),
+    )
+    .unwrap();
+    assert_eq!(
+        long.write_lengths(&mut writer, &alphabet).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        HuffmanTable::new(Vec::new())
+            .write_lengths(&mut writer, &alphabet)
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidInput
+    );
+    assert!(writer.byte_aligned());
+
+    // and data which does not hold a table
+    let mut data = Vec::new();
+    let mut writer = BitWriter::<_, LE>::new(&mut data);
+    table.write_lengths(&mut writer, &alphabet).await.unwrap();
+    writer.byte_align().await.unwrap();
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    assert_eq!(
+        HuffmanTable::read_lengths(&mut reader, &alphabet[0..100])
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data[0..data.len() / 2]));
+    assert_eq!(
+        HuffmanTable::read_lengths(&mut reader, &alphabet)
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::UnexpectedEof
+    );
+    // two lengths whose code length code has no codes
+    let data = [0x02, 0x00, 0x00, 0x00, 0x00];
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    assert_eq!(
+        HuffmanTable::read_lengths(&mut reader, &alphabet)
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_huffman_table_serde() {
+    use tokio_bitstream_io::huffman::HuffmanTable;
+    use tokio_bitstream_io::LE;
+
+    let table = HuffmanTable::new(vec![('a', vec![0]), ('b', vec![1, 0]), ('c', vec![1, 1])]);
+    let json = serde_json::to_string(&table).unwrap();
+    assert_eq!(json, r#"{"codes":[["a",[0]],["b",[1,0]],["c",[1,1]]]}"#);
+    let decoded: HuffmanTable<char> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, table);
+    assert!(decoded.read_tree::<LE>().is_ok());
+}
+
+#[tokio::test]
+async fn test_write_tree_variants() {
+    use std::io::{Cursor, ErrorKind};
+    use tokio_bitstream_io::huffman::{
+        canonical_codes, compile_dense_write_tree, compile_hash_write_tree, HuffmanTable,
+        WriteHuffmanCodes,
+    };
+    use tokio_bitstream_io::{
+        BitCounter, BitReader, BitRecorder, BitWrite, BitWriter, HuffmanRead, HuffmanWrite, LE,
+    };
+
+    // DEFLATE's fixed literal/length code
+    let lengths: Vec<(u16, u32)> = (0..=285)
+        .map(|symbol| {
+            let len = match symbol {
+                0..=143 => 8,
+                144..=255 => 9,
+                256..=279 => 7,
+                _ => 8,
+            };
+            (symbol, len)
+        })
+        .chain((286..=287).map(|symbol| (symbol, 8)))
+        .collect();
+    let codes = canonical_codes(lengths).unwrap();
+    let symbols: Vec<u16> = (0..1000).map(|i| (i * 7919 % 286) as u16).collect();
+
+    let sorted = compile_write_tree::<LE, u16>(codes.clone()).unwrap();
+    let dense = compile_dense_write_tree::<LE, u16>(codes.clone()).unwrap();
+    assert!(dense.has_symbol(285));
+    assert!(!dense.has_symbol(288));
+    for symbol in 0..=287u16 {
+        assert_eq!(
+            dense.get_code(&symbol).unwrap(),
+            sorted.get_code(&symbol).unwrap()
+        );
+    }
+
+    let mut expected = Vec::new();
+    {
+        let mut writer = BitWriter::<_, LE>::new(&mut expected);
+        for symbol in symbols.iter() {
+            writer.write_huffman(&sorted, *symbol).await.unwrap();
+        }
+        writer.byte_align().await.unwrap();
+    }
+    let mut data = Vec::new();
+    {
+        let mut writer = BitWriter::<_, LE>::new(&mut data);
+        for symbol in symbols.iter() {
+            writer.write_huffman(&dense, symbol).await.unwrap();
+        }
+        writer.byte_align().await.unwrap();
+    }
+    assert_eq!(data, expected);
+
+    let mut counter = BitCounter::<u32, LE>::new();
+    let mut recorder = BitRecorder::<u32, LE>::new();
+    for symbol in symbols.iter() {
+        counter.write_huffman(&dense, *symbol).await.unwrap();
+        recorder.write_huffman(&dense, *symbol).await.unwrap();
+    }
+    assert_eq!(counter.written(), recorder.written());
+    assert_eq!((counter.written() as usize).div_ceil(8), expected.len());
+
+    let tree = HuffmanTable::new(codes).read_tree::<LE>().unwrap();
+    let mut reader = BitReader::<_, LE>::new(Cursor::new(&data));
+    for symbol in symbols.iter() {
+        assert_eq!(reader.read_huffman(&tree).await.unwrap(), *symbol);
+    }
+
+    // string tokens which are only hashable
+    let words = ["to", "be", "or", "not"];
+    let tree = compile_hash_write_tree::<LE, String>(
+        words
+            .iter()
+            .map(|word| word.to_string())
+            .zip(canonical_codes(vec![(0, 1), (1, 2), (2, 3), (3, 3)]).unwrap())
+            .map(|(word, (_, code))| (word, code))
+            .collect(),
+    )
+    .unwrap();
+    assert!(tree.has_symbol(&"or".to_string()));
+    assert!(!tree.has_symbol(&"and".to_string()));
+    let mut data = Vec::new();
+    {
+        let mut writer = BitWriter::<_, LE>::new(&mut data);
+        for word in "to be or not to be".split(' ').map(String::from) {
+            writer.write_huffman(&tree, &word).await.unwrap();
+        }
+        writer.write_huffman(&tree, "be".to_string()).await.unwrap();
+        writer.byte_align().await.unwrap();
+    }
+    // 0 10 110 111 0 10 10, first bits least significant
+    assert_eq!(data, [0b1101_1010, 0b0001_0101]);
+
+    // symbols missing from a tree are an error for every variant
+    let mut writer = BitWriter::<_, LE>::new(Vec::new());
+    assert_eq!(
+        writer
+            .write_huffman(&tree, "and".to_string())
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write_huffman(&dense, 300u16).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write_huffman(&sorted, 300u16).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert!(writer.byte_aligned());
+}
@@ -0,0 +1,163 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate tokio_bitstream_io;
+use std::io::{self, Cursor};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio_bitstream_io::rbsp::{RbspReader, RbspWriter};
+use tokio_bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter};
+
+/// Passes along at most one byte per call
+struct Trickle<T>(T);
+
+impl<T: AsyncRead + Unpin> AsyncRead for Trickle<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let mut byte = [0];
+        let mut one = ReadBuf::new(&mut byte);
+        match Pin::new(&mut self.0).poll_read(cx, &mut one) {
+            Poll::Ready(Ok(())) => {
+                buf.put_slice(one.filled());
+                Poll::Ready(Ok(()))
+            }
+            other => other,
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Trickle<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let len = buf.len().min(1);
+        Pin::new(&mut self.0).poll_write(cx, &buf[0..len])
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+const RBSP: [u8; 16] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x02, 0x00, 0x00, 0x03, 0x00, 0x00, 0x04, 0x00,
+];
+
+const ESCAPED: [u8; 20] = [
+    0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x00, 0x01, 0x00, 0x00, 0x03, 0x02, 0x00, 0x00, 0x03, 0x03,
+    0x00, 0x00, 0x04, 0x00,
+];
+
+#[tokio::test]
+async fn test_rbsp_reader() {
+    let mut rbsp = Vec::new();
+    let mut reader = RbspReader::new(Cursor::new(&ESCAPED));
+    reader.read_to_end(&mut rbsp).await.unwrap();
+    assert_eq!(rbsp, RBSP);
+    assert_eq!(reader.removed(), 4);
+
+    // removal works no matter how the escaped stream is split up
+    let mut rbsp = Vec::new();
+    let mut reader = RbspReader::new(Trickle(Cursor::new(&ESCAPED)));
+    reader.read_to_end(&mut rbsp).await.unwrap();
+    assert_eq!(rbsp, RBSP);
+    assert_eq!(reader.removed(), 4);
+
+    // a stream of nothing but removed bytes is not mistaken for EOF
+    let escaped = [0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03];
+    let mut reader = RbspReader::new(Trickle(Cursor::new(&escaped)));
+    let mut rbsp = [0; 5];
+    reader.read_exact(&mut rbsp).await.unwrap();
+    assert_eq!(rbsp, [0; 5]);
+    assert_eq!(reader.read_u8().await.unwrap(), 0);
+    assert_eq!(reader.read(&mut rbsp).await.unwrap(), 0);
+    assert_eq!(reader.removed(), 3);
+
+    // a run of zeros longer than the counter's range is still tracked
+    let mut escaped = vec![0; 300];
+    escaped.extend_from_slice(&[0x03, 0x01]);
+    let mut rbsp = Vec::new();
+    let mut reader = RbspReader::new(Cursor::new(&escaped));
+    reader.read_to_end(&mut rbsp).await.unwrap();
+    assert_eq!(rbsp.len(), 301);
+    assert_eq!(rbsp[300], 0x01);
+    assert_eq!(reader.removed(), 1);
+}
+
+#[tokio::test]
+async fn test_rbsp_bit_reader_position() {
+    let mut reader = BitReader::endian(RbspReader::new(Cursor::new(&ESCAPED)), BigEndian);
+    assert_eq!(reader.read::<u64>(48).await.unwrap(), 0x000000000001);
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x00);
+
+    // 7 unescaped bytes read so far, plus the removed bytes,
+    // is the number of escaped bytes consumed
+    let rbsp = reader.reader().unwrap();
+    assert_eq!(rbsp.removed(), 2);
+    assert_eq!(rbsp.get_ref().position(), 7 + 2);
+}
+
+#[tokio::test]
+async fn test_rbsp_writer() {
+    let mut writer = RbspWriter::new(Vec::new());
+    writer.write_all(&RBSP).await.unwrap();
+    writer.shutdown().await.unwrap();
+    assert_eq!(writer.inserted(), 4);
+    assert_eq!(writer.into_inner(), ESCAPED);
+
+    // trailing zero pairs get a final emulation-prevention byte
+    let mut writer = RbspWriter::new(Trickle(Vec::new()));
+    for byte in RBSP.iter().chain([0x00].iter()) {
+        writer.write_u8(*byte).await.unwrap();
+    }
+    writer.shutdown().await.unwrap();
+    assert_eq!(writer.inserted(), 5);
+    let mut expected = ESCAPED.to_vec();
+    expected.extend_from_slice(&[0x00, 0x03]);
+    assert_eq!(writer.into_inner().0, expected);
+}
+
+#[tokio::test]
+async fn test_rbsp_roundtrip() {
+    let mut writer = BitWriter::endian(RbspWriter::new(Vec::new()), BigEndian);
+    for value in 0..1024u32 {
+        writer.write(value % 13 + 3, value & 0x7).await.unwrap();
+        writer.write(16, 0).await.unwrap();
+    }
+    writer.byte_align().await.unwrap();
+    writer.write(8, 0xFF).await.unwrap();
+    let mut escaped = writer.into_writer();
+    escaped.flush().await.unwrap();
+    let inserted = escaped.inserted();
+    let escaped = escaped.into_inner();
+
+    assert!(inserted > 0);
+    assert!(escaped.windows(3).all(|w| w[0..2] != [0, 0] || w[2] > 0x02));
+
+    let mut reader = BitReader::endian(RbspReader::new(Trickle(Cursor::new(&escaped))), BigEndian);
+    for value in 0..1024u32 {
+        assert_eq!(
+            reader.read::<u32>(value % 13 + 3).await.unwrap(),
+            value & 0x7
+        );
+        assert_eq!(reader.read::<u32>(16).await.unwrap(), 0);
+    }
+    reader.byte_align();
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0xFF);
+    assert_eq!(reader.reader().unwrap().removed(), inserted);
+}
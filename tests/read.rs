@@ -671,3 +671,579 @@ async fn test_read_bytes() {
     let read_data: [u8; 4] = r.read_to_bytes().await.unwrap();
     assert_eq!(actual_data, read_data);
 }
+
+#[tokio::test]
+async fn test_buffered_reader() {
+    use tokio_bitstream_io::huffman::compile_read_tree;
+    use tokio_bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, ByteReader, HuffmanRead};
+
+    let actual_data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    let tree = compile_read_tree(vec![
+        (0, vec![1, 1]),
+        (1, vec![1, 0]),
+        (2, vec![0, 1]),
+        (3, vec![0, 0, 1]),
+        (4, vec![0, 0, 0]),
+    ])
+    .unwrap();
+
+    async fn read_all<R: BitRead + HuffmanRead<BigEndian>>(
+        r: &mut R,
+        tree: &[tokio_bitstream_io::huffman::ReadHuffmanTree<BigEndian, i32>],
+    ) -> Vec<u64> {
+        let mut values = Vec::new();
+        for _ in 0..20 {
+            values.push(r.read::<u64>(3).await.unwrap());
+            values.push(r.read::<u64>(61).await.unwrap());
+            values.push(r.read_signed::<i32>(13).await.unwrap() as u64);
+            r.skip(77).await.unwrap();
+            values.push(u64::from(r.read_unary0().await.unwrap()));
+            values.push(u64::from(r.read_unary1().await.unwrap()));
+            values.push(r.read_huffman(tree).await.unwrap() as u64);
+            r.byte_align();
+            let mut buf = [0; 9];
+            r.read_bytes(&mut buf).await.unwrap();
+            values.extend(buf.iter().map(|b| u64::from(*b)));
+        }
+        values
+    }
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let expected = read_all(&mut r, &tree).await;
+
+    for capacity in [1, 2, 3, 7, 8, 64, 4096] {
+        let mut r: BitReader<_, BigEndian> =
+            BitReader::with_capacity(capacity, Cursor::new(&actual_data));
+        assert_eq!(read_all(&mut r, &tree).await, expected);
+    }
+
+    // read-ahead bytes are handed back rather than lost
+    let mut r: BitReader<_, BigEndian> = BitReader::with_capacity(16, Cursor::new(&actual_data));
+    assert_eq!(r.read::<u32>(12).await.unwrap(), 0x000);
+    assert!(r.reader().is_none());
+    r.byte_align();
+    assert_eq!(r.buffer(), &actual_data[2..16]);
+    assert!(r.reader().is_none());
+    let (mut cursor, rest) = r.into_parts();
+    assert_eq!(rest, &actual_data[2..16]);
+    assert_eq!(cursor.position(), 16);
+    cursor.set_position(3);
+    let mut r = BitReader::endian(cursor, BigEndian);
+    assert_eq!(r.read::<u8>(8).await.unwrap(), 3);
+
+    // and carried over to a ByteReader, which reads them first
+    let mut r: BitReader<_, BigEndian> = BitReader::with_capacity(16, Cursor::new(&actual_data));
+    assert_eq!(r.read::<u8>(8).await.unwrap(), 0);
+    let mut r = r.into_bytereader();
+    assert!(r.reader().is_none());
+    assert_eq!(r.read::<u16>().await.unwrap(), 0x0102);
+    r.skip(12).await.unwrap();
+    assert_eq!(r.read::<u32>().await.unwrap(), 0x0F10_1112);
+    assert_eq!(r.read_to_bytes::<2>().await.unwrap(), [0x13, 0x14]);
+    assert_eq!(r.bytes_read(), 21);
+    let mut r = r.into_bitreader();
+    assert_eq!(r.peek::<u8>(8).await.unwrap(), 0x15);
+    let (cursor, rest) = r.into_bytereader().into_parts();
+    assert_eq!(rest, &actual_data[0x15..0x20]);
+    assert_eq!(cursor.position(), 0x20);
+
+    // into_reader still unwraps the reader, dropping read-ahead bytes
+    let mut r: BitReader<_, BigEndian> = BitReader::with_capacity(16, Cursor::new(&actual_data));
+    assert_eq!(r.read::<u8>(8).await.unwrap(), 0);
+    assert_eq!(r.into_reader().position(), 16);
+    let mut r = ByteReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read::<u8>().await.unwrap(), 0);
+    assert!(r.reader().is_some());
+    assert_eq!(r.into_reader().position(), 1);
+}
+
+#[tokio::test]
+async fn test_peek() {
+    use std::io::ErrorKind;
+    use tokio_bitstream_io::{BigEndian, BitRead, BitReader, Endianness, LittleEndian};
+
+    async fn test_peek_endian<E: Endianness + Clone>(capacity: usize) {
+        let actual_data: [u8; 12] = [
+            0xB1, 0xED, 0x3B, 0xC1, 0x5A, 0x0F, 0x99, 0xE7, 0x01, 0x80, 0x7F, 0xFE,
+        ];
+        for offset in 0..16 {
+            for bits in 1..=64 {
+                let mut r: BitReader<_, E> =
+                    BitReader::with_capacity(capacity, Cursor::new(&actual_data));
+                r.skip(offset).await.unwrap();
+                let mut unpeeked = r.clone();
+                assert_eq!(
+                    r.peek_bit().await.unwrap(),
+                    unpeeked.clone().read_bit().await.unwrap()
+                );
+                let peeked = r.peek::<u64>(bits).await.unwrap();
+                assert_eq!(peeked, unpeeked.read::<u64>(bits).await.unwrap());
+                assert_eq!(r.read::<u64>(bits).await.unwrap(), peeked);
+                assert_eq!(
+                    r.read::<u8>(8).await.unwrap(),
+                    unpeeked.read::<u8>(8).await.unwrap()
+                );
+            }
+        }
+
+        // peeking past the end leaves the stream intact
+        let mut r: BitReader<_, E> = BitReader::with_capacity(capacity, Cursor::new(&actual_data));
+        r.skip(3).await.unwrap();
+        assert_eq!(
+            r.peek::<u128>(94).await.unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+        assert!(r.peek::<u128>(93).await.is_ok());
+        assert!(r.read::<u128>(93).await.is_ok());
+        assert_eq!(r.peek_bit().await.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+        // widths beyond the type are rejected
+        assert_eq!(
+            r.peek::<u8>(9).await.unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    for capacity in [0, 1, 5, 64] {
+        test_peek_endian::<BigEndian>(capacity).await;
+        test_peek_endian::<LittleEndian>(capacity).await;
+    }
+}
+
+#[tokio::test]
+async fn test_exp_golomb_errors() {
+    use std::io::ErrorKind;
+    use tokio_bitstream_io::{BigEndian, BitRead, BitReader};
+
+    // 32 leading zeros is the longest acceptable code
+    let mut data = vec![0u8; 4];
+    data.extend([0x80, 0, 0, 0, 0]);
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_exp_golomb(0).await.unwrap(), (1 << 32) - 1);
+
+    let mut data = vec![0u8; 4];
+    data.extend([0x40, 0, 0, 0, 0]);
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_exp_golomb(0).await.unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_signed_exp_golomb(0).await.unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_exp_golomb(32).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+
+    // bad code parameters report what was wrong with them
+    use tokio_bitstream_io::error::{BitstreamError, BitstreamErrorKind};
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    let kind = |err| BitstreamError::from(err).kind();
+    assert_eq!(
+        kind(r.read_exp_golomb(32).await.unwrap_err()),
+        BitstreamErrorKind::ExcessiveBits
+    );
+    assert_eq!(
+        kind(r.read_rice(64).await.unwrap_err()),
+        BitstreamErrorKind::ExcessiveBits
+    );
+    assert_eq!(
+        kind(r.read_golomb(0).await.unwrap_err()),
+        BitstreamErrorKind::ExcessiveValue
+    );
+
+    // an endless run of zeros fails once the prefix is too long
+    // rather than reading all of it
+    let data = vec![0u8; 1 << 20];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_exp_golomb(0).await.unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.bits_read(), 33);
+    assert!(r.buffer().is_empty());
+}
+
+#[tokio::test]
+async fn test_varint_overflow() {
+    use std::io::ErrorKind;
+    use tokio_bitstream_io::{BigEndian, ByteRead, ByteReader};
+
+    async fn leb128<N: tokio_bitstream_io::Numeric>(data: &[u8]) -> std::io::Result<N> {
+        ByteReader::endian(Cursor::new(data), BigEndian)
+            .read_leb128::<N>()
+            .await
+    }
+    async fn signed_leb128<S: tokio_bitstream_io::SignedNumeric>(
+        data: &[u8],
+    ) -> std::io::Result<S> {
+        ByteReader::endian(Cursor::new(data), BigEndian)
+            .read_signed_leb128::<S>()
+            .await
+    }
+
+    assert_eq!(leb128::<u8>(&[0xFF, 0x01]).await.unwrap(), 255);
+    assert_eq!(
+        leb128::<u8>(&[0x80, 0x02]).await.unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    // redundant padding groups are permitted
+    assert_eq!(leb128::<u8>(&[0x80, 0x80, 0x80, 0x00]).await.unwrap(), 0);
+    assert_eq!(
+        leb128::<u128>(&[0xFF; 18].iter().copied().chain([0x03]).collect::<Vec<u8>>())
+            .await
+            .unwrap(),
+        u128::MAX
+    );
+    assert_eq!(
+        leb128::<u128>(&[0xFF; 18].iter().copied().chain([0x07]).collect::<Vec<u8>>())
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+
+    assert_eq!(signed_leb128::<i8>(&[0xFF, 0x00]).await.unwrap(), 127);
+    assert_eq!(signed_leb128::<i8>(&[0x80, 0x7F]).await.unwrap(), -128);
+    assert_eq!(
+        signed_leb128::<i8>(&[0x80, 0x01]).await.unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(
+        signed_leb128::<i8>(&[0xFF, 0x7E]).await.unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let mut r = ByteReader::endian(Cursor::new(&[0x81, 0x80, 0x00]), BigEndian);
+    assert_eq!(r.read_vlq::<u8>().await.unwrap_err().kind(), ErrorKind::InvalidData);
+    let mut r = ByteReader::endian(Cursor::new(&[0x41, 0x00]), BigEndian);
+    assert_eq!(
+        r.read_quic_varint::<u8>().await.unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    let mut r = ByteReader::endian(Cursor::new(&[0x80, 0x00]), BigEndian);
+    assert_eq!(
+        r.read_quic_varint::<u32>().await.unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[tokio::test]
+async fn test_bits_read() {
+    use tokio_bitstream_io::huffman::compile_read_tree;
+    use tokio_bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, ByteReader, HuffmanRead};
+
+    let data: Vec<u8> = (0..64).collect();
+
+    for capacity in [0, 1, 5, 64].iter().copied() {
+        let mut reader: BitReader<_, BigEndian> =
+            BitReader::with_capacity(capacity, Cursor::new(&data));
+        assert_eq!(reader.bits_read(), 0);
+        reader.read::<u8>(3).await.unwrap();
+        assert_eq!(reader.bits_read(), 3);
+        reader.read_signed::<i32>(20).await.unwrap();
+        assert_eq!(reader.bits_read(), 23);
+        reader.skip(100).await.unwrap();
+        assert_eq!(reader.bits_read(), 123);
+
+        // looking ahead does not move the position
+        reader.peek::<u16>(16).await.unwrap();
+        reader.peek_bit().await.unwrap();
+        assert_eq!(reader.bits_read(), 123);
+
+        reader.byte_align();
+        assert_eq!(reader.bits_read(), 128);
+        let mut buf = [0; 3];
+        reader.read_bytes(&mut buf).await.unwrap();
+        assert_eq!(reader.bits_read(), 152);
+        reader.read_bit().await.unwrap();
+        reader.read_bytes(&mut buf).await.unwrap();
+        assert_eq!(reader.bits_read(), 177);
+        reader.read_unary0().await.unwrap();
+        reader.read_unary1().await.unwrap();
+        let unary = reader.bits_read();
+        assert!(unary > 177);
+
+        let tree = compile_read_tree(vec![
+            (0u8, vec![0]),
+            (1u8, vec![1, 0]),
+            (2u8, vec![1, 1, 0]),
+            (3u8, vec![1, 1, 1]),
+        ])
+        .unwrap();
+        let mut expected = unary;
+        for _ in 0..20 {
+            expected += match reader.read_huffman(&tree).await.unwrap() {
+                0 => 1,
+                1 => 2,
+                _ => 3,
+            };
+            assert_eq!(reader.bits_read(), expected);
+        }
+    }
+
+    // conversions carry the count along
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    reader.read::<u16>(16).await.unwrap();
+    reader.bytereader().unwrap().read::<u32>().await.unwrap();
+    assert_eq!(reader.bits_read(), 48);
+    reader.read::<u8>(4).await.unwrap();
+    assert!(reader.bytereader().is_none());
+    reader.byte_align();
+    let mut bytes = reader.into_bytereader();
+    assert_eq!(bytes.bytes_read(), 7);
+    bytes.skip(3).await.unwrap();
+    bytes.read_to_vec(2).await.unwrap();
+    assert_eq!(bytes.bytes_read(), 12);
+    {
+        let mut bits = bytes.bitreader();
+        bits.read::<u8>(4).await.unwrap();
+        assert_eq!(bits.bits_read(), 100);
+    }
+    // the partially-read byte has been consumed
+    assert_eq!(bytes.bytes_read(), 13);
+    let reader = bytes.into_bitreader();
+    assert_eq!(reader.bits_read(), 104);
+
+    // a fresh reader starts counting at 0, wherever the stream is
+    let mut reader = ByteReader::endian(Cursor::new(&data), BigEndian);
+    reader.reader().unwrap().set_position(10);
+    assert_eq!(reader.bytes_read(), 0);
+    assert_eq!(reader.read::<u8>().await.unwrap(), 10);
+    assert_eq!(reader.bytes_read(), 1);
+}
+
+#[tokio::test]
+async fn test_take() {
+    use tokio_bitstream_io::huffman::compile_read_tree;
+    use tokio_bitstream_io::{BigEndian, BitRead, BitReader, HuffmanRead, LittleEndian};
+
+    let data: Vec<u8> = vec![0xB1, 0xED, 0x3B, 0xC1, 0xFF, 0x00, 0x80, 0xAA];
+
+    for capacity in [0, 1, 3, 64].iter().copied() {
+        // reads within the limit match the unlimited reader,
+        // wherever it starts
+        for start in 0..16 {
+            for limit in 0..24 {
+                let mut expected = BitReader::endian(Cursor::new(&data), BigEndian);
+                expected.skip(start).await.unwrap();
+                let mut reader: BitReader<_, BigEndian> =
+                    BitReader::with_capacity(capacity, Cursor::new(&data));
+                reader.skip(start).await.unwrap();
+                {
+                    let mut take = reader.take(limit);
+                    let mut remaining = limit;
+                    while remaining >= 3 {
+                        assert_eq!(
+                            take.read::<u8>(3).await.unwrap(),
+                            expected.read::<u8>(3).await.unwrap()
+                        );
+                        remaining -= 3;
+                        assert_eq!(take.remaining(), remaining);
+                    }
+                    let err = take.read::<u8>(3).await.unwrap_err();
+                    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+                    assert_eq!(take.remaining(), remaining);
+                    if remaining > 0 {
+                        assert_eq!(
+                            take.peek_bit().await.unwrap(),
+                            expected.peek_bit().await.unwrap()
+                        );
+                    } else {
+                        assert!(take.peek_bit().await.is_err());
+                    }
+                    expected.skip(remaining as u32).await.unwrap();
+                }
+                // dropping skips the rest
+                assert_eq!(reader.bits_read(), u64::from(start) + limit);
+                assert_eq!(
+                    reader.read::<u16>(12).await.unwrap(),
+                    expected.read::<u16>(12).await.unwrap()
+                );
+            }
+        }
+    }
+
+    // skips left over by dropped takes accumulate
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    reader.take(3);
+    reader.take_bytes(1);
+    assert!(!reader.byte_aligned());
+    assert_eq!(reader.bits_read(), 11);
+    reader.byte_align();
+    assert_eq!(reader.bits_read(), 16);
+    assert_eq!(reader.read::<u8>(8).await.unwrap(), 0x3B);
+
+    // finishing reports a skip past the end of the stream
+    let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    let mut take = reader.take_bytes(10);
+    assert_eq!(take.read::<u8>(4).await.unwrap(), 0x1);
+    assert_eq!(take.read_signed::<i8>(4).await.unwrap(), -5);
+    let mut buf = [0; 2];
+    take.read_bytes(&mut buf).await.unwrap();
+    assert_eq!(buf, [0xED, 0x3B]);
+    assert!(take.byte_aligned());
+    assert_eq!(take.remaining(), 56);
+    assert_eq!(
+        take.finish().await.unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+
+    // unary and Huffman codes are not read past the limit
+    let data = [0b1111_1110, 0b1101_1101, 0b0011_1000];
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    {
+        let mut take = reader.take(6);
+        assert!(take.read_unary0().await.is_err());
+    }
+    assert_eq!(reader.read::<u8>(2).await.unwrap(), 0b10);
+    {
+        let mut take = reader.take(4);
+        assert_eq!(take.read_unary1().await.unwrap(), 0);
+        take.byte_align();
+        assert_eq!(take.remaining(), 0);
+    }
+    let tree = compile_read_tree(vec![
+        ('a', vec![0]),
+        ('b', vec![1, 0]),
+        ('c', vec![1, 1, 0]),
+        ('d', vec![1, 1, 1]),
+    ])
+    .unwrap();
+    {
+        let mut take = reader.take(5);
+        assert_eq!(take.read_huffman(&tree).await.unwrap(), 'c');
+        assert_eq!(take.read_huffman(&tree).await.unwrap(), 'b');
+        assert!(take.read_huffman(&tree).await.is_err());
+        assert_eq!(take.remaining(), 0);
+    }
+    assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'a');
+    assert_eq!(reader.read_huffman(&tree).await.unwrap(), 'd');
+}
+
+/// Yields one byte at a time, and only every other time it is polled
+struct Trickle<R> {
+    inner: R,
+    ready: bool,
+}
+
+impl<R: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for Trickle<R> {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        self.ready = !self.ready;
+        if self.ready {
+            let mut byte = [0];
+            let mut one = tokio::io::ReadBuf::new(&mut byte);
+            let result = std::pin::Pin::new(&mut self.inner).poll_read(cx, &mut one);
+            buf.put_slice(one.filled());
+            result
+        } else {
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+}
+
+/// Polls a fresh read once at a time, dropping it
+/// whenever it is pending, until one completes
+macro_rules! retry {
+    ($read:expr) => {
+        loop {
+            if let Some(result) = futures::FutureExt::now_or_never($read) {
+                break result;
+            }
+        }
+    };
+}
+
+#[tokio::test]
+async fn test_cancel_safety() {
+    use tokio_bitstream_io::huffman::compile_read_tree;
+    use tokio_bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter, HuffmanRead};
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    writer.write_bit(true).await.unwrap();
+    writer.write(20, 0xABCDEu32).await.unwrap();
+    writer.write_signed(19, -12345i32).await.unwrap();
+    writer.write(16, 0x1234u16).await.unwrap();
+    writer.write(53, 0u64).await.unwrap();
+    writer.write_unary0(20).await.unwrap();
+    writer.write_unary1(11).await.unwrap();
+    writer.write(30, 0x2AAA_AAAAu32).await.unwrap();
+    writer.write(9, 0x1FFu16).await.unwrap();
+    writer.write(6, 0b110_111u8).await.unwrap();
+    writer.byte_align().await.unwrap();
+    let data = writer.into_writer();
+
+    let tree = compile_read_tree(vec![
+        ('a', vec![0]),
+        ('b', vec![1, 0]),
+        ('c', vec![1, 1, 0]),
+        ('d', vec![1, 1, 1]),
+    ])
+    .unwrap();
+
+    for capacity in [0, 4].iter() {
+        let trickle = Trickle {
+            inner: Cursor::new(&data),
+            ready: false,
+        };
+        let mut reader = BitReader::<_, BigEndian>::with_capacity(*capacity, trickle);
+        assert!(retry!(reader.read_bit()).unwrap());
+        assert_eq!(retry!(reader.read::<u32>(20)).unwrap(), 0xABCDE);
+        assert_eq!(retry!(reader.read_signed::<i32>(19)).unwrap(), -12345);
+        assert_eq!(retry!(reader.peek::<u16>(16)).unwrap(), 0x1234);
+        assert_eq!(retry!(reader.read::<u16>(16)).unwrap(), 0x1234);
+        // cancelled skips are finished by the next read
+        let _ = futures::FutureExt::now_or_never(reader.skip(13));
+        assert!(futures::FutureExt::now_or_never(reader.skip(40)).is_none());
+        assert_eq!(retry!(reader.read_unary0()).unwrap(), 20);
+        assert_eq!(retry!(reader.read_unary1()).unwrap(), 11);
+        {
+            let mut take = reader.take(30);
+            assert_eq!(retry!(take.read::<u8>(3)).unwrap(), 0b101);
+        }
+        // the rest of the take is skipped before this
+        assert_eq!(retry!(reader.read::<u16>(9)).unwrap(), 0x1FF);
+        assert_eq!(retry!(reader.read_huffman(&tree)).unwrap(), 'c');
+        assert_eq!(retry!(reader.read_huffman(&tree)).unwrap(), 'd');
+        assert_eq!(reader.bits_read(), 187);
+
+        // nothing is consumed by a read which reaches the end
+        assert_eq!(
+            retry!(reader.read::<u8>(8)).unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(retry!(reader.read::<u8>(5)).unwrap(), 0);
+        assert_eq!(reader.bits_read(), 192);
+    }
+}
+
+#[tokio::test]
+async fn test_long_unary() {
+    use tokio_bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    // runs longer than the reader will buffer are consumed as they're counted
+    let mut data = vec![0xFF; 10_000];
+    data.extend([0b1110_1111, 0x00, 0x80]);
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_unary0().await.unwrap(), 80_003);
+    assert!(r.buffer().len() <= 4096);
+    assert_eq!(r.read::<u8>(4).await.unwrap(), 0b1111);
+    assert_eq!(r.read_unary1().await.unwrap(), 8);
+
+    let mut data = vec![0x00; 5_000];
+    data.push(0b1000_0000);
+    let mut r: BitReader<_, LittleEndian> = BitReader::with_capacity(16, Cursor::new(&data));
+    assert_eq!(r.read::<u8>(3).await.unwrap(), 0);
+    assert_eq!(r.read_unary1().await.unwrap(), 40_004);
+    assert!(r.byte_aligned());
+}
@@ -135,3 +135,49 @@ pub async fn test_reader_seek_end() -> io::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+pub async fn test_reader_seek_before_start() -> io::Result<()> {
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+
+    assert_eq!(r.read::<u8>(5).await?, 0b10110);
+    for from in [
+        SeekFrom::Current(-6),
+        SeekFrom::End(33),
+        SeekFrom::Current(i64::MIN),
+    ] {
+        assert_eq!(
+            r.seek_bits(from).await.unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        // a failed seek leaves the reader where it was
+        assert_eq!(r.position_in_bits().await?, 5);
+    }
+    assert_eq!(r.read::<u8>(3).await?, 0b001);
+    r.seek_bits(SeekFrom::End(32)).await?;
+    assert_eq!(r.position_in_bits().await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_buffered_reader_seek() -> io::Result<()> {
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+    let mut r: BitReader<_, BigEndian> = BitReader::with_capacity(8, Cursor::new(&actual_data));
+
+    assert_eq!(r.read::<u8>(3).await?, 0b101);
+    assert_eq!(r.position_in_bits().await?, 3);
+    assert_eq!(r.buffer().len(), 3);
+    r.seek_bits(SeekFrom::Current(6)).await?;
+    assert_eq!(r.position_in_bits().await?, 9);
+    assert_eq!(r.read::<u8>(7).await?, 0b110_1101);
+    r.seek_bits(SeekFrom::Start(4)).await?;
+    assert_eq!(r.position_in_bits().await?, 4);
+    assert_eq!(r.read::<u16>(12).await?, 0x1ED);
+    r.seek_bits(SeekFrom::End(4)).await?;
+    assert_eq!(r.read::<u8>(4).await?, 0x1);
+    assert_eq!(r.position_in_bits().await?, 32);
+
+    Ok(())
+}
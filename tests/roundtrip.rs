@@ -102,3 +102,287 @@ macro_rules! define_unary_roundtrip {
 
 define_unary_roundtrip!(test_unary_roundtrip_be, BigEndian);
 define_unary_roundtrip!(test_unary_roundtrip_le, LittleEndian);
+
+macro_rules! define_exp_golomb_roundtrip {
+    ($func_name:ident, $endianness:ident) => {
+        #[tokio::test]
+        async fn $func_name() {
+            use tokio_bitstream_io::{BitCounter, BitRecorder};
+
+            let unsigned: Vec<u64> = (0..1024)
+                .chain([(1 << 32) - 2, (1 << 32) - 1, (1 << 33) - 2])
+                .collect();
+            let signed: Vec<i64> = (-512..512)
+                .chain([(1 << 32) - 1, -((1 << 32) - 1)])
+                .collect();
+
+            for k in [0, 1, 2, 5, 31] {
+                let mut output: Vec<u8> = Vec::new();
+                let mut counter: BitCounter<u64, $endianness> = BitCounter::new();
+                let mut recorder: BitRecorder<u64, $endianness> = BitRecorder::new();
+                {
+                    let mut writer = BitWriter::endian(&mut output, $endianness);
+                    for value in unsigned.iter() {
+                        writer.write_exp_golomb(k, *value).await.unwrap();
+                        counter.write_exp_golomb(k, *value).await.unwrap();
+                        recorder.write_exp_golomb(k, *value).await.unwrap();
+                    }
+                    for value in signed.iter() {
+                        writer.write_signed_exp_golomb(k, *value).await.unwrap();
+                        counter.write_signed_exp_golomb(k, *value).await.unwrap();
+                        recorder.write_signed_exp_golomb(k, *value).await.unwrap();
+                    }
+                    writer.byte_align().await.unwrap();
+                }
+                assert_eq!(counter.written(), recorder.written());
+                assert_eq!(counter.written().div_ceil(8), output.len() as u64);
+                {
+                    let mut played = BitWriter::endian(Vec::new(), $endianness);
+                    recorder.playback(&mut played).await.unwrap();
+                    played.byte_align().await.unwrap();
+                    assert_eq!(played.into_writer(), output);
+                }
+                {
+                    let mut c = Cursor::new(&output);
+                    let mut reader = BitReader::endian(&mut c, $endianness);
+                    for value in unsigned.iter() {
+                        assert_eq!(reader.read_exp_golomb(k).await.unwrap(), *value);
+                    }
+                    for value in signed.iter() {
+                        assert_eq!(reader.read_signed_exp_golomb(k).await.unwrap(), *value);
+                    }
+                }
+            }
+        }
+    };
+}
+
+define_exp_golomb_roundtrip!(test_exp_golomb_roundtrip_be, BigEndian);
+define_exp_golomb_roundtrip!(test_exp_golomb_roundtrip_le, LittleEndian);
+
+macro_rules! define_rice_roundtrip {
+    ($func_name:ident, $endianness:ident) => {
+        #[tokio::test]
+        async fn $func_name() {
+            use tokio_bitstream_io::BitCounter;
+
+            let unsigned: Vec<u64> = (0..300).chain([1 << 20, (1 << 20) + 77]).collect();
+            let signed: Vec<i64> = (-150..150).chain([-(1 << 20), 1 << 20]).collect();
+
+            for k in [0, 1, 4, 15, 63] {
+                let mut output: Vec<u8> = Vec::new();
+                let mut counter: BitCounter<u64, $endianness> = BitCounter::new();
+                {
+                    let mut writer = BitWriter::endian(&mut output, $endianness);
+                    for value in unsigned.iter().filter(|v| (*v >> k) < 4096) {
+                        writer.write_rice(k, *value).await.unwrap();
+                        counter.write_rice(k, *value).await.unwrap();
+                    }
+                    for value in signed.iter().filter(|v| (v.unsigned_abs() >> k) < 2048) {
+                        writer.write_signed_rice(k, *value).await.unwrap();
+                        counter.write_signed_rice(k, *value).await.unwrap();
+                    }
+                    writer.byte_align().await.unwrap();
+                }
+                assert_eq!(counter.written().div_ceil(8), output.len() as u64);
+                {
+                    let mut c = Cursor::new(&output);
+                    let mut reader = BitReader::endian(&mut c, $endianness);
+                    for value in unsigned.iter().filter(|v| (*v >> k) < 4096) {
+                        assert_eq!(reader.read_rice(k).await.unwrap(), *value);
+                    }
+                    for value in signed.iter().filter(|v| (v.unsigned_abs() >> k) < 2048) {
+                        assert_eq!(reader.read_signed_rice(k).await.unwrap(), *value);
+                    }
+                }
+            }
+
+            for m in [1, 2, 3, 5, 7, 10, 64, 100, 1000, u64::MAX] {
+                let mut output: Vec<u8> = Vec::new();
+                let mut counter: BitCounter<u64, $endianness> = BitCounter::new();
+                {
+                    let mut writer = BitWriter::endian(&mut output, $endianness);
+                    for value in unsigned.iter().filter(|v| (*v / m) < 4096) {
+                        writer.write_golomb(m, *value).await.unwrap();
+                        counter.write_golomb(m, *value).await.unwrap();
+                    }
+                    writer.byte_align().await.unwrap();
+                }
+                assert_eq!(counter.written().div_ceil(8), output.len() as u64);
+                {
+                    let mut c = Cursor::new(&output);
+                    let mut reader = BitReader::endian(&mut c, $endianness);
+                    for value in unsigned.iter().filter(|v| (*v / m) < 4096) {
+                        assert_eq!(reader.read_golomb(m).await.unwrap(), *value);
+                    }
+                }
+            }
+        }
+    };
+}
+
+define_rice_roundtrip!(test_rice_roundtrip_be, BigEndian);
+define_rice_roundtrip!(test_rice_roundtrip_le, LittleEndian);
+
+macro_rules! define_varint_roundtrip {
+    ($func_name:ident, $endianness:ident) => {
+        #[tokio::test]
+        async fn $func_name() {
+            use tokio_bitstream_io::{ByteRead, ByteReader, ByteWrite, ByteWriter};
+
+            macro_rules! unsigned_values {
+                ($t:ty) => {
+                    [0, 1, 63, 64, 127, 128, <$t>::MAX / 2, <$t>::MAX - 1, <$t>::MAX]
+                };
+            }
+            macro_rules! signed_values {
+                ($t:ty) => {
+                    [0, 1, -1, 63, -64, 64, -65, <$t>::MIN, <$t>::MIN + 1, <$t>::MAX]
+                };
+            }
+            macro_rules! check {
+                ($t:ty, $values:expr, $write:ident, $read:ident) => {
+                    for values in [$values] {
+                        let mut output = Vec::new();
+                        {
+                            let mut writer = ByteWriter::endian(&mut output, $endianness);
+                            for v in values.iter() {
+                                writer.$write(*v).await.unwrap();
+                            }
+                        }
+                        {
+                            let mut reader = ByteReader::endian(Cursor::new(&output), $endianness);
+                            for v in values.iter() {
+                                assert_eq!(reader.$read::<$t>().await.unwrap(), *v);
+                            }
+                        }
+
+                        // the same codes, but not byte-aligned
+                        let mut output = Vec::new();
+                        {
+                            let mut writer = BitWriter::endian(&mut output, $endianness);
+                            for v in values.iter() {
+                                writer.write_bit(true).await.unwrap();
+                                writer.$write(*v).await.unwrap();
+                            }
+                            writer.byte_align().await.unwrap();
+                        }
+                        {
+                            let mut reader = BitReader::endian(Cursor::new(&output), $endianness);
+                            for v in values.iter() {
+                                assert!(reader.read_bit().await.unwrap());
+                                assert_eq!(reader.$read::<$t>().await.unwrap(), *v);
+                            }
+                        }
+                    }
+                };
+            }
+
+            check!(u8, unsigned_values!(u8), write_leb128, read_leb128);
+            check!(u16, unsigned_values!(u16), write_leb128, read_leb128);
+            check!(u32, unsigned_values!(u32), write_leb128, read_leb128);
+            check!(u64, unsigned_values!(u64), write_leb128, read_leb128);
+            check!(u128, unsigned_values!(u128), write_leb128, read_leb128);
+            check!(i8, signed_values!(i8), write_signed_leb128, read_signed_leb128);
+            check!(i16, signed_values!(i16), write_signed_leb128, read_signed_leb128);
+            check!(i32, signed_values!(i32), write_signed_leb128, read_signed_leb128);
+            check!(i64, signed_values!(i64), write_signed_leb128, read_signed_leb128);
+            check!(i128, signed_values!(i128), write_signed_leb128, read_signed_leb128);
+            check!(u8, unsigned_values!(u8), write_vlq, read_vlq);
+            check!(u32, unsigned_values!(u32), write_vlq, read_vlq);
+            check!(u128, unsigned_values!(u128), write_vlq, read_vlq);
+            check!(u8, unsigned_values!(u8), write_quic_varint, read_quic_varint);
+            check!(u32, unsigned_values!(u32), write_quic_varint, read_quic_varint);
+            check!(
+                u64,
+                [0, 63, 64, 16383, 16384, (1 << 30) - 1, 1 << 30, (1 << 62) - 1],
+                write_quic_varint,
+                read_quic_varint
+            );
+        }
+    };
+}
+
+define_varint_roundtrip!(test_varint_roundtrip_be, BigEndian);
+define_varint_roundtrip!(test_varint_roundtrip_le, LittleEndian);
+
+macro_rules! define_parse_build_roundtrip {
+    ($func_name:ident, $endianness:ident) => {
+        #[tokio::test]
+        async fn $func_name() {
+            use tokio_bitstream_io::{FromBitStreamWith, ToBitStreamWith};
+
+            /// a run of values whose width is given by the context
+            #[derive(Debug, PartialEq, Eq)]
+            struct Samples(Vec<i32>);
+
+            impl FromBitStreamWith<u32> for Samples {
+                type Error = std::io::Error;
+
+                async fn from_reader<R: BitRead + ?Sized>(
+                    r: &mut R,
+                    bits: &u32,
+                ) -> std::io::Result<Self> {
+                    let count = r.read::<u8>(8).await?;
+                    let mut samples = Vec::with_capacity(count.into());
+                    for _ in 0..count {
+                        samples.push(r.read_signed(*bits).await?);
+                    }
+                    Ok(Self(samples))
+                }
+            }
+
+            impl ToBitStreamWith<u32> for Samples {
+                type Error = std::io::Error;
+
+                async fn to_writer<W: BitWrite + ?Sized>(
+                    &self,
+                    w: &mut W,
+                    bits: &u32,
+                ) -> std::io::Result<()> {
+                    w.write(8, self.0.len() as u8).await?;
+                    for sample in self.0.iter() {
+                        w.write_signed(*bits, *sample).await?;
+                    }
+                    Ok(())
+                }
+            }
+
+            let tuple = (
+                true,
+                u8::MAX,
+                i16::MIN,
+                u32::MAX / 3,
+                i64::MIN + 1,
+                u128::MAX - 7,
+                (false, -1i8),
+                [[0x1234u16, 0x5678], [0x9ABC, 0xDEF0]],
+            );
+            let items = vec![(false, 1u8), (true, 2), (false, 3)];
+            let samples = Samples(vec![-4, 3, 0, -1, 2]);
+
+            let mut output = Vec::new();
+            {
+                let mut writer = BitWriter::endian(&mut output, $endianness);
+                writer.build(&tuple).await.unwrap();
+                writer.build(&items).await.unwrap();
+                writer.build_with(&samples, &3).await.unwrap();
+                writer.byte_align().await.unwrap();
+            }
+
+            let mut counter = tokio_bitstream_io::BitCounter::<u32, $endianness>::new();
+            counter.build(&tuple).await.unwrap();
+            counter.build(&items).await.unwrap();
+            counter.build_with(&samples, &3).await.unwrap();
+            assert_eq!(counter.written(), 1 + 8 + 16 + 32 + 64 + 128 + 9 + 64 + 27 + 8 + 15);
+
+            let mut reader = BitReader::endian(Cursor::new(&output), $endianness);
+            assert_eq!(reader.parse::<(bool, u8, i16, u32, i64, u128, (bool, i8), [[u16; 2]; 2])>().await.unwrap(), tuple);
+            assert_eq!(reader.parse_with::<Vec<(bool, u8)>, _>(&3).await.unwrap(), items);
+            assert_eq!(reader.parse_with::<Samples, _>(&3).await.unwrap(), samples);
+        }
+    };
+}
+
+define_parse_build_roundtrip!(test_parse_build_roundtrip_be, BigEndian);
+define_parse_build_roundtrip!(test_parse_build_roundtrip_le, LittleEndian);
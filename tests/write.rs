@@ -1,4 +1,4 @@
-#![allow(clippy::unusual_byte_groupings)]
+#![allow(clippy::unusual_byte_groupings, clippy::legacy_numeric_constants)]
 // Copyright 2017 Brian Langenberger
 //
 // Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
@@ -1720,3 +1720,309 @@ async fn test_recorder_huffman_le() {
     w.playback(&mut w2).await.unwrap();
     assert_eq!(w2.into_writer().as_slice(), &final_data);
 }
+
+#[tokio::test]
+async fn test_exp_golomb_errors() {
+    use std::io::ErrorKind;
+    use tokio_bitstream_io::{BigEndian, BitCounter, BitWrite, BitWriter};
+
+    let mut w = BitWriter::endian(tokio::io::sink(), BigEndian);
+    assert!(w.write_exp_golomb(0, (1 << 33) - 2).await.is_ok());
+    assert_eq!(
+        w.write_exp_golomb(0, (1 << 33) - 1).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        w.write_exp_golomb(32, 0).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+
+    // bad code parameters report what was wrong with them
+    use tokio_bitstream_io::error::{BitstreamError, BitstreamErrorKind};
+    let kind = |err| BitstreamError::from(err).kind();
+    assert_eq!(
+        kind(w.write_exp_golomb(32, 0).await.unwrap_err()),
+        BitstreamErrorKind::ExcessiveBits
+    );
+    assert_eq!(
+        kind(w.write_rice(64, 0).await.unwrap_err()),
+        BitstreamErrorKind::ExcessiveBits
+    );
+    assert_eq!(
+        kind(w.write_golomb(0, 0).await.unwrap_err()),
+        BitstreamErrorKind::ExcessiveValue
+    );
+    assert_eq!(
+        w.write_signed_exp_golomb(0, i64::MIN).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        w.write_signed_exp_golomb(31, i64::MAX).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+
+    let mut c: BitCounter<u32, BigEndian> = BitCounter::new();
+    assert_eq!(
+        c.write_signed_exp_golomb(0, i64::MIN).await.unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(c.written(), 0);
+}
+
+#[tokio::test]
+async fn test_rice_counter() {
+    use tokio_bitstream_io::{BigEndian, BitCounter, BitWrite};
+
+    // choosing the best Rice parameter by trial encoding
+    let residuals: [i64; 8] = [3, -7, 12, 0, -1, 25, -30, 4];
+    let mut sizes = Vec::new();
+    for k in 0..6 {
+        let mut c: BitCounter<u32, BigEndian> = BitCounter::new();
+        for r in residuals.iter() {
+            c.write_signed_rice(k, *r).await.unwrap();
+        }
+        sizes.push(c.written());
+    }
+    assert_eq!(sizes, [169, 95, 62, 50, 47, 50]);
+
+    let mut c: BitCounter<u32, BigEndian> = BitCounter::new();
+    c.write_golomb(5, 0).await.unwrap();
+    c.write_golomb(5, 4).await.unwrap();
+    c.write_golomb(5, 6).await.unwrap();
+    assert_eq!(c.written(), 11);
+}
+
+#[tokio::test]
+async fn test_bits_written() {
+    use tokio_bitstream_io::huffman::compile_write_tree;
+    use tokio_bitstream_io::{BigEndian, BitWrite, BitWriter, ByteWrite, HuffmanWrite};
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    assert_eq!(writer.bits_written(), 0);
+    writer.write(3, 0b101u8).await.unwrap();
+    assert_eq!(writer.bits_written(), 3);
+    writer.write_signed(20, -5i32).await.unwrap();
+    assert_eq!(writer.bits_written(), 23);
+    writer.write_bit(true).await.unwrap();
+    writer.write(64, u64::MAX).await.unwrap();
+    assert_eq!(writer.bits_written(), 88);
+    writer.write_bytes(b"abc").await.unwrap();
+    assert_eq!(writer.bits_written(), 112);
+    writer.write_unary0(5).await.unwrap();
+    writer.write_unary1(10).await.unwrap();
+    assert_eq!(writer.bits_written(), 129);
+
+    let tree = compile_write_tree(vec![
+        ('a', vec![0]),
+        ('b', vec![1, 0]),
+        ('c', vec![1, 1, 0]),
+        ('d', vec![1, 1, 1]),
+    ])
+    .unwrap();
+    for symbol in "abcdabcd".chars() {
+        writer.write_huffman(&tree, symbol).await.unwrap();
+    }
+    assert_eq!(writer.bits_written(), 129 + 18);
+
+    writer.byte_align().await.unwrap();
+    assert_eq!(writer.bits_written(), 152);
+    writer.bytewriter().unwrap().write(0u32).await.unwrap();
+    assert_eq!(writer.bits_written(), 184);
+
+    let mut bytes = writer.into_bytewriter();
+    assert_eq!(bytes.bytes_written(), 23);
+    bytes.write_bytes(&[1, 2]).await.unwrap();
+    {
+        let mut bits = bytes.bitwriter();
+        bits.write(12, 0xFFFu16).await.unwrap();
+        assert_eq!(bits.bits_written(), 212);
+    }
+    // the unwritten partial byte is lost
+    assert_eq!(bytes.bytes_written(), 26);
+    let writer = bytes.into_bitwriter();
+    assert_eq!(writer.bits_written(), 208);
+    assert_eq!(writer.into_writer().len(), 26);
+}
+
+#[tokio::test]
+async fn test_placeholders() {
+    use tokio_bitstream_io::{BigEndian, BitWrite, BitWriter, Endianness, LittleEndian};
+
+    async fn check<E: Endianness + Copy>(endianness: E) {
+        // each field is written either directly or via a placeholder
+        // patched afterwards in a scrambled order
+        let fields: Vec<(u32, u32)> = (0..40u32)
+            .map(|i| (i % 23 + 1, i.wrapping_mul(0x9E37_79B9) & ((1 << (i % 23 + 1)) - 1)))
+            .collect();
+
+        let mut expected = BitWriter::endian(Vec::new(), endianness);
+        for (bits, value) in fields.iter() {
+            expected.write(*bits, *value).await.unwrap();
+        }
+        expected.byte_align().await.unwrap();
+        let expected = expected.into_writer();
+
+        let mut writer = BitWriter::endian(Vec::new(), endianness);
+        let mut placeholders = Vec::new();
+        for (i, (bits, value)) in fields.iter().enumerate() {
+            if i % 3 == 1 {
+                placeholders.push((writer.reserve(*bits).await.unwrap(), *value));
+            } else {
+                writer.write(*bits, *value).await.unwrap();
+            }
+            if i == 12 {
+                // patching everything so far lets the held bytes through
+                for (placeholder, value) in placeholders.drain(..) {
+                    writer.patch(&placeholder, value).await.unwrap();
+                }
+                assert!(!writer.byte_aligned() || writer.writer().is_some());
+            }
+        }
+        writer.byte_align().await.unwrap();
+        assert!(writer.writer().is_none());
+        let bits_written = writer.bits_written();
+
+        let mut order: Vec<usize> = (0..placeholders.len()).collect();
+        order.sort_by_key(|i| (i * 7) % placeholders.len());
+        for i in order {
+            let (placeholder, value) = &placeholders[i];
+            writer.patch(placeholder, *value).await.unwrap();
+        }
+        assert!(writer.writer().is_some());
+        assert_eq!(writer.bits_written(), bits_written);
+        assert_eq!(writer.into_writer(), expected);
+    }
+
+    check(BigEndian).await;
+    check(LittleEndian).await;
+
+    // values which don't fit leave the placeholder outstanding
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    let placeholder = writer.reserve(4).await.unwrap();
+    assert_eq!(placeholder.bits(), 4);
+    assert_eq!(placeholder.position(), 0);
+    writer.write(4, 0b1111u8).await.unwrap();
+    assert!(writer.patch(&placeholder, 16u8).await.is_err());
+    assert!(writer.patch(&placeholder, 0u64).await.is_ok());
+    assert!(writer.patch(&placeholder, 0u64).await.is_err());
+    assert_eq!(writer.into_writer(), [0b0000_1111]);
+
+    // patching a placeholder still in the partial byte
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    writer.write_bit(true).await.unwrap();
+    let placeholder = writer.reserve(3).await.unwrap();
+    writer.patch(&placeholder, 0b101u8).await.unwrap();
+    writer.write(4, 0b1000u8).await.unwrap();
+    assert_eq!(writer.into_writer(), [0b1000_1011]);
+
+    // placeholders at the same spot in another writer are rejected
+    let mut first = BitWriter::endian(Vec::new(), BigEndian);
+    let mut second = BitWriter::endian(Vec::new(), BigEndian);
+    let from_first = first.reserve(8).await.unwrap();
+    let from_second = second.reserve(8).await.unwrap();
+    assert!(first.patch(&from_second, 1u8).await.is_err());
+    assert!(second.patch(&from_first, 2u8).await.is_err());
+    first.patch(&from_first, 1u8).await.unwrap();
+    second.patch(&from_second, 2u8).await.unwrap();
+    assert_eq!(first.into_writer(), [1]);
+    assert_eq!(second.into_writer(), [2]);
+}
+
+#[tokio::test]
+async fn test_seek_bits() {
+    use std::io::{Cursor, SeekFrom};
+    use tokio_bitstream_io::{BigEndian, BitWrite, BitWriter, Endianness, LittleEndian};
+
+    // writes values bit by bit into a model of the stream
+    fn model_write(model: &mut Vec<bool>, position: usize, bits: u32, value: u32, big: bool) {
+        for i in 0..bits {
+            let bit = if big {
+                (value >> (bits - 1 - i)) & 1 == 1
+            } else {
+                (value >> i) & 1 == 1
+            };
+            let index = position + i as usize;
+            if index >= model.len() {
+                model.resize(index + 1, false);
+            }
+            model[index] = bit;
+        }
+    }
+
+    fn model_bytes(model: &[bool], big: bool) -> Vec<u8> {
+        model
+            .chunks(8)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0, |byte, (i, bit)| {
+                    let shift = if big { 7 - i } else { i };
+                    byte | (u8::from(*bit) << shift)
+                })
+            })
+            .collect()
+    }
+
+    async fn check<E: Endianness + Copy>(endianness: E, big: bool) {
+        let initial = vec![0xA5; 6];
+        // 0xA5 is the same in either bit order
+        let mut model: Vec<bool> = (0..initial.len() * 8)
+            .map(|i| (0xA5u8 >> (i % 8)) & 1 == 1)
+            .collect();
+        assert_eq!(model_bytes(&model, big), initial);
+
+        let mut writer = BitWriter::endian(Cursor::new(initial), endianness);
+        let mut position = 0;
+        for step in 0..200u32 {
+            let bits = step % 11 + 1;
+            let value = step.wrapping_mul(0x9E37_79B9) & ((1 << bits) - 1);
+            let target = match step % 4 {
+                0 => SeekFrom::Start(u64::from(step * 7 % 60)),
+                1 => SeekFrom::Current(-(i64::from(step % 9))),
+                2 => SeekFrom::End(i64::from(step % 5)),
+                _ => SeekFrom::Current(i64::from(step % 3)),
+            };
+            let expected = match target {
+                SeekFrom::Start(p) => p as i64,
+                SeekFrom::Current(o) => position as i64 + o,
+                SeekFrom::End(o) => (model.len().div_ceil(8) * 8) as i64 - o,
+            };
+            if expected < 0 {
+                continue;
+            }
+            position = writer.seek_bits(target).await.unwrap() as usize;
+            assert_eq!(position as i64, expected);
+            assert_eq!(writer.position_in_bits().await.unwrap(), position as u64);
+            if position > model.len() {
+                model.resize(position, false);
+            }
+            writer.write(bits, value).await.unwrap();
+            model_write(&mut model, position, bits, value, big);
+            position += bits as usize;
+            assert_eq!(writer.position_in_bits().await.unwrap(), position as u64);
+        }
+        writer.seek_bits(SeekFrom::Start(0)).await.unwrap();
+        assert_eq!(writer.into_writer().into_inner(), model_bytes(&model, big));
+    }
+
+    check(BigEndian, true).await;
+    check(LittleEndian, false).await;
+
+    // seeking is refused while placeholders are outstanding
+    let mut writer = BitWriter::endian(Cursor::new(Vec::new()), BigEndian);
+    writer.write(4, 0b1111u8).await.unwrap();
+    let placeholder = writer.reserve(8).await.unwrap();
+    assert_eq!(writer.position_in_bits().await.unwrap(), 12);
+    assert!(writer.seek_bits(SeekFrom::Start(0)).await.is_err());
+    writer.patch(&placeholder, 0xFFu8).await.unwrap();
+    assert_eq!(writer.seek_bits(SeekFrom::Start(2)).await.unwrap(), 2);
+    writer.write(2, 0b00u8).await.unwrap();
+    writer.seek_bits(SeekFrom::Current(0)).await.unwrap();
+    assert_eq!(writer.into_writer().into_inner(), [0b1100_1111, 0b1111_0000]);
+
+    // seeking before the start of the stream is an error, not a panic
+    let mut writer = BitWriter::endian(Cursor::new(vec![0xFF]), BigEndian);
+    let err = writer.seek_bits(SeekFrom::Current(-1)).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let err = writer.seek_bits(SeekFrom::End(9)).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(writer.seek_bits(SeekFrom::End(8)).await.unwrap(), 0);
+}